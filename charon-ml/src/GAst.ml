@@ -28,6 +28,7 @@ type 'body gfun_decl = {
   kind : item_kind;
   is_global_initializer : GlobalDeclId.id option;
   body : 'body gexpr_body option;
+  calls : FunDeclId.id list;
 }
 [@@deriving show]
 
@@ -42,6 +42,7 @@ and gfun_decl_of_json
           ("kind", kind);
           ("is_global_initializer", is_global_initializer);
           ("body", body);
+          ("calls", calls);
         ] ->
         let* def_id = FunDeclId.id_of_json ctx def_id in
         let* item_meta = item_meta_of_json ctx item_meta in
@@ -51,7 +52,17 @@ and gfun_decl_of_json
           option_of_json global_decl_id_of_json ctx is_global_initializer
         in
         let* body = maybe_opaque_body_of_json body_of_json ctx body in
-        Ok { def_id; item_meta; signature; kind; is_global_initializer; body }
+        let* calls = list_of_json fun_decl_id_of_json ctx calls in
+        Ok
+          {
+            def_id;
+            item_meta;
+            signature;
+            kind;
+            is_global_initializer;
+            body;
+            calls;
+          }
     | _ -> Error "")
 
 (** Deserialize a map from file id to file name.
@@ -166,7 +177,15 @@ and gcrate_of_json
     (body_of_json : of_json_ctx -> json -> ('body gexpr_body, string) result)
     (js : json) : ('body gcrate, string) result =
   match js with
-  | `Assoc [ ("charon_version", charon_version); ("translated", translated) ] ->
+  | `Assoc
+      [
+        ("charon_version", charon_version);
+        ("charon_commit", _);
+        ("rustc_version", _);
+        ("translated", translated);
+        ("external_crates", _);
+        ("external_opaque_items", _);
+      ] ->
       (* Ensure the version is the one we support. *)
       let* charon_version = string_of_json () charon_version in
       if
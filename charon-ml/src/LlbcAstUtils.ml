@@ -25,6 +25,7 @@ let statement_has_loops (st : statement) : bool =
     object
       inherit [_] iter_statement
       method! visit_Loop _ _ = raise Found
+      method! visit_ForLoop _ _ _ _ = raise Found
     end
   in
   try
@@ -56,6 +57,9 @@ let rec chain_statements (st1 : statement) (st2 : statement) : statement =
   | FakeRead _
   | Drop _
   | Loop _
+  | LetElse _
+  | ForLoop _
+  | IfLet _
   | Error _ ->
       (* Simply create a sequence *)
       mk_sequence st1 st2
@@ -158,7 +162,15 @@ class ['self] iter_crate_with_span =
 
     method visit_fun_decl (_ : (any_decl_id * span) option) (decl : fun_decl)
         : unit =
-      let { def_id; item_meta; signature; kind; is_global_initializer; body } =
+      let {
+        def_id;
+        item_meta;
+        signature;
+        kind;
+        is_global_initializer;
+        body;
+        calls = _;
+      } =
         decl
       in
       let decl_span_info = Some (IdFun def_id, item_meta.span) in
@@ -499,6 +499,13 @@ and match_pattern_with_type_id (ctx : ctx) (c : match_config) (m : maps)
               PIdent ("boxed", []);
               PIdent ("Box", pgenerics);
             ] ) ) -> match_generic_args ctx c m pgenerics generics
+      | ( TRc,
+          [ PIdent ("alloc", []); PIdent ("rc", []); PIdent ("Rc", pgenerics) ]
+        )
+      | ( TArc,
+          [
+            PIdent ("alloc", []); PIdent ("sync", []); PIdent ("Arc", pgenerics);
+          ] ) -> match_generic_args ctx c m pgenerics generics
       | TStr, [ PIdent ("str", []) ] -> generics = TypesUtils.empty_generic_args
       | _ -> false)
 
@@ -662,6 +669,10 @@ and match_expr_with_const_generic (ctx : ctx) (c : match_config) (m : maps)
 let builtin_fun_id_to_string (fid : E.builtin_fun_id) : string =
   match fid with
   | BoxNew -> "alloc::boxed::{Box<@T, alloc::alloc::Global>}::new"
+  | RcNew -> "RcNew"
+  | ArcNew -> "ArcNew"
+  | RcClone -> "RcClone"
+  | ArcClone -> "ArcClone"
   | ArrayToSliceShared -> "ArrayToSliceShared"
   | ArrayToSliceMut -> "ArrayToSliceMut"
   | ArrayRepeat -> "ArrayRepeat"
@@ -670,6 +681,7 @@ let builtin_fun_id_to_string (fid : E.builtin_fun_id) : string =
       let op = if is_range then "SubSlice" else "Index" in
       let mutability = PrintTypes.ref_kind_to_string mutability in
       ty ^ op ^ mutability
+  | PrimitiveMethod m -> PrintExpressions.primitive_method_to_string m
 
 let match_fn_ptr (ctx : ctx) (c : match_config) (p : pattern) (func : E.fn_ptr)
     : bool =
@@ -916,6 +928,8 @@ and path_elem_with_generic_args_to_pattern (ctx : ctx) (c : to_pat_config)
       | None -> PIdent (s, [])
       | Some args -> PIdent (s, args))
   | PeImpl (impl, _) -> impl_elem_to_pattern ctx c impl
+  | PeMonomorphized _ ->
+      raise (Failure "Monomorphized instances are not valid in patterns")
 
 and impl_elem_to_pattern (ctx : ctx) (c : to_pat_config) (impl : T.impl_elem) :
     pattern_elem =
@@ -954,6 +968,14 @@ and ty_to_pattern_aux (ctx : ctx) (c : to_pat_config) (m : constraints)
       | TBuiltin TArray -> EPrimAdt (TArray, generics)
       | TBuiltin TSlice -> EPrimAdt (TSlice, generics)
       | TBuiltin TBox -> EComp [ PIdent ("Box", generics) ]
+      | TBuiltin TRc ->
+          EComp
+            [ PIdent ("alloc", []); PIdent ("rc", []); PIdent ("Rc", generics) ]
+      | TBuiltin TArc ->
+          EComp
+            [
+              PIdent ("alloc", []); PIdent ("sync", []); PIdent ("Arc", generics);
+            ]
       | TBuiltin TStr -> EComp [ PIdent ("str", generics) ])
   | TVar v -> EVar (type_var_to_pattern m v)
   | TLiteral lit -> literal_type_to_pattern c lit
@@ -1026,6 +1048,7 @@ and const_generic_to_pattern (ctx : ctx) (c : to_pat_config) (m : constraints)
       let d = T.GlobalDeclId.Map.find gid ctx.crate.global_decls in
       let n = name_to_pattern_aux ctx c d.item_meta.name in
       GExpr (EComp n)
+  | CgApp _ -> raise (Failure "Unimplemented: const generic expression")
 
 and generic_args_to_pattern (ctx : ctx) (c : to_pat_config) (m : constraints)
     (generics : T.generic_args) : generic_args =
@@ -69,31 +69,29 @@ let unop_to_string (env : 'a fmt_env) (unop : unop) : string =
   | Neg -> "-"
   | Cast cast_kind -> cast_kind_to_string env cast_kind
 
-let binop_to_string (binop : binop) : string =
-  match binop with
-  | BitXor -> "^"
-  | BitAnd -> "&"
-  | BitOr -> "|"
-  | Eq -> "=="
-  | Lt -> "<"
-  | Le -> "<="
-  | Ne -> "!="
-  | Ge -> ">="
-  | Gt -> ">"
-  | Div -> "/"
-  | Rem -> "%"
-  | Add -> "+"
-  | Sub -> "-"
-  | Mul -> "*"
-  | CheckedAdd -> "checked.+"
-  | CheckedSub -> "checked.-"
-  | CheckedMul -> "checked.*"
-  | Shl -> "<<"
-  | Shr -> ">>"
+let primitive_method_to_string (m : primitive_method) : string =
+  match m with
+  | CountOnes -> "CountOnes"
+  | CountZeros -> "CountZeros"
+  | LeadingZeros -> "LeadingZeros"
+  | TrailingZeros -> "TrailingZeros"
+  | RotateLeft -> "RotateLeft"
+  | RotateRight -> "RotateRight"
+  | SwapBytes -> "SwapBytes"
+  | ReverseBits -> "ReverseBits"
+  | Pow -> "Pow"
+  | Abs -> "Abs"
+  | Min -> "Min"
+  | Max -> "Max"
+  | Sqrt -> "Sqrt"
 
 let builtin_fun_id_to_string (aid : builtin_fun_id) : string =
   match aid with
   | BoxNew -> "alloc::boxed::Box::new"
+  | RcNew -> "alloc::rc::Rc::new"
+  | ArcNew -> "alloc::sync::Arc::new"
+  | RcClone -> "alloc::rc::Rc::clone"
+  | ArcClone -> "alloc::sync::Arc::clone"
   | ArrayToSliceShared -> "@ArrayToSliceShared"
   | ArrayToSliceMut -> "@ArrayToSliceMut"
   | ArrayRepeat -> "@ArrayRepeat"
@@ -102,6 +100,7 @@ let builtin_fun_id_to_string (aid : builtin_fun_id) : string =
       let op = if is_range then "SubSlice" else "Index" in
       let mutability = ref_kind_to_string mutability in
       "@" ^ ty ^ op ^ mutability
+  | PrimitiveMethod m -> "@" ^ primitive_method_to_string m
 
 let fun_id_to_string (env : 'a fmt_env) (fid : fun_id) : string =
   match fid with
@@ -174,6 +173,7 @@ let rvalue_to_string (env : 'a fmt_env) (rv : rvalue) : string =
           (ty_to_string env ty
           :: List.map (const_generic_to_string env) const_generics)
       ^ ">(" ^ place_to_string env place ^ ")"
+  | BlackBox op -> "black_box(" ^ operand_to_string env op ^ ")"
   | Global global_ref ->
       let generics = generic_args_to_string env global_ref.global_generics in
       "global " ^ global_decl_id_to_string env global_ref.global_id ^ generics
@@ -18,6 +18,7 @@ let any_decl_id_to_string (id : any_decl_id) : string =
 let fn_operand_to_string (env : 'a fmt_env) (op : fn_operand) : string =
   match op with
   | FnOpRegular func -> fn_ptr_to_string env func
+  | FnOpDynamic func -> "dyn " ^ fn_ptr_to_string env func
   | FnOpMove p -> "move " ^ place_to_string env p
 
 let call_to_string (env : 'a fmt_env) (indent : string) (call : call) : string =
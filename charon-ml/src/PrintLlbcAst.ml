@@ -108,6 +108,40 @@ module Ast = struct
         indent ^ "loop {\n"
         ^ statement_to_string env (indent ^ indent_incr) indent_incr loop_st
         ^ "\n" ^ indent ^ "}"
+    | LetElse (p, variant_id, bindings, else_st) ->
+        let indent1 = indent ^ indent_incr in
+        indent ^ "let " ^ VariantId.to_string variant_id ^ " = "
+        ^ place_to_string env p ^ " {\n"
+        ^ statement_to_string env indent1 indent_incr bindings
+        ^ "\n" ^ indent ^ "}\n" ^ indent ^ "else {\n"
+        ^ statement_to_string env indent1 indent_incr else_st
+        ^ "\n" ^ indent ^ "}"
+    | ForLoop (iter, item, body) ->
+        indent ^ "for " ^ place_to_string env item ^ " in "
+        ^ place_to_string env iter ^ " {\n"
+        ^ statement_to_string env (indent ^ indent_incr) indent_incr body
+        ^ "\n" ^ indent ^ "}"
+    | IfLet (conditions, then_st) ->
+        let indent1 = indent ^ indent_incr in
+        let conditions =
+          List.mapi
+            (fun i (cond : let_condition) ->
+              let prefix = if i = 0 then "if" else "&&" in
+              indent ^ prefix ^ " let "
+              ^ VariantId.to_string cond.variant
+              ^ " = "
+              ^ place_to_string env cond.scrutinee
+              ^ " {\n"
+              ^ statement_to_string env indent1 indent_incr cond.bindings
+              ^ "\n" ^ indent ^ "}\n" ^ indent ^ "else {\n"
+              ^ statement_to_string env indent1 indent_incr cond.else_block
+              ^ "\n" ^ indent ^ "}")
+            conditions
+        in
+        String.concat "\n" conditions
+        ^ "\n" ^ indent ^ "then {\n"
+        ^ statement_to_string env indent1 indent_incr then_st
+        ^ "\n" ^ indent ^ "}"
     | Error s -> indent ^ "ERROR(' " ^ s ^ "')"
 
   let fun_sig_to_string (env : fmt_env) (indent : string) (indent_incr : string)
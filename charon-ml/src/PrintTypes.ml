@@ -164,6 +164,28 @@ let region_binder_to_string (value_to_string : 'a fmt_env -> 'c -> string)
       ^ String.concat "," (List.map region_var_to_string rb.binder_regions)
       ^ "> " ^ value
 
+let binop_to_string (binop : binop) : string =
+  match binop with
+  | BitXor -> "^"
+  | BitAnd -> "&"
+  | BitOr -> "|"
+  | Eq -> "=="
+  | Lt -> "<"
+  | Le -> "<="
+  | Ne -> "!="
+  | Ge -> ">="
+  | Gt -> ">"
+  | Div -> "/"
+  | Rem -> "%"
+  | Add -> "+"
+  | Sub -> "-"
+  | Mul -> "*"
+  | CheckedAdd -> "checked.+"
+  | CheckedSub -> "checked.-"
+  | CheckedMul -> "checked.*"
+  | Shl -> "<<"
+  | Shr -> ">>"
+
 let rec type_id_to_string (env : 'a fmt_env) (id : type_id) : string =
   match id with
   | TAdtId id -> type_decl_id_to_string env id
@@ -171,6 +193,8 @@ let rec type_id_to_string (env : 'a fmt_env) (id : type_id) : string =
   | TBuiltin aty -> (
       match aty with
       | TBox -> "alloc::boxed::Box"
+      | TRc -> "alloc::rc::Rc"
+      | TArc -> "alloc::sync::Arc"
       | TStr -> "str"
       | TArray -> "@Array"
       | TSlice -> "@Slice")
@@ -212,6 +236,18 @@ and const_generic_to_string (env : 'a fmt_env) (cg : const_generic) : string =
   | CgGlobal id -> global_decl_id_to_string env id
   | CgVar var -> const_generic_db_var_to_string env var
   | CgValue lit -> literal_to_string lit
+  | CgApp (op, args) -> (
+      match (op, args) with
+      | CgBinOp binop, [ x; y ] ->
+          "(" ^ const_generic_to_string env x ^ " " ^ binop_to_string binop ^ " "
+          ^ const_generic_to_string env y
+          ^ ")"
+      | CgNeg, [ x ] -> "(-" ^ const_generic_to_string env x ^ ")"
+      | CgNot, [ x ] -> "(!" ^ const_generic_to_string env x ^ ")"
+      | _ ->
+          show_const_generic_op op ^ "("
+          ^ String.concat ", " (List.map (const_generic_to_string env) args)
+          ^ ")")
 
 and ty_to_string (env : 'a fmt_env) (ty : ty) : string =
   match ty with
@@ -349,6 +385,7 @@ and path_elem_to_string (env : 'a fmt_env) (e : path_elem) : string =
         if d = Disambiguator.zero then "" else "#" ^ Disambiguator.to_string d
       in
       "{" ^ impl_elem_to_string env impl ^ "}" ^ d
+  | PeMonomorphized args -> generic_args_to_string env args
 
 and name_to_string (env : 'a fmt_env) (n : name) : string =
   let name = List.map (path_elem_to_string env) n in
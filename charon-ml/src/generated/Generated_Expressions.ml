@@ -109,34 +109,6 @@ and cast_kind =
           [`std::mem::transmute`] does.
        *)
 
-(** Binary operations. *)
-and binop =
-  | BitXor
-  | BitAnd
-  | BitOr
-  | Eq
-  | Lt
-  | Le
-  | Ne
-  | Ge
-  | Gt
-  | Div
-      (** Fails if the divisor is 0, or if the operation is `int::MIN / -1`. *)
-  | Rem
-      (** Fails if the divisor is 0, or if the operation is `int::MIN % -1`. *)
-  | Add  (** Fails on overflow. *)
-  | Sub  (** Fails on overflow. *)
-  | Mul  (** Fails on overflow. *)
-  | CheckedAdd
-      (** Returns `(result, did_overflow)`, where `result` is the result of the operation with
-          wrapping semantics, and `did_overflow` is a boolean that indicates whether the operation
-          overflowed. This operation does not fail.
-       *)
-  | CheckedSub  (** Like `CheckedAdd`. *)
-  | CheckedMul  (** Like `CheckedAdd`. *)
-  | Shl  (** Fails if the shift is bigger than the bit-size of the type. *)
-  | Shr  (** Fails if the shift is bigger than the bit-size of the type. *)
-
 and operand =
   | Copy of place
   | Move of place
@@ -160,6 +132,10 @@ and fun_id =
  *)
 and builtin_fun_id =
   | BoxNew  (** `alloc::boxed::Box::new` *)
+  | RcNew  (** `alloc::rc::Rc::new` *)
+  | ArcNew  (** `alloc::sync::Arc::new` *)
+  | RcClone  (** `<alloc::rc::Rc<T> as Clone>::clone` *)
+  | ArcClone  (** `<alloc::sync::Arc<T> as Clone>::clone` *)
   | ArrayToSliceShared
       (** Cast an array as a slice.
 
@@ -184,6 +160,11 @@ and builtin_fun_id =
           - `fn SliceSubSliceMut<T>(&mut [T], usize, usize) -> &mut [T]`
           - etc
        *)
+  | PrimitiveMethod of primitive_method
+      (** A method of the primitive integer or float types, e.g. `u32::count_ones`. The function
+          takes a single type argument, the type of `self`; its signature is the one of the original
+          method.
+       *)
 
 (** One of 8 built-in indexing operations. *)
 and builtin_index_op = {
@@ -199,6 +180,26 @@ and builtin_index_op = {
      *)
 }
 
+(** The methods of the primitive numeric types that we treat as built-in, instead of calling the
+    functions of the standard library.
+ *)
+and primitive_method =
+  | CountOnes
+      (** `count_ones`: the number of ones in the binary representation of an integer. *)
+  | CountZeros
+      (** `count_zeros`: the number of zeros in the binary representation of an integer. *)
+  | LeadingZeros  (** `leading_zeros` *)
+  | TrailingZeros  (** `trailing_zeros` *)
+  | RotateLeft  (** `rotate_left` *)
+  | RotateRight  (** `rotate_right` *)
+  | SwapBytes  (** `swap_bytes` *)
+  | ReverseBits  (** `reverse_bits` *)
+  | Pow  (** `pow`: exponentiation by a `u32`. *)
+  | Abs  (** `abs`, for the signed integers and the floats. *)
+  | Min  (** `min`, for the floats. *)
+  | Max  (** `max`, for the floats. *)
+  | Sqrt  (** `sqrt`, for the floats. *)
+
 and fun_id_or_trait_method_ref =
   | FunId of fun_id
   | TraitMethod of trait_ref * trait_item_name * fun_decl_id
@@ -337,6 +338,11 @@ and rvalue =
           rustc introduces a check that the length of the slice is exactly equal
           to 1 and that we preserve.
        *)
+  | BlackBox of operand
+      (** A call to `core::hint::black_box`: this returns its operand unchanged but acts as an
+          optimization barrier.
+          Not present in MIR; introduced in [reconstruct_black_box.rs].
+       *)
 
 (** An aggregated ADT.
 
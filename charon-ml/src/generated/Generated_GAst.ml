@@ -95,12 +95,17 @@ and item_kind =
        *)
 
 (** A function operand is used in function calls.
-    It either designates a top-level function, or a place in case
-    we are using function pointers stored in local variables.
+    It either designates a top-level function, a method called through a vtable, or a place in
+    case we are using function pointers stored in local variables.
  *)
 and fn_operand =
   | FnOpRegular of fn_ptr
       (** Regular case: call to a top-level function, trait method, etc. *)
+  | FnOpDynamic of fn_ptr
+      (** Dynamic dispatch: call to a method of a trait object, which is looked up in the vtable of
+          the receiver (the first argument of the call). The function pointer refers to the trait
+          method, with a [TraitRefKind::Dyn] trait reference.
+       *)
   | FnOpMove of place
       (** Use of a function pointer stored in a local variable *)
 
@@ -111,7 +116,11 @@ and call = { func : fn_operand; args : operand list; dest : place }
     instance. We eliminate the assertions in [crate::remove_dynamic_checks],
     then introduce other dynamic checks in [crate::reconstruct_asserts].
  *)
-and assertion = { cond : operand; expected : bool }
+and assertion = {
+  cond : operand;
+  expected : bool;
+  kind : assert_kind;  (** Why the assertion is there. *)
+}
 
 and closure_kind = Fn | FnMut | FnOnce
 
@@ -178,14 +187,29 @@ type global_decl = {
   def_id : global_decl_id;
   item_meta : item_meta;  (** The meta data associated with the declaration. *)
   generics : generic_params;
+      (** The generic parameters of the global. Globals can be generic when they are associated
+          constants of a generic impl or trait, or with the unstable `generic_const_items` feature.
+       *)
   ty : ty;
   kind : item_kind;
       (** The global kind: "regular" function, trait const declaration, etc. *)
+  global_kind : global_kind;
+      (** Whether this is a constant, a static or a thread-local static. *)
   body : fun_decl_id;
       (** The initializer function used to compute the initial value for this constant/static. It
         uses the same generic parameters as the global.
      *)
 }
+
+(** The kind of storage of a global. *)
+and global_kind =
+  | NamedConst  (** A `const` item, including associated constants. *)
+  | Static  (** A `static` item. *)
+  | StaticMut  (** A `static mut` item. *)
+  | ThreadLocal
+      (** A thread-local static, e.g. one defined with `thread_local!`. Each thread gets its own copy
+          of the value.
+       *)
 [@@deriving
   show,
     eq,
@@ -432,8 +456,102 @@ and cli_options = {
   print_built_llbc : bool;
   print_llbc : bool;
   no_merge_goto_chains : bool;
+  lower_discriminant_checks : bool;
+      (** Replace calls to `is_some`/`is_none`/`is_ok`/`is_err` (and the equivalent matches) with a
+          comparison on the discriminant of the `Option`/`Result`.
+       *)
+  time_passes : bool;
+      (** Measure the time spent in each transformation pass, and print a summary to stderr. *)
+  reconstruct_let_else : bool;
+      (** Reconstruct `let`-else statements (e.g. `let Some(x) = opt else { return };`) instead of
+          leaving them as a match with a diverging branch.
+       *)
+  remove_copy_drops : bool;
+      (** Remove the drops of values whose type is `Copy`, since these are no-ops. *)
+  only : string list;
+      (** Only translate the items that match one of these patterns, along with their
+          dependencies.
+       *)
+  cfg : string list;
+      (** Conditional compilation options to pass to rustc, e.g. `--cfg feature=x`. *)
+  substitute_single_use : bool;
+      (** Substitute the temporaries which are assigned once and used once in the statement that
+          uses them, e.g. `t := a + b; @0 := move t` becomes `@0 := a + b`.
+       *)
+  reconstruct_for_loops : bool;
+      (** Reconstruct the `for` loops (e.g. `for x in iter { ... }`) instead of leaving them as a
+          loop which calls `Iterator::next`.
+       *)
+  error_format : error_format;
+      (** The format in which to print the errors and warnings: `human` (the default) or `json`,
+          which prints each of them as a JSON object on its own line.
+       *)
+  print_stats : bool;
+      (** Print statistics about the translated crate (number of items, of statements, etc.) at the
+          end of the transformations.
+       *)
+  sink_common_statements : bool;
+      (** Move the statements which end all the branches of a switch to the block where the branches
+          join, e.g. [if b { x := 1; y := 0 } else { x := 2; y := 0 }] becomes
+          [if b { x := 1 } else { x := 2 }; y := 0].
+       *)
+  dedup_constants : bool;
+      (** Hoist the non-trivial constants which appear several times in the crate (e.g. borrowed
+          arrays) into shared globals, and replace them with reads of those globals.
+       *)
+  name_tuple_fields : bool;
+      (** Name the fields of tuple structs and tuple variants after their index, i.e. `"0"`, `"1"`,
+          etc., instead of leaving them unnamed.
+       *)
+  reconstruct_let_chains : bool;
+      (** Reconstruct the `if let` chains, e.g. `if let Some(x) = a && let Ok(y) = b { .. }`, instead
+          of leaving them as nested matches.
+       *)
+  keep_discriminant_reads : bool;
+      (** Keep the discriminant reads (`_x = discriminant(_y)`) and the switches on their value,
+          instead of turning them into `match`es on the enum variants.
+       *)
+  preset : preset option;
+      (** Set a coherent group of the transformation options at once, see [Preset]. The preset only
+          turns options on: the options passed individually are kept. The driver expands it before
+          using the options, hence the exported options show the ones it set.
+       *)
+  keep_overflow_checks : bool;
+      (** Keep the arithmetic overflow checks (e.g. the ones enabled with `-C overflow-checks=on`)
+          as assertions, instead of removing them like the other dynamic checks.
+       *)
+  monomorphize : bool;
+      (** Instantiate the generic functions and types at the concrete arguments they are used with,
+          starting from the non-generic items. The instances are added to the crate as new items.
+       *)
+  include_std : bool;
+      (** Translate the bodies of the items of the standard library (`core`, `alloc` and `std`) we
+          encounter, when their MIR is available, instead of leaving them opaque. This is equivalent
+          to `--include core --include alloc --include std`.
+       *)
+  no_cleanup : bool;
+      (** Don't run the cleanup passes which are not needed to get well-formed (U)LLBC: the removal of
+          the unused `PhantomData` locals, of the overwritten `SetDiscriminant`s, of the trivial
+          assertions and of the match arms of the uninhabited variants, and the folding of
+          `size_of`/`align_of`. The calls to `black_box` and to the methods of the primitive types are
+          also kept as regular calls.
+       *)
 }
 
+(** The format in which to print the errors and warnings. *)
+and error_format =
+  | Human  (** Human-readable messages with source snippets. *)
+  | Json  (** One JSON object per message, with the level, the message and the spans. *)
+
+(** A named group of transformation options. *)
+and preset =
+  | Aeneas
+      (** The output expected by Aeneas: sets `--hide-marker-traits` and `--remove-copy-drops`. *)
+  | Raw
+      (** Stay as close to the MIR as possible: sets `--no-merge-goto-chains`,
+          `--keep-discriminant-reads` and `--no-cleanup`.
+       *)
+
 (** A (group of) top-level declaration(s), properly reordered.
     "G" stands for "generic"
  *)
@@ -135,30 +135,6 @@ and cast_kind_of_json (ctx : of_json_ctx) (js : json) :
         Ok (CastTransmute (x_0, x_1))
     | _ -> Error "")
 
-and binop_of_json (ctx : of_json_ctx) (js : json) : (binop, string) result =
-  combine_error_msgs js __FUNCTION__
-    (match js with
-    | `String "BitXor" -> Ok BitXor
-    | `String "BitAnd" -> Ok BitAnd
-    | `String "BitOr" -> Ok BitOr
-    | `String "Eq" -> Ok Eq
-    | `String "Lt" -> Ok Lt
-    | `String "Le" -> Ok Le
-    | `String "Ne" -> Ok Ne
-    | `String "Ge" -> Ok Ge
-    | `String "Gt" -> Ok Gt
-    | `String "Div" -> Ok Div
-    | `String "Rem" -> Ok Rem
-    | `String "Add" -> Ok Add
-    | `String "Sub" -> Ok Sub
-    | `String "Mul" -> Ok Mul
-    | `String "CheckedAdd" -> Ok CheckedAdd
-    | `String "CheckedSub" -> Ok CheckedSub
-    | `String "CheckedMul" -> Ok CheckedMul
-    | `String "Shl" -> Ok Shl
-    | `String "Shr" -> Ok Shr
-    | _ -> Error "")
-
 and operand_of_json (ctx : of_json_ctx) (js : json) : (operand, string) result =
   combine_error_msgs js __FUNCTION__
     (match js with
@@ -189,12 +165,19 @@ and builtin_fun_id_of_json (ctx : of_json_ctx) (js : json) :
   combine_error_msgs js __FUNCTION__
     (match js with
     | `String "BoxNew" -> Ok BoxNew
+    | `String "RcNew" -> Ok RcNew
+    | `String "ArcNew" -> Ok ArcNew
+    | `String "RcClone" -> Ok RcClone
+    | `String "ArcClone" -> Ok ArcClone
     | `String "ArrayToSliceShared" -> Ok ArrayToSliceShared
     | `String "ArrayToSliceMut" -> Ok ArrayToSliceMut
     | `String "ArrayRepeat" -> Ok ArrayRepeat
     | `Assoc [ ("Index", index) ] ->
         let* index = builtin_index_op_of_json ctx index in
         Ok (Index index)
+    | `Assoc [ ("PrimitiveMethod", primitive_method) ] ->
+        let* primitive_method = primitive_method_of_json ctx primitive_method in
+        Ok (PrimitiveMethod primitive_method)
     | _ -> Error "")
 
 and builtin_index_op_of_json (ctx : of_json_ctx) (js : json) :
@@ -213,6 +196,25 @@ and builtin_index_op_of_json (ctx : of_json_ctx) (js : json) :
         Ok ({ is_array; mutability; is_range } : builtin_index_op)
     | _ -> Error "")
 
+and primitive_method_of_json (ctx : of_json_ctx) (js : json) :
+    (primitive_method, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `String "CountOnes" -> Ok CountOnes
+    | `String "CountZeros" -> Ok CountZeros
+    | `String "LeadingZeros" -> Ok LeadingZeros
+    | `String "TrailingZeros" -> Ok TrailingZeros
+    | `String "RotateLeft" -> Ok RotateLeft
+    | `String "RotateRight" -> Ok RotateRight
+    | `String "SwapBytes" -> Ok SwapBytes
+    | `String "ReverseBits" -> Ok ReverseBits
+    | `String "Pow" -> Ok Pow
+    | `String "Abs" -> Ok Abs
+    | `String "Min" -> Ok Min
+    | `String "Max" -> Ok Max
+    | `String "Sqrt" -> Ok Sqrt
+    | _ -> Error "")
+
 and fun_id_or_trait_method_ref_of_json (ctx : of_json_ctx) (js : json) :
     (fun_id_or_trait_method_ref, string) result =
   combine_error_msgs js __FUNCTION__
@@ -315,6 +317,9 @@ and rvalue_of_json (ctx : of_json_ctx) (js : json) : (rvalue, string) result =
         let* x_1 = ty_of_json ctx x_1 in
         let* x_2 = option_of_json const_generic_of_json ctx x_2 in
         Ok (Len (x_0, x_1, x_2))
+    | `Assoc [ ("BlackBox", black_box) ] ->
+        let* black_box = operand_of_json ctx black_box in
+        Ok (BlackBox black_box)
     | _ -> Error "")
 
 and aggregate_kind_of_json (ctx : of_json_ctx) (js : json) :
@@ -432,6 +437,7 @@ and global_decl_of_json (ctx : of_json_ctx) (js : json) :
           ("generics", generics);
           ("ty", ty);
           ("kind", kind);
+          ("global_kind", global_kind);
           ("init", init);
         ] ->
         let* def_id = global_decl_id_of_json ctx def_id in
@@ -439,8 +445,21 @@ and global_decl_of_json (ctx : of_json_ctx) (js : json) :
         let* generics = generic_params_of_json ctx generics in
         let* ty = ty_of_json ctx ty in
         let* kind = item_kind_of_json ctx kind in
+        let* global_kind = global_kind_of_json ctx global_kind in
         let* body = fun_decl_id_of_json ctx init in
-        Ok ({ def_id; item_meta; generics; ty; kind; body } : global_decl)
+        Ok
+          ({ def_id; item_meta; generics; ty; kind; global_kind; body }
+            : global_decl)
+    | _ -> Error "")
+
+and global_kind_of_json (ctx : of_json_ctx) (js : json) :
+    (global_kind, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `String "NamedConst" -> Ok NamedConst
+    | `String "Static" -> Ok Static
+    | `String "StaticMut" -> Ok StaticMut
+    | `String "ThreadLocal" -> Ok ThreadLocal
     | _ -> Error "")
 
 and global_decl_ref_of_json (ctx : of_json_ctx) (js : json) :
@@ -570,6 +589,9 @@ and fn_operand_of_json (ctx : of_json_ctx) (js : json) :
     | `Assoc [ ("Regular", regular) ] ->
         let* regular = fn_ptr_of_json ctx regular in
         Ok (FnOpRegular regular)
+    | `Assoc [ ("Dynamic", dynamic) ] ->
+        let* dynamic = fn_ptr_of_json ctx dynamic in
+        Ok (FnOpDynamic dynamic)
     | `Assoc [ ("Move", move) ] ->
         let* move = place_of_json ctx move in
         Ok (FnOpMove move)
@@ -589,20 +611,50 @@ and abort_kind_of_json (ctx : of_json_ctx) (js : json) :
     (abort_kind, string) result =
   combine_error_msgs js __FUNCTION__
     (match js with
-    | `Assoc [ ("Panic", panic) ] ->
-        let* panic = name_of_json ctx panic in
-        Ok (Panic panic)
+    | `Assoc
+        [
+          ( "Panic",
+            `Assoc [ ("name", name); ("kind", kind); ("message", message) ] );
+        ] ->
+        let* name = name_of_json ctx name in
+        let* kind = panic_kind_of_json ctx kind in
+        let* message = option_of_json string_of_json ctx message in
+        Ok (Panic (name, kind, message))
     | `String "UndefinedBehavior" -> Ok UndefinedBehavior
     | _ -> Error "")
 
+and panic_kind_of_json (ctx : of_json_ctx) (js : json) :
+    (panic_kind, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `String "Explicit" -> Ok Explicit
+    | `String "AssertFailed" -> Ok AssertFailed
+    | `String "Unwrap" -> Ok Unwrap
+    | _ -> Error "")
+
+and assert_kind_of_json (ctx : of_json_ctx) (js : json) :
+    (assert_kind, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `String "BoundsCheck" -> Ok BoundsCheck
+    | `String "Overflow" -> Ok Overflow
+    | `String "DivisionByZero" -> Ok DivisionByZero
+    | `String "Compiler" -> Ok Compiler
+    | `String "Assume" -> Ok Assume
+    | `Assoc [ ("UserAssert", `Assoc [ ("message", message) ]) ] ->
+        let* message = option_of_json string_of_json ctx message in
+        Ok (UserAssert message)
+    | _ -> Error "")
+
 and assertion_of_json (ctx : of_json_ctx) (js : json) :
     (assertion, string) result =
   combine_error_msgs js __FUNCTION__
     (match js with
-    | `Assoc [ ("cond", cond); ("expected", expected) ] ->
+    | `Assoc [ ("cond", cond); ("expected", expected); ("kind", kind) ] ->
         let* cond = operand_of_json ctx cond in
         let* expected = bool_of_json ctx expected in
-        Ok ({ cond; expected } : assertion)
+        let* kind = assert_kind_of_json ctx kind in
+        Ok ({ cond; expected; kind } : assertion)
     | _ -> Error "")
 
 and fun_decl_id_of_json (ctx : of_json_ctx) (js : json) :
@@ -768,17 +820,55 @@ and item_meta_of_json (ctx : of_json_ctx) (js : json) :
         [
           ("name", name);
           ("span", span);
+          ("name_span", name_span);
           ("source_text", source_text);
           ("attr_info", attr_info);
           ("is_local", is_local);
           ("opacity", _);
+          ("no_mangle", no_mangle);
+          ("export_name", export_name);
+          ("must_use", must_use);
+          ("cold", cold);
+          ("non_exhaustive", non_exhaustive);
+          ("is_derived", is_derived);
+          ("is_foreign", is_foreign);
+          ("foreign_abi", foreign_abi);
+          ("from_macro", from_macro);
         ] ->
         let* name = name_of_json ctx name in
         let* span = span_of_json ctx span in
+        let* name_span = span_of_json ctx name_span in
         let* source_text = option_of_json string_of_json ctx source_text in
         let* attr_info = attr_info_of_json ctx attr_info in
         let* is_local = bool_of_json ctx is_local in
-        Ok ({ name; span; source_text; attr_info; is_local } : item_meta)
+        let* no_mangle = bool_of_json ctx no_mangle in
+        let* export_name = option_of_json string_of_json ctx export_name in
+        let* must_use = bool_of_json ctx must_use in
+        let* cold = bool_of_json ctx cold in
+        let* non_exhaustive = bool_of_json ctx non_exhaustive in
+        let* is_derived = bool_of_json ctx is_derived in
+        let* is_foreign = bool_of_json ctx is_foreign in
+        let* foreign_abi = option_of_json string_of_json ctx foreign_abi in
+        let* from_macro = option_of_json span_of_json ctx from_macro in
+        Ok
+          ({
+             name;
+             span;
+             name_span;
+             source_text;
+             attr_info;
+             is_local;
+             no_mangle;
+             export_name;
+             must_use;
+             cold;
+             non_exhaustive;
+             is_derived;
+             is_foreign;
+             foreign_abi;
+             from_macro;
+           }
+            : item_meta)
     | _ -> Error "")
 
 and file_name_of_json (ctx : of_json_ctx) (js : json) :
@@ -821,6 +911,9 @@ and path_elem_of_json (ctx : of_json_ctx) (js : json) :
         let* x_0 = impl_elem_of_json ctx x_0 in
         let* x_1 = disambiguator_of_json ctx x_1 in
         Ok (PeImpl (x_0, x_1))
+    | `Assoc [ ("Monomorphized", monomorphized) ] ->
+        let* monomorphized = generic_args_of_json ctx monomorphized in
+        Ok (PeMonomorphized monomorphized)
     | _ -> Error "")
 
 and impl_elem_of_json (ctx : of_json_ctx) (js : json) :
@@ -925,11 +1018,14 @@ and const_generic_var_of_json (ctx : of_json_ctx) (js : json) :
     (const_generic_var, string) result =
   combine_error_msgs js __FUNCTION__
     (match js with
-    | `Assoc [ ("index", index); ("name", name); ("ty", ty) ] ->
+    | `Assoc
+        [ ("index", index); ("name", name); ("ty", ty); ("default", default) ]
+      ->
         let* index = const_generic_var_id_of_json ctx index in
         let* name = string_of_json ctx name in
         let* ty = literal_type_of_json ctx ty in
-        Ok ({ index; name; ty } : const_generic_var)
+        let* default = option_of_json const_generic_of_json ctx default in
+        Ok ({ index; name; ty; default } : const_generic_var)
     | _ -> Error "")
 
 and trait_clause_of_json (ctx : of_json_ctx) (js : json) :
@@ -940,13 +1036,30 @@ and trait_clause_of_json (ctx : of_json_ctx) (js : json) :
         [
           ("clause_id", clause_id);
           ("span", span);
-          ("origin", _);
+          ("origin", origin);
+          ("is_const", is_const);
           ("trait_", trait);
         ] ->
         let* clause_id = trait_clause_id_of_json ctx clause_id in
         let* span = option_of_json span_of_json ctx span in
+        let* origin = predicate_origin_of_json ctx origin in
+        let* is_const = bool_of_json ctx is_const in
         let* trait = region_binder_of_json trait_decl_ref_of_json ctx trait in
-        Ok ({ clause_id; span; trait } : trait_clause)
+        Ok ({ clause_id; span; origin; is_const; trait } : trait_clause)
+    | _ -> Error "")
+
+and predicate_origin_of_json (ctx : of_json_ctx) (js : json) :
+    (predicate_origin, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `String "WhereClauseOnFn" -> Ok WhereClauseOnFn
+    | `String "WhereClauseOnType" -> Ok WhereClauseOnType
+    | `String "WhereClauseOnImpl" -> Ok WhereClauseOnImpl
+    | `String "TraitSelf" -> Ok TraitSelf
+    | `String "WhereClauseOnTrait" -> Ok WhereClauseOnTrait
+    | `Assoc [ ("TraitItem", trait_item) ] ->
+        let* trait_item = trait_item_name_of_json ctx trait_item in
+        Ok (TraitItem trait_item)
     | _ -> Error "")
 
 and region_of_json (ctx : of_json_ctx) (js : json) : (region, string) result =
@@ -1230,12 +1343,30 @@ and type_decl_of_json (ctx : of_json_ctx) (js : json) :
           ("item_meta", item_meta);
           ("generics", generics);
           ("kind", kind);
+          ("align", align);
+          ("drop_impl", drop_impl);
+          ("simd", simd);
         ] ->
         let* def_id = type_decl_id_of_json ctx def_id in
         let* item_meta = item_meta_of_json ctx item_meta in
         let* generics = generic_params_of_json ctx generics in
         let* kind = type_decl_kind_of_json ctx kind in
-        Ok ({ def_id; item_meta; generics; kind } : type_decl)
+        let* align = option_of_json int_of_json ctx align in
+        let* drop_impl = option_of_json trait_impl_id_of_json ctx drop_impl in
+        let* simd = option_of_json simd_repr_of_json ctx simd in
+        Ok
+          ({ def_id; item_meta; generics; kind; align; drop_impl; simd }
+            : type_decl)
+    | _ -> Error "")
+
+and simd_repr_of_json (ctx : of_json_ctx) (js : json) :
+    (simd_repr, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `Assoc [ ("elem_ty", elem_ty); ("lanes", lanes) ] ->
+        let* elem_ty = ty_of_json ctx elem_ty in
+        let* lanes = const_generic_of_json ctx lanes in
+        Ok ({ elem_ty; lanes } : simd_repr)
     | _ -> Error "")
 
 and variant_id_of_json (ctx : of_json_ctx) (js : json) :
@@ -1287,6 +1418,7 @@ and variant_of_json (ctx : of_json_ctx) (js : json) : (variant, string) result =
           ("attr_info", attr_info);
           ("name", name);
           ("fields", fields);
+          ("non_exhaustive", non_exhaustive);
           ("discriminant", discriminant);
         ] ->
         let* span = span_of_json ctx span in
@@ -1295,8 +1427,18 @@ and variant_of_json (ctx : of_json_ctx) (js : json) : (variant, string) result =
         let* fields =
           vector_of_json field_id_of_json field_of_json ctx fields
         in
+        let* non_exhaustive = bool_of_json ctx non_exhaustive in
         let* discriminant = scalar_value_of_json ctx discriminant in
-        Ok ({ span; attr_info; variant_name; fields; discriminant } : variant)
+        Ok
+          ({
+             span;
+             attr_info;
+             variant_name;
+             fields;
+             non_exhaustive;
+             discriminant;
+           }
+            : variant)
     | _ -> Error "")
 
 and field_of_json (ctx : of_json_ctx) (js : json) : (field, string) result =
@@ -1387,6 +1529,45 @@ and const_generic_of_json (ctx : of_json_ctx) (js : json) :
     | `Assoc [ ("Value", value) ] ->
         let* value = literal_of_json ctx value in
         Ok (CgValue value)
+    | `Assoc [ ("App", `List [ x_0; x_1 ]) ] ->
+        let* x_0 = const_generic_op_of_json ctx x_0 in
+        let* x_1 = list_of_json const_generic_of_json ctx x_1 in
+        Ok (CgApp (x_0, x_1))
+    | _ -> Error "")
+
+and const_generic_op_of_json (ctx : of_json_ctx) (js : json) :
+    (const_generic_op, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `Assoc [ ("BinOp", bin_op) ] ->
+        let* bin_op = binop_of_json ctx bin_op in
+        Ok (CgBinOp bin_op)
+    | `String "Neg" -> Ok CgNeg
+    | `String "Not" -> Ok CgNot
+    | _ -> Error "")
+
+and binop_of_json (ctx : of_json_ctx) (js : json) : (binop, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `String "BitXor" -> Ok BitXor
+    | `String "BitAnd" -> Ok BitAnd
+    | `String "BitOr" -> Ok BitOr
+    | `String "Eq" -> Ok Eq
+    | `String "Lt" -> Ok Lt
+    | `String "Le" -> Ok Le
+    | `String "Ne" -> Ok Ne
+    | `String "Ge" -> Ok Ge
+    | `String "Gt" -> Ok Gt
+    | `String "Div" -> Ok Div
+    | `String "Rem" -> Ok Rem
+    | `String "Add" -> Ok Add
+    | `String "Sub" -> Ok Sub
+    | `String "Mul" -> Ok Mul
+    | `String "CheckedAdd" -> Ok CheckedAdd
+    | `String "CheckedSub" -> Ok CheckedSub
+    | `String "CheckedMul" -> Ok CheckedMul
+    | `String "Shl" -> Ok Shl
+    | `String "Shr" -> Ok Shr
     | _ -> Error "")
 
 and ty_of_json (ctx : of_json_ctx) (js : json) : (ty, string) result =
@@ -1435,6 +1616,8 @@ and builtin_ty_of_json (ctx : of_json_ctx) (js : json) :
   combine_error_msgs js __FUNCTION__
     (match js with
     | `String "Box" -> Ok TBox
+    | `String "Rc" -> Ok TRc
+    | `String "Arc" -> Ok TArc
     | `String "Array" -> Ok TArray
     | `String "Slice" -> Ok TSlice
     | `String "Str" -> Ok TStr
@@ -1629,6 +1812,26 @@ and cli_options_of_json (ctx : of_json_ctx) (js : json) :
           ("print_built_llbc", print_built_llbc);
           ("print_llbc", print_llbc);
           ("no_merge_goto_chains", no_merge_goto_chains);
+          ("lower_discriminant_checks", lower_discriminant_checks);
+          ("time_passes", time_passes);
+          ("reconstruct_let_else", reconstruct_let_else);
+          ("remove_copy_drops", remove_copy_drops);
+          ("only", only);
+          ("cfg", cfg);
+          ("substitute_single_use", substitute_single_use);
+          ("reconstruct_for_loops", reconstruct_for_loops);
+          ("error_format", error_format);
+          ("print_stats", print_stats);
+          ("sink_common_statements", sink_common_statements);
+          ("dedup_constants", dedup_constants);
+          ("name_tuple_fields", name_tuple_fields);
+          ("reconstruct_let_chains", reconstruct_let_chains);
+          ("keep_discriminant_reads", keep_discriminant_reads);
+          ("preset", preset);
+          ("keep_overflow_checks", keep_overflow_checks);
+          ("monomorphize", monomorphize);
+          ("include_std", include_std);
+          ("no_cleanup", no_cleanup);
         ] ->
         let* ullbc = bool_of_json ctx ullbc in
         let* lib = bool_of_json ctx lib in
@@ -1663,6 +1866,30 @@ and cli_options_of_json (ctx : of_json_ctx) (js : json) :
         let* print_built_llbc = bool_of_json ctx print_built_llbc in
         let* print_llbc = bool_of_json ctx print_llbc in
         let* no_merge_goto_chains = bool_of_json ctx no_merge_goto_chains in
+        let* lower_discriminant_checks =
+          bool_of_json ctx lower_discriminant_checks
+        in
+        let* time_passes = bool_of_json ctx time_passes in
+        let* reconstruct_let_else = bool_of_json ctx reconstruct_let_else in
+        let* remove_copy_drops = bool_of_json ctx remove_copy_drops in
+        let* only = list_of_json string_of_json ctx only in
+        let* cfg = list_of_json string_of_json ctx cfg in
+        let* substitute_single_use = bool_of_json ctx substitute_single_use in
+        let* reconstruct_for_loops = bool_of_json ctx reconstruct_for_loops in
+        let* error_format = error_format_of_json ctx error_format in
+        let* print_stats = bool_of_json ctx print_stats in
+        let* sink_common_statements = bool_of_json ctx sink_common_statements in
+        let* dedup_constants = bool_of_json ctx dedup_constants in
+        let* name_tuple_fields = bool_of_json ctx name_tuple_fields in
+        let* reconstruct_let_chains = bool_of_json ctx reconstruct_let_chains in
+        let* keep_discriminant_reads =
+          bool_of_json ctx keep_discriminant_reads
+        in
+        let* preset = option_of_json preset_of_json ctx preset in
+        let* keep_overflow_checks = bool_of_json ctx keep_overflow_checks in
+        let* monomorphize = bool_of_json ctx monomorphize in
+        let* include_std = bool_of_json ctx include_std in
+        let* no_cleanup = bool_of_json ctx no_cleanup in
         Ok
           ({
              ullbc;
@@ -1696,10 +1923,45 @@ and cli_options_of_json (ctx : of_json_ctx) (js : json) :
              print_built_llbc;
              print_llbc;
              no_merge_goto_chains;
+             lower_discriminant_checks;
+             time_passes;
+             reconstruct_let_else;
+             remove_copy_drops;
+             only;
+             cfg;
+             substitute_single_use;
+             reconstruct_for_loops;
+             error_format;
+             print_stats;
+             sink_common_statements;
+             dedup_constants;
+             name_tuple_fields;
+             reconstruct_let_chains;
+             keep_discriminant_reads;
+             preset;
+             keep_overflow_checks;
+             monomorphize;
+             include_std;
+             no_cleanup;
            }
             : cli_options)
     | _ -> Error "")
 
+and error_format_of_json (ctx : of_json_ctx) (js : json) :
+    (error_format, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `String "Human" -> Ok Human
+    | `String "Json" -> Ok Json
+    | _ -> Error "")
+
+and preset_of_json (ctx : of_json_ctx) (js : json) : (preset, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `String "Aeneas" -> Ok Aeneas
+    | `String "Raw" -> Ok Raw
+    | _ -> Error "")
+
 and g_declaration_group_of_json :
       'a0.
       (of_json_ctx -> json -> ('a0, string) result) ->
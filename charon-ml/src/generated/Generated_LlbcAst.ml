@@ -27,6 +27,32 @@ type raw_statement =
   | Sequence of statement * statement
   | Switch of switch
   | Loop of statement
+  | LetElse of place * variant_id * block * block
+      (** A `let`-else, e.g. `let Some(x) = opt else { return };`. Gives the place we match on, the
+          variant it must have, the block which binds the variables of the pattern, and the `else`
+          block. If the place has the given variant, we run the bindings then continue with the next
+          statement; otherwise we run the `else` block, which always diverges.
+
+          This is introduced by [crate::transform::reconstruct_let_else] when
+          `--reconstruct-let-else` is set.
+       *)
+  | ForLoop of place * place * block
+      (** A `for` loop, e.g. `for x in iter { ... }`. Gives the iterator, the place which receives
+          the items and the body. Each iteration calls `Iterator::next` on the iterator: if it returns
+          `Some(x)`, we assign `x` to the item place and run the body, otherwise we exit the loop.
+          Like for [RawStatement::Loop], `break 0` and `continue 0` in the body refer to this loop.
+
+          This is introduced by [crate::transform::reconstruct_for_loops] when
+          `--reconstruct-for-loops` is set.
+       *)
+  | IfLet of let_condition list * block
+      (** An `if let` chain, e.g. `if let Some(x) = a && let Ok(y) = b { ... } else { ... }`. Gives
+          the conditions and the `then` block. We check the conditions in order: if all of them hold
+          we run the `then` block, otherwise we run the `else` block of the first one which fails.
+
+          This is introduced by [crate::transform::reconstruct_let_chains] when
+          `--reconstruct-let-chains` is set.
+       *)
   | Error of string
 
 and statement = {
@@ -37,6 +63,18 @@ and statement = {
 
 and block = statement
 
+(** A condition of an `if let` chain, e.g. `let Some(x) = a`. *)
+and let_condition = {
+  scrutinee : place;  (** The place we match on. *)
+  variant : variant_id;  (** The variant the place must have. *)
+  bindings : block;
+      (** The block which binds the variables of the pattern. It also computes the scrutinee of the
+          next condition, if any.
+       *)
+  else_block : block;
+      (** The block we run if the place doesn't have the expected variant. *)
+}
+
 and switch =
   | If of operand * block * block
       (** Gives the `if` block and the `else` block. The `Operand` is the condition of the `if`, e.g. `if (y == 0)` could become
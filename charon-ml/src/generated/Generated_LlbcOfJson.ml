@@ -46,6 +46,21 @@ and raw_statement_of_json (ctx : of_json_ctx) (js : json) :
     | `Assoc [ ("Loop", loop) ] ->
         let* loop = block_of_json ctx loop in
         Ok (Loop loop)
+    | `Assoc [ ("LetElse", `List [ x_0; x_1; x_2; x_3 ]) ] ->
+        let* x_0 = place_of_json ctx x_0 in
+        let* x_1 = variant_id_of_json ctx x_1 in
+        let* x_2 = block_of_json ctx x_2 in
+        let* x_3 = block_of_json ctx x_3 in
+        Ok (LetElse (x_0, x_1, x_2, x_3))
+    | `Assoc [ ("ForLoop", `List [ x_0; x_1; x_2 ]) ] ->
+        let* x_0 = place_of_json ctx x_0 in
+        let* x_1 = place_of_json ctx x_1 in
+        let* x_2 = block_of_json ctx x_2 in
+        Ok (ForLoop (x_0, x_1, x_2))
+    | `Assoc [ ("IfLet", `List [ x_0; x_1 ]) ] ->
+        let* x_0 = list_of_json let_condition_of_json ctx x_0 in
+        let* x_1 = block_of_json ctx x_1 in
+        Ok (IfLet (x_0, x_1))
     | `Assoc [ ("Error", error) ] ->
         let* error = string_of_json ctx error in
         Ok (Error error)
@@ -92,6 +107,24 @@ and block_of_json (ctx : of_json_ctx) (js : json) : (block, string) result =
       end
     | _ -> Error "")
 
+and let_condition_of_json (ctx : of_json_ctx) (js : json) :
+    (let_condition, string) result =
+  combine_error_msgs js __FUNCTION__
+    (match js with
+    | `Assoc
+        [
+          ("scrutinee", scrutinee);
+          ("variant", variant);
+          ("bindings", bindings);
+          ("else_block", else_block);
+        ] ->
+        let* scrutinee = place_of_json ctx scrutinee in
+        let* variant = variant_id_of_json ctx variant in
+        let* bindings = block_of_json ctx bindings in
+        let* else_block = block_of_json ctx else_block in
+        Ok ({ scrutinee; variant; bindings; else_block } : let_condition)
+    | _ -> Error "")
+
 and switch_of_json (ctx : of_json_ctx) (js : json) : (switch, string) result =
   combine_error_msgs js __FUNCTION__
     (match js with
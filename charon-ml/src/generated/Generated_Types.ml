@@ -114,6 +114,44 @@ and const_generic =
   | CgGlobal of global_decl_id  (** A global constant *)
   | CgVar of const_generic_var_id de_bruijn_var  (** A const generic variable *)
   | CgValue of literal  (** A concrete value *)
+  | CgApp of const_generic_op * const_generic list
+      (** An arithmetic expression over const generics, e.g. the `N * M` in `[u8; N * M]` (this
+          requires the `generic_const_exprs` feature). Gives the operation and its operands.
+       *)
+
+(** An operation in an arithmetic const generic expression. *)
+and const_generic_op =
+  | CgBinOp of binop  (** A binary operation, with two operands. *)
+  | CgNeg  (** Arithmetic negation, with one operand. *)
+  | CgNot  (** Bitwise or boolean negation, with one operand. *)
+
+(** Binary operations. *)
+and binop =
+  | BitXor
+  | BitAnd
+  | BitOr
+  | Eq
+  | Lt
+  | Le
+  | Ne
+  | Ge
+  | Gt
+  | Div
+      (** Fails if the divisor is 0, or if the operation is `int::MIN / -1`. *)
+  | Rem
+      (** Fails if the divisor is 0, or if the operation is `int::MIN % -1`. *)
+  | Add  (** Fails on overflow. *)
+  | Sub  (** Fails on overflow. *)
+  | Mul  (** Fails on overflow. *)
+  | CheckedAdd
+      (** Returns `(result, did_overflow)`, where `result` is the result of the operation with
+          wrapping semantics, and `did_overflow` is a boolean that indicates whether the operation
+          overflowed. This operation does not fail.
+       *)
+  | CheckedSub  (** Like `CheckedAdd`. *)
+  | CheckedMul  (** Like `CheckedAdd`. *)
+  | Shl  (** Fails if the shift is bigger than the bit-size of the type. *)
+  | Shr  (** Fails if the shift is bigger than the bit-size of the type. *)
 [@@deriving
   show,
     eq,
@@ -198,6 +236,7 @@ type fun_decl_ref = {
 and global_decl_ref = {
   global_id : global_decl_id;
   global_generics : generic_args;
+      (** The arguments that instantiate the generic parameters of the global. *)
 }
 
 and trait_item_name = string
@@ -441,6 +480,9 @@ and ty =
  *)
 and builtin_ty =
   | TBox  (** Boxes are de facto a primitive type. *)
+  | TRc  (** `alloc::rc::Rc`: we treat it like `Box`, with shared ownership. *)
+  | TArc
+      (** `alloc::sync::Arc`: we treat it like `Box`, with shared ownership. *)
   | TArray  (** Primitive type *)
   | TSlice  (** Primitive type *)
   | TStr  (** Primitive type *)
@@ -480,22 +522,87 @@ class ['self] map_type_decl_base =
     method visit_attr_info : 'env -> attr_info -> attr_info = fun _ x -> x
   end
 
-type abort_kind =
-  | Panic of name  (** A built-in panicking function. *)
+(** What caused a panic. Note that arithmetic overflows, out-of-bounds accesses, etc. are not
+    panics from our point of view: they are represented with [Assert] statements.
+ *)
+type panic_kind =
+  | Explicit
+      (** An explicit call to `panic!` or a similar macro (`unreachable!`, `todo!`, etc.). *)
+  | AssertFailed  (** The failure of an `assert_eq!` or `assert_ne!`. *)
+  | Unwrap  (** The failure of `Option::unwrap`, `Result::expect` and the like. *)
+
+and abort_kind =
+  | Panic of name * panic_kind * string option
+      (** A built-in panicking function.
+
+          Fields:
+          - [name]:  The name of the panicking function.
+          - [message]:  The panic message, if it is a constant string we could recover.
+       *)
   | UndefinedBehavior
       (** A MIR `Unreachable` terminator corresponds to undefined behavior in the rust abstract
           machine.
        *)
 
+(** What an [Assert] checks. *)
+and assert_kind =
+  | BoundsCheck  (** An array/slice bounds check inserted by rustc. *)
+  | Overflow  (** An arithmetic overflow check inserted by rustc. *)
+  | DivisionByZero
+      (** A check inserted by rustc that the divisor of a division or remainder is not zero. *)
+  | Compiler  (** Another check inserted by rustc. These panic when they fail. *)
+  | Assume
+      (** A condition whose failure is undefined behavior, like the argument of the `assume`
+          intrinsic.
+       *)
+  | UserAssert of string option
+      (** A user-written `assert!` or `debug_assert!`, or more generally a conditional panic.
+
+          Fields:
+          - [message]:  The panic message, if it is a constant string we could recover.
+       *)
+
 (** Meta information about an item (function, trait decl, trait impl, type decl, global). *)
 and item_meta = {
   name : name;
   span : span;
+  name_span : span;
+      (** The span of the item's name, e.g. the `foo` in `fn foo() {}`. Equal to `span` for items
+          that don't have a name in the source, like impl blocks or closures.
+       *)
   source_text : string option;
       (** The source code that corresponds to this item. *)
   attr_info : attr_info;  (** Attributes and visibility. *)
   is_local : bool;
       (** `true` if the type decl is a local type decl, `false` if it comes from an external crate. *)
+  no_mangle : bool;
+      (** Whether the item has a `#[no_mangle]` attribute, i.e. its symbol is its plain name. *)
+  export_name : string option;
+      (** The symbol name given by an `#[export_name = "..."]` attribute, if any. *)
+  must_use : bool;  (** Whether the item has a `#[must_use]` attribute. *)
+  cold : bool;
+      (** Whether the item has a `#[cold]` attribute, i.e. it is unlikely to be called. *)
+  non_exhaustive : bool;
+      (** Whether the item has a `#[non_exhaustive]` attribute. For an enum, this means that matches
+          on it from other crates must have a wildcard arm; for a struct, that it can't be built
+          with a struct expression from other crates.
+       *)
+  is_derived : bool;
+      (** Whether the item was generated by a `#[derive(...)]`, i.e. it is an impl marked
+          `#[automatically_derived]` or an item of such an impl.
+       *)
+  is_foreign : bool;
+      (** Whether the item is declared in an `extern { .. }` block, i.e. it is implemented outside of
+          Rust. Such items have no body.
+       *)
+  foreign_abi : string option;
+      (** The ABI of a foreign function, e.g. `"C"`. `None` for the other items, including foreign
+          statics.
+       *)
+  from_macro : span option;
+      (** If the item was generated by a macro, e.g. a `macro_rules!`, the span of the (outermost)
+          macro invocation that produced it.
+       *)
 }
 
 and disambiguator = (Disambiguator.id[@visitors.opaque])
@@ -504,6 +611,10 @@ and disambiguator = (Disambiguator.id[@visitors.opaque])
 and path_elem =
   | PeIdent of string * disambiguator
   | PeImpl of impl_elem * disambiguator
+  | PeMonomorphized of generic_args
+      (** An instance of a generic item created by monomorphization: the instance of `foo` at
+          `u32` is named `foo::<u32>`, i.e. the name of `foo` followed by this element.
+       *)
 
 (** There are two kinds of `impl` blocks:
     - impl blocks linked to a type ("inherent" impl blocks following Rust terminology):
@@ -565,8 +676,19 @@ and const_generic_var = {
       (** Index identifying the variable among other variables bound at the same level. *)
   name : string;  (** Const generic name *)
   ty : literal_type;  (** Type of the const generic *)
+  default : const_generic option;
+      (** The default value of the const generic, if any (e.g. `4` in `const N: usize = 4`). *)
 }
 
+(** Where a given predicate came from. *)
+and predicate_origin =
+  | WhereClauseOnFn
+  | WhereClauseOnType
+  | WhereClauseOnImpl
+  | TraitSelf
+  | WhereClauseOnTrait
+  | TraitItem of trait_item_name
+
 (** A trait predicate in a signature, of the form `Type: Trait<Args>`. This functions like a
     variable binder, to which variables of the form `TraitRefKind::Clause` can refer to.
  *)
@@ -574,6 +696,12 @@ and trait_clause = {
   clause_id : trait_clause_id;
       (** Index identifying the clause among other clauses bound at the same level. *)
   span : span option;
+  origin : predicate_origin;
+      (** Where the predicate was written, relative to the item that requires it. *)
+  is_const : bool;
+      (** Whether this is a `~const` or `const` bound, as allowed by the unstable `const_trait_impl`
+          feature.
+       *)
   trait : trait_decl_ref region_binder;  (** The trait that is implemented. *)
 }
 
@@ -655,6 +783,26 @@ and type_decl = {
   item_meta : item_meta;  (** Meta information associated with the item. *)
   generics : generic_params;
   kind : type_decl_kind;  (** The type kind: enum, struct, or opaque. *)
+  align : int option;
+      (** The alignment in bytes requested with `#[repr(align(N))]`, if any. *)
+  drop_impl : trait_impl_id option;
+      (** The `impl Drop` of this type, if any. Dropping a value of a type with such an impl runs
+          its `drop` method. This is only filled for the types of the current crate, and is not
+          counted as a dependency of the type.
+       *)
+  simd : simd_repr option;
+      (** The lanes of the type, if it is a `#[repr(simd)]` vector type. *)
+}
+
+(** The layout of a `#[repr(simd)]` type, e.g. `struct f32x4([f32; 4])`: a vector of `lanes`
+    elements of type `elem_ty`.
+ *)
+and simd_repr = {
+  elem_ty : ty;
+  lanes : const_generic;
+      (** The number of lanes. This is a const generic variable for types like
+          `struct Simd<T, const N: usize>([T; N])`.
+       *)
 }
 
 and variant_id = (VariantId.id[@visitors.opaque])
@@ -683,6 +831,10 @@ and variant = {
   attr_info : attr_info;
   variant_name : string;
   fields : field list;
+  non_exhaustive : bool;
+      (** Whether the variant has a `#[non_exhaustive]` attribute, i.e. it may get more fields in
+        the future.
+     *)
   discriminant : scalar_value;
       (** The discriminant used at runtime. This is used in `remove_read_discriminant` to match up
         `SwitchInt` targets with the corresponding `Variant`.
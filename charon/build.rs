@@ -0,0 +1,22 @@
+//! Record the git commit charon is built from, so that we can include it in the files we export.
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+    let commit = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|commit| commit.trim().to_owned())
+        .unwrap_or_else(|| "unknown".to_owned());
+    println!("cargo:rustc-env=CHARON_COMMIT_HASH={commit}");
+    // Cargo reruns the script every time when a watched path doesn't exist, e.g. when building
+    // from a source archive.
+    for path in ["../.git/HEAD", "../.git/refs/heads"] {
+        if Path::new(path).exists() {
+            println!("cargo:rerun-if-changed={path}");
+        }
+    }
+}
@@ -24,6 +24,10 @@ pub static EXPLICIT_PANIC_NAME: &[&str] = &["core", "panicking", "panic_explicit
 pub enum BuiltinFun {
     Panic,
     BoxNew,
+    RcNew,
+    ArcNew,
+    /// A method of a primitive numeric type, with that type.
+    PrimitiveMethod(ast::PrimitiveMethod, LiteralTy),
 }
 
 impl BuiltinFun {
@@ -32,15 +36,53 @@ impl BuiltinFun {
     pub fn to_ullbc_builtin_fun(self) -> ast::BuiltinFunId {
         match self {
             BuiltinFun::BoxNew => ast::BuiltinFunId::BoxNew,
+            BuiltinFun::RcNew => ast::BuiltinFunId::RcNew,
+            BuiltinFun::ArcNew => ast::BuiltinFunId::ArcNew,
+            BuiltinFun::PrimitiveMethod(method, _) => ast::BuiltinFunId::PrimitiveMethod(method),
             BuiltinFun::Panic => panic!(),
         }
     }
 }
 
+/// Recognize the methods of the inherent impls of the integer and float types that we treat as
+/// built-in, e.g. `core::num::{u32}::count_ones`.
+pub fn recognize_primitive_method(name: &Name) -> Option<BuiltinFun> {
+    use ast::PrimitiveMethod::*;
+    let [.., PathElem::Impl(ImplElem::Ty(impl_ty), _), PathElem::Ident(method_name, _)] =
+        name.name.as_slice()
+    else {
+        return None;
+    };
+    let TyKind::Literal(lit_ty @ (LiteralTy::Integer(_) | LiteralTy::Float(_))) =
+        impl_ty.skip_binder.kind()
+    else {
+        return None;
+    };
+    let method = match method_name.as_str() {
+        "count_ones" => CountOnes,
+        "count_zeros" => CountZeros,
+        "leading_zeros" => LeadingZeros,
+        "trailing_zeros" => TrailingZeros,
+        "rotate_left" => RotateLeft,
+        "rotate_right" => RotateRight,
+        "swap_bytes" => SwapBytes,
+        "reverse_bits" => ReverseBits,
+        "pow" => Pow,
+        "abs" => Abs,
+        "min" => Min,
+        "max" => Max,
+        "sqrt" => Sqrt,
+        _ => return None,
+    };
+    Some(BuiltinFun::PrimitiveMethod(method, *lit_ty))
+}
+
 impl BuiltinTy {
     pub fn get_name(self) -> Name {
         let name: &[_] = match self {
             BuiltinTy::Box => &["alloc", "boxed", "Box"],
+            BuiltinTy::Rc => &["alloc", "rc", "Rc"],
+            BuiltinTy::Arc => &["alloc", "sync", "Arc"],
             BuiltinTy::Str => &["Str"],
             BuiltinTy::Array => &["Array"],
             BuiltinTy::Slice => &["Slice"],
@@ -54,7 +96,7 @@ impl BuiltinTy {
 /// (`std::alloc::Allocator`): we ignore it.
 pub fn type_to_used_params(id: BuiltinTy) -> Vec<bool> {
     match id {
-        BuiltinTy::Box => {
+        BuiltinTy::Box | BuiltinTy::Rc | BuiltinTy::Arc => {
             vec![true, false]
         }
         BuiltinTy::Str => {
@@ -55,6 +55,10 @@ pub enum PlaceKind {
 )]
 pub enum ProjectionElem {
     /// Dereference a shared/mutable reference, a box, or a raw pointer.
+    ///
+    /// Dereferencing a value whose type implements `Deref`/`DerefMut`, e.g. `*my_smart_ptr`, is
+    /// not a projection: this appears as a call to the `deref`/`deref_mut` method, followed by a
+    /// dereference of the returned reference.
     Deref,
     /// Projection from ADTs (variants, structures).
     /// We allow projections to be used as left-values and right-values.
@@ -209,7 +213,18 @@ pub enum CastKind {
 
 /// Binary operations.
 #[derive(
-    Debug, PartialEq, Eq, Copy, Clone, EnumIsA, VariantName, Serialize, Deserialize, Drive, DriveMut,
+    Debug,
+    PartialEq,
+    Eq,
+    Copy,
+    Clone,
+    Hash,
+    EnumIsA,
+    VariantName,
+    Serialize,
+    Deserialize,
+    Drive,
+    DriveMut,
 )]
 #[charon::rename("Binop")]
 pub enum BinOp {
@@ -275,6 +290,7 @@ pub enum Operand {
     Clone,
     PartialEq,
     Eq,
+    Hash,
     EnumIsA,
     EnumAsGetters,
     VariantName,
@@ -303,6 +319,7 @@ pub enum FunId {
     Copy,
     PartialEq,
     Eq,
+    Hash,
     EnumIsA,
     EnumAsGetters,
     VariantName,
@@ -314,6 +331,14 @@ pub enum FunId {
 pub enum BuiltinFunId {
     /// `alloc::boxed::Box::new`
     BoxNew,
+    /// `alloc::rc::Rc::new`
+    RcNew,
+    /// `alloc::sync::Arc::new`
+    ArcNew,
+    /// `<alloc::rc::Rc<T> as Clone>::clone`
+    RcClone,
+    /// `<alloc::sync::Arc<T> as Clone>::clone`
+    ArcClone,
     /// Cast an array as a slice.
     ///
     /// Converted from [UnOp::ArrayToSlice]
@@ -334,10 +359,14 @@ pub enum BuiltinFunId {
     /// - `fn SliceSubSliceMut<T>(&mut [T], usize, usize) -> &mut [T]`
     /// - etc
     Index(BuiltinIndexOp),
+    /// A method of the primitive integer or float types, e.g. `u32::count_ones`. The function
+    /// takes a single type argument, the type of `self`; its signature is the one of the original
+    /// method.
+    PrimitiveMethod(PrimitiveMethod),
 }
 
 /// One of 8 built-in indexing operations.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Drive, DriveMut)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Drive, DriveMut)]
 pub struct BuiltinIndexOp {
     /// Whether this is a slice or array.
     #[drive(skip)]
@@ -352,7 +381,43 @@ pub struct BuiltinIndexOp {
     pub is_range: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, EnumAsGetters, Serialize, Deserialize, Drive, DriveMut)]
+/// The methods of the primitive numeric types that we treat as built-in, instead of calling the
+/// functions of the standard library.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, VariantName, Serialize, Deserialize, Drive, DriveMut,
+)]
+pub enum PrimitiveMethod {
+    /// `count_ones`: the number of ones in the binary representation of an integer.
+    CountOnes,
+    /// `count_zeros`: the number of zeros in the binary representation of an integer.
+    CountZeros,
+    /// `leading_zeros`
+    LeadingZeros,
+    /// `trailing_zeros`
+    TrailingZeros,
+    /// `rotate_left`
+    RotateLeft,
+    /// `rotate_right`
+    RotateRight,
+    /// `swap_bytes`
+    SwapBytes,
+    /// `reverse_bits`
+    ReverseBits,
+    /// `pow`: exponentiation by a `u32`.
+    Pow,
+    /// `abs`, for the signed integers and the floats.
+    Abs,
+    /// `min`, for the floats.
+    Min,
+    /// `max`, for the floats.
+    Max,
+    /// `sqrt`, for the floats.
+    Sqrt,
+}
+
+#[derive(
+    Debug, Clone, PartialEq, Eq, Hash, EnumAsGetters, Serialize, Deserialize, Drive, DriveMut,
+)]
 pub enum FunIdOrTraitMethodRef {
     #[charon::rename("FunId")]
     Fun(FunId),
@@ -363,7 +428,7 @@ pub enum FunIdOrTraitMethodRef {
     Trait(TraitRef, TraitItemName, FunDeclId),
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Drive, DriveMut)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize, Drive, DriveMut)]
 pub struct FnPtr {
     pub func: FunIdOrTraitMethodRef,
     pub generics: GenericArgs,
@@ -399,6 +464,7 @@ pub struct FnPtr {
     Debug,
     PartialEq,
     Eq,
+    Hash,
     Clone,
     VariantName,
     EnumIsA,
@@ -474,7 +540,7 @@ pub enum RawConstantExpr {
     RawMemory(Vec<u8>),
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Drive, DriveMut)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize, Drive, DriveMut)]
 pub struct ConstantExpr {
     pub value: RawConstantExpr,
     pub ty: Ty,
@@ -506,7 +572,8 @@ pub enum Rvalue {
     /// Note that discriminant values have type isize. We also store the identifier
     /// of the type from which we read the discriminant.
     ///
-    /// This case is filtered in [crate::remove_read_discriminant]
+    /// This case is filtered in [crate::remove_read_discriminant], except for the reads introduced
+    /// by [crate::transform::lower_discriminant_checks].
     Discriminant(Place, TypeDeclId),
     /// Creates an aggregate value, like a tuple, a struct or an enum:
     /// ```text
@@ -524,6 +591,11 @@ pub enum Rvalue {
     /// to have a look at expansion of `Bottom` values for explanations about the
     /// other case.
     ///
+    /// The operands of struct and enum variant aggregates are in the order of the fields in the
+    /// type declaration (i.e. indexed by [FieldId]), whatever the order in which the fields were
+    /// written in the source: MIR evaluates the fields in source order into temporaries, then
+    /// builds the aggregate in declaration order.
+    ///
     /// Remark: in case of closures, the aggregated value groups the closure id
     /// together with its state.
     Aggregate(AggregateKind, Vec<Operand>),
@@ -557,6 +629,10 @@ pub enum Rvalue {
     /// rustc introduces a check that the length of the slice is exactly equal
     /// to 1 and that we preserve.
     Len(Place, Ty, Option<ConstGeneric>),
+    /// A call to `core::hint::black_box`: this returns its operand unchanged but acts as an
+    /// optimization barrier.
+    /// Not present in MIR; introduced in [crate::transform::reconstruct_black_box].
+    BlackBox(Operand),
     /// [Repeat(x, n)] creates an array where [x] is copied [n] times.
     ///
     /// We translate this to a function call.
@@ -601,6 +677,7 @@ pub enum AggregateKind {
     /// sense to treat it differently because it has a variable number of fields.
     Array(Ty, ConstGeneric),
     /// Aggregated values for closures group the function id together with its
-    /// state.
+    /// state. The operands are the captured upvars: the upvars captured by reference are
+    /// borrowed with an explicit [Rvalue::Ref] before the construction.
     Closure(FunDeclId, GenericArgs),
 }
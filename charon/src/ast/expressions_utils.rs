@@ -43,6 +43,17 @@ impl Place {
     }
 }
 
+impl Operand {
+    /// The place read by this operand, if it is not a constant. The `as_const` accessor for the
+    /// other case is derived.
+    pub fn as_place(&self) -> Option<&Place> {
+        match self {
+            Operand::Copy(place) | Operand::Move(place) => Some(place),
+            Operand::Const(_) => None,
+        }
+    }
+}
+
 impl Rvalue {
     pub fn unit_value() -> Self {
         Rvalue::Aggregate(
@@ -80,7 +91,7 @@ impl ProjectionElem {
                 use TyKind::*;
                 match ty.kind() {
                     Ref(_, ty, _) | RawPtr(ty, _) => ty.clone(),
-                    Adt(TypeId::Builtin(BuiltinTy::Box), args) => {
+                    Adt(TypeId::Builtin(BuiltinTy::Box | BuiltinTy::Rc | BuiltinTy::Arc), args) => {
                         args.types.get(TypeVarId::new(0)).unwrap().clone()
                     }
                     Adt(..) | TypeVar(_) | Literal(_) | Never | TraitType(..) | DynTrait(_)
@@ -154,3 +165,26 @@ impl ProjectionElem {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operand_accessors() {
+        let u32_ty = TyKind::Literal(LiteralTy::Integer(IntegerTy::U32)).into_ty();
+        let cst = ConstantExpr {
+            value: RawConstantExpr::Literal(Literal::Scalar(ScalarValue::U32(0))),
+            ty: u32_ty.clone(),
+        };
+        let op = Operand::Const(cst.clone());
+        assert_eq!(op.as_const(), Some(&cst));
+        assert_eq!(op.as_place(), None);
+
+        let place = Place::new(VarId::new(1), u32_ty);
+        for op in [Operand::Copy(place.clone()), Operand::Move(place.clone())] {
+            assert_eq!(op.as_place(), Some(&place));
+            assert_eq!(op.as_const(), None);
+        }
+    }
+}
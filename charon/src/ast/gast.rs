@@ -7,6 +7,7 @@ use derive_generic_visitor::{Drive, DriveMut};
 use indexmap::IndexMap;
 use macros::{EnumIsA, EnumToGetters};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 
 /// A variable
 #[derive(Debug, Clone, Serialize, Deserialize, Drive, DriveMut)]
@@ -142,6 +143,10 @@ pub struct FunDecl {
     /// Opaque functions are: external functions, or local functions tagged
     /// as opaque.
     pub body: Result<Body, Opaque>,
+    /// The functions directly called by this function's body. This is computed by the
+    /// `compute_callees` pass; only calls to a statically-known [FunDeclId] are recorded.
+    #[drive(skip)]
+    pub calls: BTreeSet<FunDeclId>,
 }
 
 /// Reference to a function declaration.
@@ -161,21 +166,41 @@ pub struct GlobalDecl {
     pub def_id: GlobalDeclId,
     /// The meta data associated with the declaration.
     pub item_meta: ItemMeta,
+    /// The generic parameters of the global. Globals can be generic when they are associated
+    /// constants of a generic impl or trait, or with the unstable `generic_const_items` feature.
     pub generics: GenericParams,
     pub ty: Ty,
     /// The global kind: "regular" function, trait const declaration, etc.
     pub kind: ItemKind,
+    /// Whether this is a constant, a static or a thread-local static.
+    #[drive(skip)]
+    pub global_kind: GlobalKind,
     /// The initializer function used to compute the initial value for this constant/static. It
     /// uses the same generic parameters as the global.
     #[charon::rename("body")]
     pub init: FunDeclId,
 }
 
+/// The kind of storage of a global.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIsA)]
+pub enum GlobalKind {
+    /// A `const` item, including associated constants.
+    NamedConst,
+    /// A `static` item.
+    Static,
+    /// A `static mut` item.
+    StaticMut,
+    /// A thread-local static, e.g. one defined with `thread_local!`. Each thread gets its own copy
+    /// of the value.
+    ThreadLocal,
+}
+
 /// Reference to a global declaration.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Drive, DriveMut)]
 pub struct GlobalDeclRef {
     #[charon::rename("global_id")]
     pub id: GlobalDeclId,
+    /// The arguments that instantiate the generic parameters of the global.
     #[charon::rename("global_generics")]
     pub generics: GenericArgs,
 }
@@ -298,13 +323,17 @@ pub struct TraitImpl {
 }
 
 /// A function operand is used in function calls.
-/// It either designates a top-level function, or a place in case
-/// we are using function pointers stored in local variables.
+/// It either designates a top-level function, a method called through a vtable, or a place in
+/// case we are using function pointers stored in local variables.
 #[derive(Debug, Clone, Serialize, Deserialize, Drive, DriveMut)]
 #[charon::variants_prefix("FnOp")]
 pub enum FnOperand {
     /// Regular case: call to a top-level function, trait method, etc.
     Regular(FnPtr),
+    /// Dynamic dispatch: call to a method of a trait object, which is looked up in the vtable of
+    /// the receiver (the first argument of the call). The function pointer refers to the trait
+    /// method, with a [TraitRefKind::Dyn] trait reference.
+    Dynamic(FnPtr),
     /// Use of a function pointer stored in a local variable
     Move(Place),
 }
@@ -319,12 +348,32 @@ pub struct Call {
 #[derive(Debug, Clone, Serialize, Deserialize, Drive, DriveMut)]
 pub enum AbortKind {
     /// A built-in panicking function.
-    Panic(Name),
+    Panic {
+        /// The name of the panicking function.
+        name: Name,
+        #[drive(skip)]
+        kind: PanicKind,
+        /// The panic message, if it is a constant string we could recover.
+        #[drive(skip)]
+        message: Option<String>,
+    },
     /// A MIR `Unreachable` terminator corresponds to undefined behavior in the rust abstract
     /// machine.
     UndefinedBehavior,
 }
 
+/// What caused a panic. Note that arithmetic overflows, out-of-bounds accesses, etc. are not
+/// panics from our point of view: they are represented with [Assert] statements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PanicKind {
+    /// An explicit call to `panic!` or a similar macro (`unreachable!`, `todo!`, etc.).
+    Explicit,
+    /// The failure of an `assert_eq!` or `assert_ne!`.
+    AssertFailed,
+    /// The failure of `Option::unwrap`, `Result::expect` and the like.
+    Unwrap,
+}
+
 /// Asserts are special constructs introduced by Rust to perform dynamic
 /// checks, to detect out-of-bounds accesses or divisions by zero for
 /// instance. We eliminate the assertions in [crate::remove_dynamic_checks],
@@ -335,4 +384,28 @@ pub struct Assert {
     pub cond: Operand,
     #[drive(skip)]
     pub expected: bool,
+    /// Why the assertion is there.
+    #[drive(skip)]
+    pub kind: AssertKind,
+}
+
+/// What an [Assert] checks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AssertKind {
+    /// An array/slice bounds check inserted by rustc.
+    BoundsCheck,
+    /// An arithmetic overflow check inserted by rustc.
+    Overflow,
+    /// A check inserted by rustc that the divisor of a division or remainder is not zero.
+    DivisionByZero,
+    /// Another check inserted by rustc. These panic when they fail.
+    Compiler,
+    /// A condition whose failure is undefined behavior, like the argument of the `assume`
+    /// intrinsic.
+    Assume,
+    /// A user-written `assert!` or `debug_assert!`, or more generally a conditional panic.
+    UserAssert {
+        /// The panic message, if it is a constant string we could recover.
+        message: Option<String>,
+    },
 }
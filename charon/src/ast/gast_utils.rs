@@ -1,9 +1,15 @@
 //! Implementations for [crate::gast]
 
 use crate::ast::*;
+use crate::formatter::IntoFormatter;
 use crate::llbc_ast;
+use crate::pretty::FmtWithCtx;
 use crate::ullbc_ast;
 
+use derive_generic_visitor::Visitor;
+use index_vec::Idx;
+use indexmap::IndexSet;
+
 impl FunIdOrTraitMethodRef {
     pub fn mk_builtin(aid: BuiltinFunId) -> Self {
         Self::Fun(FunId::Builtin(aid))
@@ -77,6 +83,107 @@ impl std::ops::Index<VarId> for Locals {
     }
 }
 
+impl<T> GExprBody<T> {
+    /// The local that holds the return value. By convention, this is local 0.
+    pub fn return_local(&self) -> VarId {
+        VarId::new(0)
+    }
+
+    /// The locals that hold the input arguments, in order. By convention, these are the locals
+    /// `1..=arg_count`.
+    pub fn arg_locals(&self) -> impl Iterator<Item = VarId> {
+        (1..=self.locals.arg_count).map(VarId::new)
+    }
+
+    /// Whether this local holds one of the input arguments.
+    pub fn is_arg(&self, v: VarId) -> bool {
+        (1..=self.locals.arg_count).contains(&v.index())
+    }
+}
+
+impl FunDecl {
+    /// A hash of the signature of the function: its generics, inputs, output, unsafety and ABI. It
+    /// doesn't depend on the body nor on the spans, so that it only changes when the signature
+    /// does. The items mentioned in the signature are hashed through their names, so that the
+    /// hash doesn't depend on the order in which the items were registered. The hash function is
+    /// fixed, hence the hash is stable across runs and versions of Rust.
+    pub fn signature_hash(&self, krate: &TranslatedCrate) -> u64 {
+        let mut signature = self.signature.clone();
+        signature.dyn_visit_mut(|span: &mut Span| *span = Span::dummy());
+        let mut ids = CanonicalizeIds::default();
+        signature.drive_mut(&mut ids);
+        let fmt_ctx = krate.into_fmt();
+        let names = ids
+            .ids
+            .iter()
+            .map(|id| match krate.item_name(*id) {
+                Some(name) => name.fmt_with_ctx(&fmt_ctx),
+                None => id.to_string(),
+            })
+            .collect::<Vec<_>>();
+        let data = (&signature, names, &self.item_meta.foreign_abi);
+        fnv1a(&serde_json::to_vec(&data).unwrap())
+    }
+}
+
+/// Renumbers the item ids in their order of first occurrence, to make a value independent of the
+/// order in which the items were registered.
+#[derive(Default, Visitor)]
+struct CanonicalizeIds {
+    /// The original ids, in order of first occurrence.
+    ids: IndexSet<AnyTransId>,
+}
+
+impl CanonicalizeIds {
+    fn canonicalize<Id: Copy + Idx + Into<AnyTransId>>(&mut self, id: &mut Id) {
+        let (index, _) = self.ids.insert_full((*id).into());
+        *id = Id::from_usize(index);
+    }
+}
+
+impl VisitAstMut for CanonicalizeIds {
+    fn enter_type_decl_id(&mut self, id: &mut TypeDeclId) {
+        self.canonicalize(id)
+    }
+    fn enter_fun_decl_id(&mut self, id: &mut FunDeclId) {
+        self.canonicalize(id)
+    }
+    fn enter_global_decl_id(&mut self, id: &mut GlobalDeclId) {
+        self.canonicalize(id)
+    }
+    fn enter_trait_decl_id(&mut self, id: &mut TraitDeclId) {
+        self.canonicalize(id)
+    }
+    fn enter_trait_impl_id(&mut self, id: &mut TraitImplId) {
+        self.canonicalize(id)
+    }
+    fn enter_generic_args(&mut self, args: &mut GenericArgs) {
+        // The target is skipped by the visitor.
+        match &mut args.target {
+            GenericsSource::Item(id) => {
+                let (index, _) = self.ids.insert_full(*id);
+                // Keep the kind of the id.
+                *id = match *id {
+                    AnyTransId::Type(_) => TypeDeclId::from_usize(index).into(),
+                    AnyTransId::Fun(_) => FunDeclId::from_usize(index).into(),
+                    AnyTransId::Global(_) => GlobalDeclId::from_usize(index).into(),
+                    AnyTransId::TraitDecl(_) => TraitDeclId::from_usize(index).into(),
+                    AnyTransId::TraitImpl(_) => TraitImplId::from_usize(index).into(),
+                };
+            }
+            GenericsSource::Method(trait_id, _) => self.canonicalize(trait_id),
+            GenericsSource::Builtin | GenericsSource::Other => {}
+        }
+    }
+}
+
+/// The 64-bit FNV-1a hash of these bytes.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
 impl TraitDecl {
     pub fn methods(&self) -> impl Iterator<Item = &(TraitItemName, Binder<FunDeclRef>)> {
         self.methods.iter()
@@ -86,4 +193,82 @@ impl TraitImpl {
     pub fn methods(&self) -> impl Iterator<Item = &(TraitItemName, Binder<FunDeclRef>)> {
         self.methods.iter()
     }
+
+    /// Whether the two impls could apply to the same types, i.e. they implement the same trait and
+    /// the trait arguments (starting with the self type) are equal up to the instantiation of the
+    /// impl generics. This is an over-approximation: we don't take the where clauses into account,
+    /// and a generic parameter that appears several times is treated as distinct variables.
+    pub fn may_overlap(&self, other: &TraitImpl) -> bool {
+        let (args1, args2) = (&self.impl_trait.generics, &other.impl_trait.generics);
+        self.impl_trait.trait_id == other.impl_trait.trait_id
+            && args1.types.elem_count() == args2.types.elem_count()
+            && args1
+                .types
+                .iter()
+                .zip(args2.types.iter())
+                .all(|(ty1, ty2)| tys_may_unify(ty1, ty2))
+            && args1
+                .const_generics
+                .iter()
+                .zip(args2.const_generics.iter())
+                .all(|(cg1, cg2)| const_generics_may_unify(cg1, cg2))
+    }
+}
+
+/// Whether the two types could be made equal by instantiating their type variables.
+fn tys_may_unify(ty1: &Ty, ty2: &Ty) -> bool {
+    match (ty1.kind(), ty2.kind()) {
+        (TyKind::TypeVar(_), _) | (_, TyKind::TypeVar(_)) => true,
+        // We can't normalize associated types here.
+        (TyKind::TraitType(..), _) | (_, TyKind::TraitType(..)) => true,
+        (TyKind::Adt(id1, args1), TyKind::Adt(id2, args2)) => {
+            id1 == id2
+                && args1.types.elem_count() == args2.types.elem_count()
+                && args1
+                    .types
+                    .iter()
+                    .zip(args2.types.iter())
+                    .all(|(ty1, ty2)| tys_may_unify(ty1, ty2))
+                && args1
+                    .const_generics
+                    .iter()
+                    .zip(args2.const_generics.iter())
+                    .all(|(cg1, cg2)| const_generics_may_unify(cg1, cg2))
+        }
+        (TyKind::Ref(_, ty1, kind1), TyKind::Ref(_, ty2, kind2))
+        | (TyKind::RawPtr(ty1, kind1), TyKind::RawPtr(ty2, kind2)) => {
+            kind1 == kind2 && tys_may_unify(ty1, ty2)
+        }
+        (TyKind::Arrow(sig1), TyKind::Arrow(sig2)) => {
+            let (inputs1, output1) = &sig1.skip_binder;
+            let (inputs2, output2) = &sig2.skip_binder;
+            inputs1.len() == inputs2.len()
+                && inputs1
+                    .iter()
+                    .zip(inputs2)
+                    .all(|(ty1, ty2)| tys_may_unify(ty1, ty2))
+                && tys_may_unify(output1, output2)
+        }
+        // We don't look inside the other types: to stay on the safe side, we assume they unify as
+        // soon as one of them is not fully known.
+        _ => has_type_vars(ty1) || has_type_vars(ty2) || ty1.equivalent_modulo_regions(ty2),
+    }
+}
+
+/// Whether the type mentions type variables or associated types.
+fn has_type_vars(ty: &Ty) -> bool {
+    let mut found = false;
+    ty.dyn_visit(|ty: &Ty| {
+        if let TyKind::TypeVar(_) | TyKind::TraitType(..) = ty.kind() {
+            found = true
+        }
+    });
+    found
+}
+
+fn const_generics_may_unify(cg1: &ConstGeneric, cg2: &ConstGeneric) -> bool {
+    match (cg1, cg2) {
+        (ConstGeneric::Value(v1), ConstGeneric::Value(v2)) => v1 == v2,
+        _ => true,
+    }
 }
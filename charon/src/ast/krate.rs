@@ -1,15 +1,17 @@
 use crate::ast::*;
 use crate::formatter::{FmtCtx, Formatter, IntoFormatter};
 use crate::ids::Vector;
+use crate::pretty::FmtWithCtx;
 use crate::reorder_decls::DeclarationsGroups;
-use derive_generic_visitor::{ControlFlow, Drive, DriveMut};
+use derive_generic_visitor::{ControlFlow, Drive, DriveMut, Visitor};
 use index_vec::Idx;
 use indexmap::IndexSet;
+use itertools::Itertools;
 use macros::{EnumAsGetters, EnumIsA, VariantIndexArity, VariantName};
-use serde::{Deserialize, Serialize};
-use serde_map_to_array::HashMapToArray;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_map_to_array::{BTreeMapToArray, HashMapToArray};
 use std::cmp::{Ord, PartialOrd};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
 
 generate_index_type!(FunDeclId, "Fun");
@@ -120,7 +122,10 @@ pub struct TranslatedCrate {
     /// failed to translate.
     /// Invariant: after translation, any existing `AnyTransId` must have an associated name, even
     /// if the corresponding item wasn't translated.
-    #[serde(with = "HashMapToArray::<AnyTransId, Name>")]
+    #[serde(
+        serialize_with = "serialize_item_names",
+        deserialize_with = "HashMapToArray::<AnyTransId, Name>::deserialize"
+    )]
     pub item_names: HashMap<AnyTransId, Name>,
 
     /// The translated files.
@@ -141,6 +146,17 @@ pub struct TranslatedCrate {
     pub ordered_decls: Option<DeclarationsGroups>,
 }
 
+/// Serialize the item names sorted by id, so that the output doesn't depend on the iteration order
+/// of the `HashMap`.
+fn serialize_item_names<S: Serializer>(
+    item_names: &HashMap<AnyTransId, Name>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let item_names: BTreeMap<AnyTransId, &Name> =
+        item_names.iter().map(|(id, name)| (*id, name)).collect();
+    BTreeMapToArray::<AnyTransId, &Name>::serialize(&item_names, serializer)
+}
+
 impl TranslatedCrate {
     pub fn item_name(&self, trans_id: impl Into<AnyTransId>) -> Option<&Name> {
         self.item_names.get(&trans_id.into())
@@ -187,6 +203,356 @@ impl TranslatedCrate {
             .chain(self.trait_decls.iter_mut().map(AnyTransItemMut::TraitDecl))
             .chain(self.trait_impls.iter_mut().map(AnyTransItemMut::TraitImpl))
     }
+
+    /// The items that carry the given attribute, e.g. `clippy::foo` or `charon::opaque`.
+    pub fn items_with_attr(&self, attr: &str) -> Vec<AnyTransId> {
+        self.all_items()
+            .filter(|item| {
+                item.item_meta()
+                    .attr_info
+                    .attributes
+                    .iter()
+                    .any(|a| a.has_path(attr))
+            })
+            .map(|item| item.id())
+            .collect()
+    }
+
+    /// Resolve the associated type `name` of the given trait reference, when the implementation
+    /// is known: for a reference to a top-level impl, this is the impl's definition of the type,
+    /// instantiated with the arguments of the reference. Returns `None` when the implementation is
+    /// not known, e.g. for references to clauses.
+    pub fn resolve_assoc_type(&self, tr: &TraitRef, name: &TraitItemName) -> Option<Ty> {
+        match &tr.kind {
+            TraitRefKind::TraitImpl(impl_id, args) => {
+                let timpl = self.trait_impls.get(*impl_id)?;
+                let (_, ty) = timpl.types.iter().find(|(n, _)| n == name)?;
+                Some(ty.clone().substitute(args))
+            }
+            TraitRefKind::BuiltinOrAuto { types, .. } => {
+                let (_, ty) = types.iter().find(|(n, _)| n == name)?;
+                Some(ty.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// The impls of the given trait.
+    pub fn impls_for_trait(&self, trait_id: TraitDeclId) -> Vec<TraitImplId> {
+        self.trait_impls
+            .iter()
+            .filter(|timpl| timpl.impl_trait.trait_id == trait_id)
+            .map(|timpl| timpl.def_id)
+            .collect()
+    }
+
+    /// The ADTs that have an impl of `Copy` for all their instances, e.g.
+    /// `impl<T: Copy> Copy for Foo<T>`. See [Ty::is_copy].
+    pub fn copy_adts(&self) -> HashSet<TypeDeclId> {
+        static COPY_TRAIT: &[&str] = &["core", "marker", "Copy"];
+        self.trait_impls
+            .iter()
+            .filter(|timpl| {
+                self.item_name(timpl.impl_trait.trait_id)
+                    .is_some_and(|name| name.equals_ref_name(COPY_TRAIT))
+            })
+            .filter_map(|timpl| {
+                let self_ty = timpl.impl_trait.generics.types.get(TypeVarId::ZERO)?;
+                let TyKind::Adt(TypeId::Adt(id), args) = self_ty.kind() else {
+                    return None;
+                };
+                (args.types.iter().all(|ty| ty.kind().is_type_var())
+                    && args.types.iter().all_unique())
+                .then_some(*id)
+            })
+            .collect()
+    }
+
+    /// Whether the type is zero-sized. This is the case of the unit type and the tuples, arrays
+    /// and structs whose components are zero-sized, as well as the arrays of length 0. We are
+    /// conservative: the other types, e.g. the type variables and enums, are never considered
+    /// zero-sized.
+    pub fn ty_is_zst(&self, ty: &Ty) -> bool {
+        match ty.kind() {
+            TyKind::Adt(TypeId::Tuple, args) => args.types.iter().all(|ty| self.ty_is_zst(ty)),
+            TyKind::Adt(TypeId::Builtin(BuiltinTy::Array), args) => {
+                let is_empty = matches!(
+                    args.const_generics.get(ConstGenericVarId::ZERO),
+                    Some(ConstGeneric::Value(Literal::Scalar(len))) if len.to_bits() == 0
+                );
+                is_empty || self.ty_is_zst(&args.types[TypeVarId::ZERO])
+            }
+            TyKind::Adt(TypeId::Adt(id), args) => match self.type_decls.get(*id).map(|d| &d.kind) {
+                Some(TypeDeclKind::Struct(fields)) => fields
+                    .iter()
+                    .all(|field| self.ty_is_zst(&field.ty.clone().substitute(args))),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Whether the type has no values. This is the case of `!`, of the enums whose variants are
+    /// all uninhabited, and of the tuples and structs that have an uninhabited component. We are
+    /// conservative: the other types, e.g. the type variables and opaque types, are considered
+    /// inhabited.
+    pub fn ty_is_uninhabited(&self, ty: &Ty) -> bool {
+        match ty.kind() {
+            TyKind::Never => true,
+            TyKind::Adt(TypeId::Tuple, args) => {
+                args.types.iter().any(|ty| self.ty_is_uninhabited(ty))
+            }
+            TyKind::Adt(TypeId::Adt(id), args) => match self.type_decls.get(*id).map(|d| &d.kind) {
+                Some(TypeDeclKind::Struct(fields)) => self.fields_are_uninhabited(fields, args),
+                Some(TypeDeclKind::Enum(variants)) => variants
+                    .iter()
+                    .all(|variant| self.variant_is_uninhabited(variant, args)),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Whether the variant of an enum instantiated with `args` can't be built, because one of its
+    /// fields is uninhabited. See [Self::ty_is_uninhabited].
+    pub fn variant_is_uninhabited(&self, variant: &Variant, args: &GenericArgs) -> bool {
+        self.fields_are_uninhabited(&variant.fields, args)
+    }
+
+    fn fields_are_uninhabited(&self, fields: &Vector<FieldId, Field>, args: &GenericArgs) -> bool {
+        fields
+            .iter()
+            .any(|field| self.ty_is_uninhabited(&field.ty.clone().substitute(args)))
+    }
+}
+
+/// The reasons why [TranslatedCrate::merge] can fail.
+#[derive(Debug, Clone)]
+pub enum MergeError {
+    /// There was no crate to merge.
+    NoCrates,
+    /// Two crates define an item with the same name, and both definitions have contents.
+    DuplicateItem(Name),
+}
+
+/// Visitor that renumbers the item ids and file ids of an item moved to another crate.
+#[derive(Visitor)]
+struct IdRemapping {
+    ids: HashMap<AnyTransId, AnyTransId>,
+    file_offset: usize,
+}
+
+impl IdRemapping {
+    fn remap<Id: Copy + Into<AnyTransId> + TryFrom<AnyTransId>>(&self, id: &mut Id) {
+        if let Some(new_id) = self.ids.get(&(*id).into())
+            && let Ok(new_id) = Id::try_from(*new_id)
+        {
+            *id = new_id;
+        }
+    }
+
+    fn remap_file(&self, span: &mut RawSpan) {
+        span.file_id = FileId::from_usize(span.file_id.index() + self.file_offset);
+    }
+}
+
+impl VisitAstMut for IdRemapping {
+    fn enter_type_decl_id(&mut self, id: &mut TypeDeclId) {
+        self.remap(id)
+    }
+    fn enter_fun_decl_id(&mut self, id: &mut FunDeclId) {
+        self.remap(id)
+    }
+    fn enter_global_decl_id(&mut self, id: &mut GlobalDeclId) {
+        self.remap(id)
+    }
+    fn enter_trait_decl_id(&mut self, id: &mut TraitDeclId) {
+        self.remap(id)
+    }
+    fn enter_trait_impl_id(&mut self, id: &mut TraitImplId) {
+        self.remap(id)
+    }
+    fn enter_generic_args(&mut self, args: &mut GenericArgs) {
+        // The target is skipped by the visitor.
+        match &mut args.target {
+            GenericsSource::Item(id) => self.remap(id),
+            GenericsSource::Method(trait_id, _) => self.remap(trait_id),
+            GenericsSource::Builtin | GenericsSource::Other => {}
+        }
+    }
+    fn enter_span(&mut self, span: &mut Span) {
+        self.remap_file(&mut span.span);
+        if let Some(span) = &mut span.generated_from_span {
+            self.remap_file(span);
+        }
+    }
+}
+
+/// The key used to identify the same item across crates: its kind and its full name. The `impl`
+/// blocks in the name are printed with their self type and trait reference, which mention the
+/// other items by name, hence the key doesn't depend on the ids of the crate. Names that go
+/// through a trait impl which wasn't translated don't get a key, since we can't print that impl:
+/// the corresponding items are never deduplicated.
+fn merge_key(krate: &TranslatedCrate, id: AnyTransId) -> Option<String> {
+    let name = krate.item_names.get(&id)?;
+    let mentions_missing_impl = name.name.iter().any(|elem| match elem {
+        PathElem::Impl(ImplElem::Trait(impl_id), _) => krate.trait_impls.get(*impl_id).is_none(),
+        _ => false,
+    });
+    if mentions_missing_impl {
+        return None;
+    }
+    let name = name.fmt_with_ctx(&krate.into_fmt());
+    Some(format!("{}:{name}", id.variant_name()))
+}
+
+impl TranslatedCrate {
+    /// Combine several translated crates into a single one. The item ids are renumbered, and the
+    /// items of the different crates which have the same name are identified: this way an item
+    /// declared without contents in one crate (e.g. an `extern` function, or an item for which
+    /// translation failed) resolves to its definition in another crate. Identical external
+    /// items are kept once; two local definitions with the same name are an error.
+    ///
+    /// The crate name and options are taken from the first crate. The declaration groups are not
+    /// computed: `ordered_decls` is `None` in the result.
+    pub fn merge(crates: Vec<TranslatedCrate>) -> Result<TranslatedCrate, MergeError> {
+        let mut crates = crates.into_iter();
+        let first = crates.next().ok_or(MergeError::NoCrates)?;
+        let mut merged = TranslatedCrate {
+            crate_name: first.crate_name.clone(),
+            real_crate_name: first.real_crate_name.clone(),
+            options: first.options.clone(),
+            ..Default::default()
+        };
+        let mut by_key = HashMap::new();
+        for krate in std::iter::once(first).chain(crates) {
+            merged.merge_crate(krate, &mut by_key)?;
+        }
+        Ok(merged)
+    }
+
+    /// Whether the item has contents, i.e. is more than a declaration.
+    fn item_has_contents(&self, id: AnyTransId) -> bool {
+        match self.get_item(id) {
+            Some(AnyTransItem::Fun(decl)) => decl.body.is_ok(),
+            Some(AnyTransItem::Type(decl)) => !matches!(decl.kind, TypeDeclKind::Opaque),
+            Some(_) => true,
+            None => false,
+        }
+    }
+
+    fn remove_item(&mut self, id: AnyTransId) {
+        match id {
+            AnyTransId::Type(id) => drop(self.type_decls.remove(id)),
+            AnyTransId::Fun(id) => drop(self.fun_decls.remove(id)),
+            AnyTransId::Global(id) => drop(self.global_decls.remove(id)),
+            AnyTransId::TraitDecl(id) => drop(self.trait_decls.remove(id)),
+            AnyTransId::TraitImpl(id) => drop(self.trait_impls.remove(id)),
+        }
+    }
+
+    fn reserve_slot(&mut self, id: AnyTransId) -> AnyTransId {
+        match id {
+            AnyTransId::Type(_) => self.type_decls.reserve_slot().into(),
+            AnyTransId::Fun(_) => self.fun_decls.reserve_slot().into(),
+            AnyTransId::Global(_) => self.global_decls.reserve_slot().into(),
+            AnyTransId::TraitDecl(_) => self.trait_decls.reserve_slot().into(),
+            AnyTransId::TraitImpl(_) => self.trait_impls.reserve_slot().into(),
+        }
+    }
+
+    /// Add the items of `krate` to `self`. `by_key` maps the [merge_key] of the items added so
+    /// far to their id.
+    fn merge_crate(
+        &mut self,
+        krate: TranslatedCrate,
+        by_key: &mut HashMap<String, AnyTransId>,
+    ) -> Result<(), MergeError> {
+        let mut remapping = IdRemapping {
+            ids: HashMap::new(),
+            file_offset: self.files.next_id().index(),
+        };
+        // The items of `krate` which we keep.
+        let mut kept = HashSet::new();
+        for &id in &krate.all_ids {
+            let key = merge_key(&krate, id);
+            if let Some(key) = &key
+                && let Some(&existing_id) = by_key.get(key)
+            {
+                remapping.ids.insert(id, existing_id);
+                if !krate.item_has_contents(id) {
+                    continue;
+                } else if !self.item_has_contents(existing_id) {
+                    self.remove_item(existing_id);
+                    kept.insert(id);
+                } else if krate
+                    .get_item(id)
+                    .is_some_and(|item| !item.item_meta().is_local)
+                {
+                    continue;
+                } else {
+                    return Err(MergeError::DuplicateItem(krate.item_names[&id].clone()));
+                }
+            } else {
+                let new_id = self.reserve_slot(id);
+                self.all_ids.insert(new_id);
+                if let Some(key) = key {
+                    by_key.insert(key, new_id);
+                }
+                remapping.ids.insert(id, new_id);
+                kept.insert(id);
+            }
+        }
+
+        for file in krate.files {
+            self.files.push(file);
+        }
+        for &id in &kept {
+            let new_id = remapping.ids[&id];
+            if let Some(name) = krate.item_names.get(&id) {
+                let mut name = name.clone();
+                name.drive_mut(&mut remapping);
+                self.item_names.insert(new_id, name);
+            }
+        }
+
+        macro_rules! move_decls {
+            ($decls:ident $(, |$decl:ident| $fixup:expr)?) => {
+                for (id, mut decl) in krate.$decls.into_iter_indexed() {
+                    if !kept.contains(&AnyTransId::from(id)) {
+                        continue;
+                    }
+                    decl.drive_mut(&mut remapping);
+                    decl.def_id = remapping.ids[&AnyTransId::from(id)].try_into().unwrap();
+                    $({
+                        let $decl = &mut decl;
+                        $fixup;
+                    })?
+                    self.$decls.set_slot(decl.def_id, decl);
+                }
+            };
+        }
+        // `drop_impl` and `calls` are skipped by the visitor.
+        move_decls!(
+            type_decls,
+            |decl| if let Some(drop_impl) = &mut decl.drop_impl {
+                remapping.remap(drop_impl)
+            }
+        );
+        move_decls!(fun_decls, |decl| {
+            decl.calls = std::mem::take(&mut decl.calls)
+                .into_iter()
+                .map(|mut id| {
+                    remapping.remap(&mut id);
+                    id
+                })
+                .collect()
+        });
+        move_decls!(global_decls);
+        move_decls!(trait_decls);
+        move_decls!(trait_impls);
+        Ok(())
+    }
 }
 
 impl<'ctx> AnyTransItem<'ctx> {
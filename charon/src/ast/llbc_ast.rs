@@ -49,10 +49,47 @@ pub enum RawStatement {
     Nop,
     Switch(Switch),
     Loop(Block),
+    /// A `let`-else, e.g. `let Some(x) = opt else { return };`. Gives the place we match on, the
+    /// variant it must have, the block which binds the variables of the pattern, and the `else`
+    /// block. If the place has the given variant, we run the bindings then continue with the next
+    /// statement; otherwise we run the `else` block, which always diverges.
+    ///
+    /// This is introduced by [crate::transform::reconstruct_let_else] when
+    /// `--reconstruct-let-else` is set.
+    LetElse(Place, VariantId, Block, Block),
+    /// A `for` loop, e.g. `for x in iter { ... }`. Gives the iterator, the place which receives
+    /// the items and the body. Each iteration calls `Iterator::next` on the iterator: if it returns
+    /// `Some(x)`, we assign `x` to the item place and run the body, otherwise we exit the loop.
+    /// Like for [RawStatement::Loop], `break 0` and `continue 0` in the body refer to this loop.
+    ///
+    /// This is introduced by [crate::transform::reconstruct_for_loops] when
+    /// `--reconstruct-for-loops` is set.
+    ForLoop(Place, Place, Block),
+    /// An `if let` chain, e.g. `if let Some(x) = a && let Ok(y) = b { ... } else { ... }`. Gives
+    /// the conditions and the `then` block. We check the conditions in order: if all of them hold
+    /// we run the `then` block, otherwise we run the `else` block of the first one which fails.
+    ///
+    /// This is introduced by [crate::transform::reconstruct_let_chains] when
+    /// `--reconstruct-let-chains` is set.
+    IfLet(Vec<LetCondition>, Block),
     #[drive(skip)]
     Error(String),
 }
 
+/// A condition of an `if let` chain, e.g. `let Some(x) = a`.
+#[derive(Debug, Clone, Serialize, Deserialize, Drive, DriveMut)]
+pub struct LetCondition {
+    /// The place we match on.
+    pub scrutinee: Place,
+    /// The variant the place must have.
+    pub variant: VariantId,
+    /// The block which binds the variables of the pattern. It also computes the scrutinee of the
+    /// next condition, if any.
+    pub bindings: Block,
+    /// The block we run if the place doesn't have the expected variant.
+    pub else_block: Block,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Drive, DriveMut)]
 pub struct Statement {
     pub span: Span,
@@ -173,6 +173,20 @@ impl Block {
     }
 }
 
+impl ExprBody {
+    /// Apply a function to all the statements of the body, including the nested ones, in a
+    /// top-down manner.
+    pub fn visit_statements<F: FnMut(&Statement)>(&self, mut f: F) {
+        self.body.dyn_visit_in_body(|st: &Statement| f(st));
+    }
+
+    /// Apply a function to all the statements of the body, including the nested ones, in a
+    /// top-down manner.
+    pub fn visit_statements_mut<F: FnMut(&mut Statement)>(&mut self, f: F) {
+        self.body.visit_statements(f);
+    }
+}
+
 /// Small visitor to visit statements and blocks.
 #[derive(Visitor)]
 pub struct BlockVisitor<F: FnMut(&mut Block), G: FnMut(&mut Statement)> {
@@ -193,7 +207,9 @@ impl<F: FnMut(&mut Block), G: FnMut(&mut Statement)> VisitBodyMut for BlockVisit
     fn exit_llbc_block(&mut self, x: &mut Block) {
         (self.exit_blk)(x)
     }
-    fn enter_llbc_statement(&mut self, x: &mut Statement) {
-        (self.enter_stmt)(x)
+    fn visit_llbc_statement(&mut self, x: &mut Statement) -> ControlFlow<Self::Break> {
+        (self.enter_stmt)(x);
+        // Statements can be nested arbitrarily deep.
+        crate::common::ensure_sufficient_stack(|| self.visit_inner(x))
     }
 }
@@ -207,6 +207,9 @@ pub enum ItemOpacity {
 pub struct ItemMeta {
     pub name: Name,
     pub span: Span,
+    /// The span of the item's name, e.g. the `foo` in `fn foo() {}`. Equal to `span` for items
+    /// that don't have a name in the source, like impl blocks or closures.
+    pub name_span: Span,
     /// The source code that corresponds to this item.
     #[drive(skip)]
     pub source_text: Option<String>,
@@ -226,6 +229,38 @@ pub struct ItemMeta {
     #[charon::opaque]
     #[drive(skip)]
     pub opacity: ItemOpacity,
+    /// Whether the item has a `#[no_mangle]` attribute, i.e. its symbol is its plain name.
+    #[drive(skip)]
+    pub no_mangle: bool,
+    /// The symbol name given by an `#[export_name = "..."]` attribute, if any.
+    #[drive(skip)]
+    pub export_name: Option<String>,
+    /// Whether the item has a `#[must_use]` attribute.
+    #[drive(skip)]
+    pub must_use: bool,
+    /// Whether the item has a `#[cold]` attribute, i.e. it is unlikely to be called.
+    #[drive(skip)]
+    pub cold: bool,
+    /// Whether the item has a `#[non_exhaustive]` attribute. For an enum, this means that matches
+    /// on it from other crates must have a wildcard arm; for a struct, that it can't be built
+    /// with a struct expression from other crates.
+    #[drive(skip)]
+    pub non_exhaustive: bool,
+    /// Whether the item was generated by a `#[derive(...)]`, i.e. it is an impl marked
+    /// `#[automatically_derived]` or an item of such an impl.
+    #[drive(skip)]
+    pub is_derived: bool,
+    /// Whether the item is declared in an `extern { .. }` block, i.e. it is implemented outside of
+    /// Rust. Such items have no body.
+    #[drive(skip)]
+    pub is_foreign: bool,
+    /// The ABI of a foreign function, e.g. `"C"`. `None` for the other items, including foreign
+    /// statics.
+    #[drive(skip)]
+    pub foreign_abi: Option<String>,
+    /// If the item was generated by a macro, e.g. a `macro_rules!`, the span of the (outermost)
+    /// macro invocation that produced it.
+    pub from_macro: Option<Span>,
 }
 
 /// A filename.
@@ -222,6 +222,23 @@ impl Attribute {
         };
         Ok(Some(parsed))
     }
+
+    /// Whether this attribute is written with the given path, e.g. `clippy::foo` or
+    /// `charon::opaque`. Doc comments have no path.
+    pub fn has_path(&self, path: &str) -> bool {
+        let attr_name = match self {
+            Self::Unknown(raw_attr) => return raw_attr.path == path,
+            Self::DocComment(_) => return false,
+            Self::Opaque => "opaque",
+            Self::Rename(_) => "rename",
+            Self::VariantsPrefix(_) => "variants_prefix",
+            Self::VariantsSuffix(_) => "variants_suffix",
+        };
+        matches!(
+            path.split_once("::"),
+            Some(("charon" | "aeneas", name)) if name == attr_name
+        )
+    }
 }
 
 impl ItemOpacity {
@@ -14,6 +14,9 @@ generate_index_type!(Disambiguator);
 pub enum PathElem {
     Ident(#[drive(skip)] String, Disambiguator),
     Impl(ImplElem, Disambiguator),
+    /// An instance of a generic item created by monomorphization: the instance of `foo` at
+    /// `u32` is named `foo::<u32>`, i.e. the name of `foo` followed by this element.
+    Monomorphized(Box<GenericArgs>),
 }
 
 /// There are two kinds of `impl` blocks:
@@ -8,7 +8,7 @@ impl PathElem {
     fn equals_ident(&self, id: &str) -> bool {
         match self {
             PathElem::Ident(s, d) => s == id && d.is_zero(),
-            PathElem::Impl(..) => false,
+            PathElem::Impl(..) | PathElem::Monomorphized(..) => false,
         }
     }
 }
@@ -55,4 +55,21 @@ impl Name {
     pub fn equals_ref_name(&self, ref_name: &[&str]) -> bool {
         self.compare_with_ref_name(true, ref_name)
     }
+
+    /// Check if the name starts with the given identifiers. Like [Self::compare_with_ref_name],
+    /// this skips the `impl` path elements.
+    pub fn starts_with(&self, segments: &[&str]) -> bool {
+        self.compare_with_ref_name(false, segments)
+    }
+
+    /// Check if the name ends with the given identifiers, e.g. `["Iterator", "next"]`. This skips
+    /// the `impl` path elements.
+    pub fn ends_with(&self, segments: &[&str]) -> bool {
+        let name: Vec<&PathElem> = self.name.iter().filter(|e| e.is_ident()).collect();
+        name.len() >= segments.len()
+            && name[name.len() - segments.len()..]
+                .iter()
+                .zip(segments)
+                .all(|(elem, segment)| elem.equals_ident(segment))
+    }
 }
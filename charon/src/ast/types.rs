@@ -365,6 +365,26 @@ pub struct TypeDecl {
     pub generics: GenericParams,
     /// The type kind: enum, struct, or opaque.
     pub kind: TypeDeclKind,
+    /// The alignment in bytes requested with `#[repr(align(N))]`, if any.
+    #[drive(skip)]
+    pub align: Option<u64>,
+    /// The `impl Drop` of this type, if any. Dropping a value of a type with such an impl runs
+    /// its `drop` method. This is only filled for the types of the current crate, and is not
+    /// counted as a dependency of the type.
+    #[drive(skip)]
+    pub drop_impl: Option<TraitImplId>,
+    /// The lanes of the type, if it is a `#[repr(simd)]` vector type.
+    pub simd: Option<SimdRepr>,
+}
+
+/// The layout of a `#[repr(simd)]` type, e.g. `struct f32x4([f32; 4])`: a vector of `lanes`
+/// elements of type `elem_ty`.
+#[derive(Debug, Clone, Serialize, Deserialize, Drive, DriveMut)]
+pub struct SimdRepr {
+    pub elem_ty: Ty,
+    /// The number of lanes. This is a const generic variable for types like
+    /// `struct Simd<T, const N: usize>([T; N])`.
+    pub lanes: ConstGeneric,
 }
 
 generate_index_type!(VariantId, "Variant");
@@ -398,6 +418,10 @@ pub struct Variant {
     #[drive(skip)]
     pub name: String,
     pub fields: Vector<FieldId, Field>,
+    /// Whether the variant has a `#[non_exhaustive]` attribute, i.e. it may get more fields in
+    /// the future.
+    #[drive(skip)]
+    pub non_exhaustive: bool,
     /// The discriminant used at runtime. This is used in `remove_read_discriminant` to match up
     /// `SwitchInt` targets with the corresponding `Variant`.
     pub discriminant: ScalarValue,
@@ -585,6 +609,21 @@ pub enum ConstGeneric {
     Var(ConstGenericDbVar),
     /// A concrete value
     Value(Literal),
+    /// An arithmetic expression over const generics, e.g. the `N * M` in `[u8; N * M]` (this
+    /// requires the `generic_const_exprs` feature). Gives the operation and its operands.
+    App(ConstGenericOp, Vec<ConstGeneric>),
+}
+
+/// An operation in an arithmetic const generic expression.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Drive, DriveMut, Hash)]
+#[charon::variants_prefix("Cg")]
+pub enum ConstGenericOp {
+    /// A binary operation, with two operands.
+    BinOp(BinOp),
+    /// Arithmetic negation, with one operand.
+    Neg,
+    /// Bitwise or boolean negation, with one operand.
+    Not,
 }
 
 /// A type.
@@ -731,6 +770,10 @@ pub enum TyKind {
 pub enum BuiltinTy {
     /// Boxes are de facto a primitive type.
     Box,
+    /// `alloc::rc::Rc`: we treat it like `Box`, with shared ownership.
+    Rc,
+    /// `alloc::sync::Arc`: we treat it like `Box`, with shared ownership.
+    Arc,
     /// Primitive type
     Array,
     /// Primitive type
@@ -775,7 +818,8 @@ pub struct FunSig {
     ///
     /// Importantly: if the signature is for a closure, then:
     /// - the type and const generic params actually come from the parent function
-    ///   (the function in which the closure is defined)
+    ///   (the function in which the closure is defined); for nested closures, this is the
+    ///   outermost enclosing function
     /// - the region variables are local to the closure
     #[drive(skip)]
     pub is_closure: bool,
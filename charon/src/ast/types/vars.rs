@@ -129,6 +129,8 @@ pub struct ConstGenericVar {
     pub name: String,
     /// Type of the const generic
     pub ty: LiteralTy,
+    /// The default value of the const generic, if any (e.g. `4` in `const N: usize = 4`).
+    pub default: Option<ConstGeneric>,
 }
 
 /// A trait predicate in a signature, of the form `Type: Trait<Args>`. This functions like a
@@ -140,9 +142,12 @@ pub struct TraitClause {
     // TODO: does not need to be an option.
     pub span: Option<Span>,
     /// Where the predicate was written, relative to the item that requires it.
-    #[charon::opaque]
     #[drive(skip)]
     pub origin: PredicateOrigin,
+    /// Whether this is a `~const` or `const` bound, as allowed by the unstable `const_trait_impl`
+    /// feature.
+    #[drive(skip)]
+    pub is_const: bool,
     /// The trait that is implemented.
     #[charon::rename("trait")]
     pub trait_: PolyTraitDeclRef,
@@ -117,66 +117,69 @@ impl<T> Binder<T> {
     }
 }
 
-impl<T: AstVisitable> Binder<Binder<T>> {
-    /// Flatten two levels of binders into a single one.
-    pub fn flatten(self) -> Binder<T> {
-        #[derive(Visitor)]
-        struct FlattenVisitor<'a> {
-            shift_by: &'a GenericParams,
-            binder_depth: DeBruijnId,
+/// Visitor used to merge a binder into its parent binder. Visiting starts inside the inner binder:
+/// the variables bound by the inner binder get shifted to come after the ones of `shift_by`, and
+/// the variables bound further out lose one level of binding.
+#[derive(Visitor)]
+struct FlattenVisitor<'a> {
+    shift_by: &'a GenericParams,
+    binder_depth: DeBruijnId,
+}
+impl VisitAstMut for FlattenVisitor<'_> {
+    fn enter_region_binder<T: AstVisitable>(&mut self, _: &mut RegionBinder<T>) {
+        self.binder_depth = self.binder_depth.incr()
+    }
+    fn exit_region_binder<T: AstVisitable>(&mut self, _: &mut RegionBinder<T>) {
+        self.binder_depth = self.binder_depth.decr()
+    }
+    fn enter_binder<T: AstVisitable>(&mut self, _: &mut Binder<T>) {
+        self.binder_depth = self.binder_depth.incr()
+    }
+    fn exit_binder<T: AstVisitable>(&mut self, _: &mut Binder<T>) {
+        self.binder_depth = self.binder_depth.decr()
+    }
+    fn enter_de_bruijn_id(&mut self, db_id: &mut DeBruijnId) {
+        if *db_id > self.binder_depth {
+            // We started visiting at the inner binder, so in this branch we're either
+            // mentioning the outer binder or a binder further beyond. Either way we
+            // decrease the depth; variables that point to the outer binder don't have to
+            // be shifted.
+            *db_id = db_id.decr();
         }
-        impl VisitAstMut for FlattenVisitor<'_> {
-            fn enter_region_binder<T: AstVisitable>(&mut self, _: &mut RegionBinder<T>) {
-                self.binder_depth = self.binder_depth.incr()
-            }
-            fn exit_region_binder<T: AstVisitable>(&mut self, _: &mut RegionBinder<T>) {
-                self.binder_depth = self.binder_depth.decr()
-            }
-            fn enter_binder<T: AstVisitable>(&mut self, _: &mut Binder<T>) {
-                self.binder_depth = self.binder_depth.incr()
-            }
-            fn exit_binder<T: AstVisitable>(&mut self, _: &mut Binder<T>) {
-                self.binder_depth = self.binder_depth.decr()
-            }
-            fn enter_de_bruijn_id(&mut self, db_id: &mut DeBruijnId) {
-                if *db_id > self.binder_depth {
-                    // We started visiting at the inner binder, so in this branch we're either
-                    // mentioning the outer binder or a binder further beyond. Either way we
-                    // decrease the depth; variables that point to the outer binder don't have to
-                    // be shifted.
-                    *db_id = db_id.decr();
-                }
-            }
-            fn enter_region(&mut self, x: &mut Region) {
-                if let Region::Var(var) = x
-                    && let Some(id) = var.bound_at_depth_mut(self.binder_depth)
-                {
-                    *id += self.shift_by.regions.slot_count();
-                }
-            }
-            fn enter_ty_kind(&mut self, x: &mut TyKind) {
-                if let TyKind::TypeVar(var) = x
-                    && let Some(id) = var.bound_at_depth_mut(self.binder_depth)
-                {
-                    *id += self.shift_by.types.slot_count();
-                }
-            }
-            fn enter_const_generic(&mut self, x: &mut ConstGeneric) {
-                if let ConstGeneric::Var(var) = x
-                    && let Some(id) = var.bound_at_depth_mut(self.binder_depth)
-                {
-                    *id += self.shift_by.const_generics.slot_count();
-                }
-            }
-            fn enter_trait_ref_kind(&mut self, x: &mut TraitRefKind) {
-                if let TraitRefKind::Clause(var) = x
-                    && let Some(id) = var.bound_at_depth_mut(self.binder_depth)
-                {
-                    *id += self.shift_by.trait_clauses.slot_count();
-                }
-            }
+    }
+    fn enter_region(&mut self, x: &mut Region) {
+        if let Region::Var(var) = x
+            && let Some(id) = var.bound_at_depth_mut(self.binder_depth)
+        {
+            *id += self.shift_by.regions.slot_count();
         }
+    }
+    fn enter_ty_kind(&mut self, x: &mut TyKind) {
+        if let TyKind::TypeVar(var) = x
+            && let Some(id) = var.bound_at_depth_mut(self.binder_depth)
+        {
+            *id += self.shift_by.types.slot_count();
+        }
+    }
+    fn enter_const_generic(&mut self, x: &mut ConstGeneric) {
+        if let ConstGeneric::Var(var) = x
+            && let Some(id) = var.bound_at_depth_mut(self.binder_depth)
+        {
+            *id += self.shift_by.const_generics.slot_count();
+        }
+    }
+    fn enter_trait_ref_kind(&mut self, x: &mut TraitRefKind) {
+        if let TraitRefKind::Clause(var) = x
+            && let Some(id) = var.bound_at_depth_mut(self.binder_depth)
+        {
+            *id += self.shift_by.trait_clauses.slot_count();
+        }
+    }
+}
 
+impl<T: AstVisitable> Binder<Binder<T>> {
+    /// Flatten two levels of binders into a single one.
+    pub fn flatten(self) -> Binder<T> {
         // We will concatenate both sets of params.
         let mut outer_params = self.params;
 
@@ -263,6 +266,44 @@ impl<T> RegionBinder<T> {
         }
     }
 
+    /// Move the binder under `by` additional binders, shifting the variables bound outside of it.
+    pub fn shift_in(&mut self, by: usize)
+    where
+        T: AstVisitable,
+    {
+        let by = DeBruijnId::new(by);
+        let Continue(()) = self.visit_db_id::<Infallible>(|id| {
+            *id = id.plus(by);
+            Continue(())
+        });
+    }
+
+    /// Merge `inner`, which is a binder found inside of `self`, with `self`. The resulting binder
+    /// binds the regions of `self` followed by the regions of `inner`.
+    pub fn merge_with<U>(self, inner: RegionBinder<U>) -> RegionBinder<(T, U)>
+    where
+        U: AstVisitable,
+    {
+        let mut regions = self.regions;
+        let shift_by = GenericParams {
+            regions: regions.clone(),
+            ..GenericParams::empty()
+        };
+        let mut inner_value = inner.skip_binder;
+        inner_value.drive_mut(&mut FlattenVisitor {
+            shift_by: &shift_by,
+            binder_depth: Default::default(),
+        });
+        for mut var in inner.regions {
+            var.index += shift_by.regions.slot_count();
+            regions.push(var);
+        }
+        RegionBinder {
+            regions,
+            skip_binder: (self.skip_binder, inner_value),
+        }
+    }
+
     /// Substitute the bound variables with erased lifetimes.
     pub fn erase(self) -> T
     where
@@ -374,6 +415,45 @@ impl GenericArgs {
     }
 }
 
+/// Helper to construct [`GenericArgs`] one argument at a time, e.g. when synthesizing a call to a
+/// known function.
+#[derive(Debug, Clone)]
+pub struct GenericArgsBuilder {
+    args: GenericArgs,
+}
+
+impl GenericArgsBuilder {
+    pub fn new(target: GenericsSource) -> Self {
+        GenericArgsBuilder {
+            args: GenericArgs::empty(target),
+        }
+    }
+
+    pub fn region(mut self, r: Region) -> Self {
+        self.args.regions.push(r);
+        self
+    }
+
+    pub fn ty(mut self, ty: Ty) -> Self {
+        self.args.types.push(ty);
+        self
+    }
+
+    pub fn const_generic(mut self, cg: ConstGeneric) -> Self {
+        self.args.const_generics.push(cg);
+        self
+    }
+
+    pub fn trait_ref(mut self, tr: TraitRef) -> Self {
+        self.args.trait_refs.push(tr);
+        self
+    }
+
+    pub fn build(self) -> GenericArgs {
+        self.args
+    }
+}
+
 impl GenericsSource {
     pub fn item<I: Into<AnyTransId>>(id: I) -> Self {
         Self::Item(id.into())
@@ -417,6 +497,19 @@ impl IntegerTy {
     }
 }
 
+impl LiteralTy {
+    /// Whether the literal has this type, e.g. to check that the value given to a const generic
+    /// parameter has the type of that parameter.
+    pub fn accepts(&self, lit: &Literal) -> bool {
+        match (self, lit) {
+            (LiteralTy::Integer(ty), Literal::Scalar(v)) => v.get_integer_ty() == *ty,
+            (LiteralTy::Float(ty), Literal::Float(v)) => v.ty == *ty,
+            (LiteralTy::Bool, Literal::Bool(_)) | (LiteralTy::Char, Literal::Char(_)) => true,
+            _ => false,
+        }
+    }
+}
+
 /// A value of type `T` bound by the generic parameters of item
 /// `item`. Used when dealing with multiple items at a time, to
 /// ensure we don't mix up generics.
@@ -517,6 +610,37 @@ impl Ty {
         TyKind::Adt(TypeId::Tuple, GenericArgs::empty(GenericsSource::Builtin)).into_ty()
     }
 
+    /// Iterate over this type and the types nested inside it, in pre-order. The traversal is
+    /// lazy: sub-types are only looked up when the iterator reaches them. This only descends into
+    /// types directly, not into const generics or trait refs other than the arguments of an
+    /// associated type.
+    pub fn walk(&self) -> impl Iterator<Item = &Ty> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let ty = stack.pop()?;
+            let children: Vec<&Ty> = match ty.kind() {
+                TyKind::Adt(_, args) => args.types.iter().collect(),
+                TyKind::Ref(_, ty, _) | TyKind::RawPtr(ty, _) => vec![ty],
+                TyKind::TraitType(trait_ref, _) => trait_ref
+                    .trait_decl_ref
+                    .skip_binder
+                    .generics
+                    .types
+                    .iter()
+                    .collect(),
+                TyKind::Arrow(binder) => {
+                    let (inputs, output) = &binder.skip_binder;
+                    inputs.iter().chain([output]).collect()
+                }
+                TyKind::TypeVar(_) | TyKind::Literal(_) | TyKind::Never | TyKind::DynTrait(_) => {
+                    vec![]
+                }
+            };
+            stack.extend(children.into_iter().rev());
+            Some(ty)
+        })
+    }
+
     /// Return true if this is a scalar type
     pub fn is_scalar(&self) -> bool {
         match self.kind() {
@@ -539,6 +663,28 @@ impl Ty {
         }
     }
 
+    /// Whether the type is `Copy`. This is the case of the types built from literals, shared
+    /// references, raw pointers, function pointers, tuples, arrays and ADTs that have a `Copy`
+    /// impl. We are conservative: a type variable is never considered `Copy`, and we only consider
+    /// the impls that apply to all the instances of an ADT whose type arguments are `Copy`, i.e.
+    /// the ADTs in `copy_adts`, as computed by [TranslatedCrate::copy_adts].
+    pub fn is_copy(&self, copy_adts: &HashSet<TypeDeclId>) -> bool {
+        match self.kind() {
+            TyKind::Literal(_)
+            | TyKind::Never
+            | TyKind::Ref(_, _, RefKind::Shared)
+            | TyKind::RawPtr(..)
+            | TyKind::Arrow(..) => true,
+            TyKind::Adt(TypeId::Tuple | TypeId::Builtin(BuiltinTy::Array), args) => {
+                args.types.iter().all(|ty| ty.is_copy(copy_adts))
+            }
+            TyKind::Adt(TypeId::Adt(id), args) => {
+                copy_adts.contains(id) && args.types.iter().all(|ty| ty.is_copy(copy_adts))
+            }
+            _ => false,
+        }
+    }
+
     /// Return true if the type is Box
     pub fn is_box(&self) -> bool {
         match self.kind() {
@@ -592,6 +738,34 @@ impl Ty {
             _ => None,
         }
     }
+
+    /// If this is a reference, return its region, the referenced type and whether it is shared or
+    /// mutable.
+    #[allow(clippy::should_implement_trait)]
+    pub fn as_ref(&self) -> Option<(&Region, &Ty, RefKind)> {
+        match self.kind() {
+            TyKind::Ref(region, ty, kind) => Some((region, ty, *kind)),
+            _ => None,
+        }
+    }
+
+    pub fn as_literal(&self) -> Option<LiteralTy> {
+        match self.kind() {
+            TyKind::Literal(lit) => Some(*lit),
+            _ => None,
+        }
+    }
+
+    /// Compare two types structurally, treating all the regions as equal (bound variables,
+    /// `'static` and erased regions alike).
+    pub fn equivalent_modulo_regions(&self, other: &Ty) -> bool {
+        fn erase_regions(ty: &Ty) -> Ty {
+            let mut ty = ty.clone();
+            ty.dyn_visit_mut(|r: &mut Region| *r = Region::Erased);
+            ty
+        }
+        self == other || erase_regions(self) == erase_regions(other)
+    }
 }
 
 impl TyKind {
@@ -646,6 +820,25 @@ impl FunIdOrTraitMethodRef {
     }
 }
 
+impl TypeDecl {
+    /// Look up a field by name. `variant` must be `None` for structs and unions, and give the
+    /// variant for enums. Returns `None` if there is no such field or if the type has no fields.
+    pub fn field_by_name(
+        &self,
+        variant: Option<VariantId>,
+        name: &str,
+    ) -> Option<(FieldId, &Field)> {
+        let fields = match (&self.kind, variant) {
+            (TypeDeclKind::Struct(fields) | TypeDeclKind::Union(fields), None) => fields,
+            (TypeDeclKind::Enum(variants), Some(variant_id)) => &variants.get(variant_id)?.fields,
+            _ => return None,
+        };
+        fields
+            .iter_indexed()
+            .find(|(_, field)| field.name.as_deref() == Some(name))
+    }
+}
+
 impl Field {
     /// The new name for this field, as suggested by the `#[charon::rename]` attribute.
     pub fn renamed_name(&self) -> Option<&str> {
@@ -908,3 +1101,157 @@ mk_index_impls!(GenericParams.regions[RegionId]: RegionVar);
 mk_index_impls!(GenericParams.types[TypeVarId]: TypeVar);
 mk_index_impls!(GenericParams.const_generics[ConstGenericVarId]: ConstGenericVar);
 mk_index_impls!(GenericParams.trait_clauses[TraitClauseId]: TraitClause);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ty_walk() {
+        // `Box<(u32, &bool)>`
+        let u32_ty = TyKind::Literal(LiteralTy::Integer(IntegerTy::U32)).into_ty();
+        let bool_ty = TyKind::Literal(LiteralTy::Bool).into_ty();
+        let ref_ty = TyKind::Ref(Region::Erased, bool_ty.clone(), RefKind::Shared).into_ty();
+        let tuple_ty = TyKind::Adt(
+            TypeId::Tuple,
+            GenericArgsBuilder::new(GenericsSource::Builtin)
+                .ty(u32_ty.clone())
+                .ty(ref_ty.clone())
+                .build(),
+        )
+        .into_ty();
+        let box_ty = TyKind::Adt(
+            TypeId::Builtin(BuiltinTy::Box),
+            GenericArgsBuilder::new(GenericsSource::Builtin)
+                .ty(tuple_ty.clone())
+                .build(),
+        )
+        .into_ty();
+        assert_eq!(
+            box_ty.walk().collect::<Vec<_>>(),
+            [&box_ty, &tuple_ty, &u32_ty, &ref_ty, &bool_ty]
+        );
+    }
+
+    #[test]
+    fn ty_accessors() {
+        let u32_lit = LiteralTy::Integer(IntegerTy::U32);
+        let u32_ty = TyKind::Literal(u32_lit).into_ty();
+        let ref_ty = TyKind::Ref(Region::Erased, u32_ty.clone(), RefKind::Mut).into_ty();
+        let args = GenericArgsBuilder::new(GenericsSource::Builtin)
+            .ty(u32_ty.clone())
+            .build();
+        let box_ty = TyKind::Adt(TypeId::Builtin(BuiltinTy::Box), args.clone()).into_ty();
+
+        assert_eq!(
+            box_ty.as_adt(),
+            Some((TypeId::Builtin(BuiltinTy::Box), &args))
+        );
+        assert_eq!(u32_ty.as_adt(), None);
+
+        assert_eq!(
+            ref_ty.as_ref(),
+            Some((&Region::Erased, &u32_ty, RefKind::Mut))
+        );
+        assert_eq!(box_ty.as_ref(), None);
+
+        assert_eq!(u32_ty.as_literal(), Some(u32_lit));
+        assert_eq!(ref_ty.as_literal(), None);
+    }
+
+    #[test]
+    fn generic_args_builder() {
+        // The arguments of a call like `Vec::<u32>::push(&mut v, 0)`.
+        let u32_ty = TyKind::Literal(LiteralTy::Integer(IntegerTy::U32)).into_ty();
+        let args = GenericArgsBuilder::new(GenericsSource::Builtin)
+            .region(Region::Erased)
+            .ty(u32_ty.clone())
+            .build();
+        assert_eq!(args.regions.iter().collect::<Vec<_>>(), [&Region::Erased]);
+        assert_eq!(args.types.iter().collect::<Vec<_>>(), [&u32_ty]);
+        assert!(args.const_generics.is_empty());
+        assert!(args.trait_refs.is_empty());
+        assert_eq!(args.target, GenericsSource::Builtin);
+
+        let cg = ConstGeneric::Value(Literal::Scalar(ScalarValue::Usize(4)));
+        let args = GenericArgsBuilder::new(GenericsSource::Builtin)
+            .ty(u32_ty)
+            .const_generic(cg.clone())
+            .build();
+        assert!(args.regions.is_empty());
+        assert_eq!(args.const_generics.iter().collect::<Vec<_>>(), [&cg]);
+    }
+
+    #[test]
+    fn equivalent_modulo_regions() {
+        let u32_ty = TyKind::Literal(LiteralTy::Integer(IntegerTy::U32)).into_ty();
+        let ref_ty = |r: Region| TyKind::Ref(r, u32_ty.clone(), RefKind::Shared).into_ty();
+        let var = |i: usize| Region::Var(DeBruijnVar::Free(RegionId::new(i)));
+        // `&'a u32` and `&'b u32`.
+        let (ref_a, ref_b) = (ref_ty(var(0)), ref_ty(var(1)));
+        assert_ne!(ref_a, ref_b);
+        assert!(ref_a.equivalent_modulo_regions(&ref_b));
+        assert!(ref_a.equivalent_modulo_regions(&ref_ty(Region::Static)));
+        assert!(ref_a.equivalent_modulo_regions(&ref_ty(Region::Erased)));
+
+        let mut_ref = TyKind::Ref(var(0), u32_ty.clone(), RefKind::Mut).into_ty();
+        assert!(!ref_a.equivalent_modulo_regions(&mut_ref));
+        assert!(!ref_a.equivalent_modulo_regions(&u32_ty));
+    }
+
+    #[test]
+    fn literal_ty_accepts() {
+        let lit = Literal::Scalar(ScalarValue::U32(3));
+        assert!(LiteralTy::Integer(IntegerTy::U32).accepts(&lit));
+        assert!(!LiteralTy::Integer(IntegerTy::U8).accepts(&lit));
+        assert!(!LiteralTy::Bool.accepts(&lit));
+        assert!(LiteralTy::Bool.accepts(&Literal::Bool(true)));
+        assert!(!LiteralTy::Char.accepts(&Literal::Bool(true)));
+    }
+
+    #[test]
+    fn region_binder_shift_and_merge() {
+        let u32_ty = TyKind::Literal(LiteralTy::Integer(IntegerTy::U32)).into_ty();
+        let ref_ty = |dbid: usize, id: usize| {
+            let var = DeBruijnVar::bound(DeBruijnId::new(dbid), RegionId::new(id));
+            TyKind::Ref(Region::Var(var), u32_ty.clone(), RefKind::Shared).into_ty()
+        };
+        fn binder<T>(name: &str, value: T) -> RegionBinder<T> {
+            let mut regions = Vector::new();
+            regions.push_with(|index| RegionVar {
+                index,
+                name: Some(name.to_owned()),
+            });
+            RegionBinder {
+                regions,
+                skip_binder: value,
+            }
+        }
+
+        // `for<'a> (&'a u32, &'x u32)` where `'x` is bound one level above the binder.
+        let mut shifted = binder("'a", (ref_ty(0, 0), ref_ty(1, 0)));
+        shifted.shift_in(2);
+        assert_eq!(shifted.skip_binder, (ref_ty(0, 0), ref_ty(3, 0)));
+
+        // `for<'a> &'a u32` and `for<'b> (&'b u32, &'a u32)`, the latter found inside the former.
+        let outer = binder("'a", ref_ty(0, 0));
+        let inner = binder("'b", (ref_ty(0, 0), ref_ty(1, 0)));
+        let merged = outer.merge_with(inner);
+        let regions = merged
+            .regions
+            .iter_indexed_values()
+            .map(|(id, var)| (id, var.index, var.name.clone().unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            regions,
+            [
+                (RegionId::new(0), RegionId::new(0), "'a".to_owned()),
+                (RegionId::new(1), RegionId::new(1), "'b".to_owned())
+            ]
+        );
+        assert_eq!(
+            merged.skip_binder,
+            (ref_ty(0, 0), (ref_ty(0, 1), ref_ty(0, 0)))
+        );
+    }
+}
@@ -2,6 +2,8 @@
 use crate::ids::Vector;
 use crate::meta::Span;
 use crate::ullbc_ast::*;
+use petgraph::algo::dominators::simple_fast;
+use petgraph::graphmap::DiGraphMap;
 use std::mem;
 use take_mut::take;
 
@@ -155,6 +157,22 @@ impl ExprBody {
             }
         }
     }
+
+    /// Compute the immediate dominator of each block, i.e. the closest block through which all
+    /// the paths from the entry block to this block go. This is `None` for the entry block and
+    /// for the unreachable blocks.
+    pub fn dominators(&self) -> Vector<BlockId, Option<BlockId>> {
+        let mut cfg: DiGraphMap<BlockId, ()> = DiGraphMap::new();
+        for (block_id, block) in self.body.iter_indexed() {
+            cfg.add_node(block_id);
+            for target in block.targets() {
+                cfg.add_edge(block_id, target, ());
+            }
+        }
+        let dominators = simple_fast(&cfg, START_BLOCK_ID);
+        self.body
+            .map_ref_indexed(|block_id, _| dominators.immediate_dominator(block_id))
+    }
 }
 
 /// Transform a body by applying a function to its operands, and
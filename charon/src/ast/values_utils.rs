@@ -233,6 +233,86 @@ impl ScalarValue {
             ty: TyKind::Literal(LiteralTy::Integer(self.get_integer_ty())).into_ty(),
         }
     }
+
+    /// Apply an operation on the mathematical values of two integers, and convert the result back
+    /// to their type. Returns `None` if the types differ, if the operation fails (e.g. division
+    /// by zero) or if the result doesn't fit in the type.
+    fn checked_binop(
+        &self,
+        other: &ScalarValue,
+        int_op: impl FnOnce(i128, i128) -> Option<i128>,
+        uint_op: impl FnOnce(u128, u128) -> Option<u128>,
+    ) -> Option<ScalarValue> {
+        let ty = self.get_integer_ty();
+        if other.get_integer_ty() != ty {
+            return None;
+        }
+        if ty.is_signed() {
+            let v = int_op(self.as_int().ok()?, other.as_int().ok()?)?;
+            ScalarValue::from_int(ty, v).ok()
+        } else {
+            let v = uint_op(self.as_uint().ok()?, other.as_uint().ok()?)?;
+            ScalarValue::from_uint(ty, v).ok()
+        }
+    }
+
+    /// Apply an operation on the bit representations of two integers, truncating the result to
+    /// the width of their type. Panics if the types differ.
+    fn wrapping_binop(&self, other: &ScalarValue, op: impl FnOnce(u128, u128) -> u128) -> Self {
+        let ty = self.get_integer_ty();
+        assert_eq!(ty, other.get_integer_ty(), "Mismatched integer types");
+        ScalarValue::from_bits(ty, op(self.to_bits(), other.to_bits()))
+    }
+
+    pub fn checked_add(&self, other: &ScalarValue) -> Option<ScalarValue> {
+        self.checked_binop(other, i128::checked_add, u128::checked_add)
+    }
+
+    pub fn checked_sub(&self, other: &ScalarValue) -> Option<ScalarValue> {
+        self.checked_binop(other, i128::checked_sub, u128::checked_sub)
+    }
+
+    pub fn checked_mul(&self, other: &ScalarValue) -> Option<ScalarValue> {
+        self.checked_binop(other, i128::checked_mul, u128::checked_mul)
+    }
+
+    pub fn checked_div(&self, other: &ScalarValue) -> Option<ScalarValue> {
+        self.checked_binop(other, i128::checked_div, u128::checked_div)
+    }
+
+    pub fn checked_rem(&self, other: &ScalarValue) -> Option<ScalarValue> {
+        self.checked_binop(other, i128::checked_rem, u128::checked_rem)
+    }
+
+    /// Panics if the types differ.
+    pub fn wrapping_add(&self, other: &ScalarValue) -> ScalarValue {
+        self.wrapping_binop(other, u128::wrapping_add)
+    }
+
+    /// Panics if the types differ.
+    pub fn wrapping_sub(&self, other: &ScalarValue) -> ScalarValue {
+        self.wrapping_binop(other, u128::wrapping_sub)
+    }
+
+    /// Panics if the types differ.
+    pub fn wrapping_mul(&self, other: &ScalarValue) -> ScalarValue {
+        self.wrapping_binop(other, u128::wrapping_mul)
+    }
+
+    /// Like `wrapping_add`, but also returns whether the operation overflowed.
+    pub fn overflowing_add(&self, other: &ScalarValue) -> (ScalarValue, bool) {
+        (self.wrapping_add(other), self.checked_add(other).is_none())
+    }
+
+    /// Like `wrapping_sub`, but also returns whether the operation overflowed.
+    pub fn overflowing_sub(&self, other: &ScalarValue) -> (ScalarValue, bool) {
+        (self.wrapping_sub(other), self.checked_sub(other).is_none())
+    }
+
+    /// Like `wrapping_mul`, but also returns whether the operation overflowed.
+    pub fn overflowing_mul(&self, other: &ScalarValue) -> (ScalarValue, bool) {
+        (self.wrapping_mul(other), self.checked_mul(other).is_none())
+    }
 }
 
 /// Custom serializer that stores integers as strings to avoid overflow.
@@ -303,3 +383,29 @@ impl<'de> Deserialize<'de> for ScalarValue {
         deserializer.deserialize_map(Visitor)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scalar_arithmetic() {
+        let max = ScalarValue::U8(255);
+        let one = ScalarValue::U8(1);
+        assert_eq!(max.wrapping_add(&one), ScalarValue::U8(0));
+        assert_eq!(max.overflowing_add(&one), (ScalarValue::U8(0), true));
+        assert_eq!(max.checked_add(&one), None);
+        assert_eq!(one.checked_add(&one), Some(ScalarValue::U8(2)));
+        assert_eq!(ScalarValue::U8(0).wrapping_sub(&one), max);
+        // Mismatched types.
+        assert_eq!(one.checked_add(&ScalarValue::U16(1)), None);
+
+        let min = ScalarValue::I8(i8::MIN);
+        let minus_one = ScalarValue::I8(-1);
+        assert_eq!(min.wrapping_add(&minus_one), ScalarValue::I8(i8::MAX));
+        assert_eq!(min.checked_sub(&ScalarValue::I8(1)), None);
+        assert_eq!(min.checked_div(&minus_one), None);
+        assert_eq!(minus_one.checked_div(&ScalarValue::I8(0)), None);
+        assert_eq!(minus_one.checked_mul(&minus_one), Some(ScalarValue::I8(1)));
+    }
+}
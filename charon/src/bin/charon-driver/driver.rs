@@ -1,9 +1,9 @@
 use crate::translate::translate_crate_to_ullbc;
 use charon_lib::options::CliOpts;
+use charon_lib::transform::{run_passes, TransformCtx, SHARED_FINALIZING_PASSES};
 use charon_lib::transform::{
     Pass, PrintCtxPass, FINAL_CLEANUP_PASSES, INITIAL_CLEANUP_PASSES, LLBC_PASSES, ULLBC_PASSES,
 };
-use charon_lib::transform::{TransformCtx, SHARED_FINALIZING_PASSES};
 use charon_lib::{export, options};
 use rustc_driver::{Callbacks, Compilation};
 use rustc_interface::{interface::Compiler, Queries};
@@ -290,13 +290,14 @@ pub fn transformation_passes(options: &CliOpts) -> Vec<Pass> {
 pub fn transform(ctx: &mut TransformCtx, options: &CliOpts) -> export::CrateData {
     // The bulk of the translation is done, we no longer need to interact with rustc internals. We
     // run several passes that simplify the items and cleanup the bodies.
-    for pass in transformation_passes(options) {
-        trace!("# Starting pass {}", pass.name());
-        pass.run(ctx);
-        if ctx.errors.borrow().has_errors() {
-            break;
-        }
+    let timings = run_passes(ctx, transformation_passes(options));
+    if ctx.options.time_passes {
+        eprint!("{timings}");
+    }
+    if ctx.options.print_stats {
+        eprint!("{}", ctx.stats());
     }
 
-    export::CrateData::new(&ctx)
+    let rustc_version = rustc_interface::util::rustc_version_str().unwrap_or("unknown");
+    export::CrateData::new(&ctx, rustc_version)
 }
@@ -77,7 +77,7 @@ fn main() {
     // Retrieve the Charon options by deserializing them from the environment variable
     // (cargo-charon serialized the arguments and stored them in a specific environment
     // variable before calling cargo with RUSTC_WORKSPACE_WRAPPER=charon-driver).
-    let options: options::CliOpts = match std::env::var(options::CHARON_ARGS) {
+    let mut options: options::CliOpts = match std::env::var(options::CHARON_ARGS) {
         Ok(opts) => serde_json::from_str(opts.as_str()).unwrap(),
         Err(_) => {
             // Parse any arguments after `--` as charon arguments.
@@ -91,6 +91,8 @@ fn main() {
             }
         }
     };
+    // Expand the preset once, so that the options we store and export are the ones we use.
+    options.apply_preset();
 
     if !has_sysroot_arg {
         compiler_args.extend(vec!["--sysroot".to_string(), sysroot.clone()]);
@@ -198,6 +200,16 @@ fn main() {
         compiler_args.push(extra_flag);
     }
 
+    // Forward the `--cfg` options; rustc expects the values to be quoted, e.g. `feature="x"`.
+    for cfg in options.cfg.iter() {
+        let cfg = match cfg.split_once('=') {
+            Some((key, value)) if !value.starts_with('"') => format!("{key}=\"{value}\""),
+            _ => cfg.clone(),
+        };
+        compiler_args.push("--cfg".to_string());
+        compiler_args.push(cfg);
+    }
+
     let disabled_mir_passes = [
         "AfterConstProp",
         "AfterGVN",
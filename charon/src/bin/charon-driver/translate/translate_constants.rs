@@ -1,7 +1,11 @@
 //! Functions to translate constants to LLBC.
 use super::translate_ctx::*;
 use charon_lib::ast::*;
+use charon_lib::common::ensure_sufficient_stack;
 use hax_frontend_exporter as hax;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::DefId;
+use rustc_middle::{mir, ty};
 
 impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
     fn translate_constant_literal_to_raw_constant_expr(
@@ -64,6 +68,15 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
         &mut self,
         span: Span,
         v: &hax::ConstantExpr,
+    ) -> Result<ConstantExpr, Error> {
+        // Constants can be nested arbitrarily deep, so we grow the stack as needed.
+        ensure_sufficient_stack(|| self.translate_constant_expr_inner(span, v))
+    }
+
+    fn translate_constant_expr_inner(
+        &mut self,
+        span: Span,
+        v: &hax::ConstantExpr,
     ) -> Result<ConstantExpr, Error> {
         use hax::ConstantExprKind;
         let ty = &v.ty;
@@ -189,6 +202,12 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
         span: Span,
         v: &hax::ConstantExpr,
     ) -> Result<ConstGeneric, Error> {
+        if let hax::ConstantExprKind::GlobalName { id, generics, .. } = v.contents.as_ref()
+            && !generics.is_empty()
+            && let Some(cg) = self.translate_abstract_const(span, id, generics)?
+        {
+            return Ok(cg);
+        }
         // Remark: we can't user globals as constant generics (meaning
         // the user provided type annotation should always be none).
         let value = self
@@ -213,4 +232,117 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
             }
         }
     }
+
+    /// With `generic_const_exprs`, a const generic such as the `N + 1` in `[u8; N + 1]` is an
+    /// anonymous constant that depends on generic parameters, and can't be evaluated. We
+    /// reconstruct the arithmetic expression it stands for. Returns `None` if `id` is not such a
+    /// constant.
+    fn translate_abstract_const(
+        &mut self,
+        span: Span,
+        id: &hax::DefId,
+        generics: &[hax::GenericArg],
+    ) -> Result<Option<ConstGeneric>, Error> {
+        let def_id: DefId = id.into();
+        let tcx = self.t_ctx.tcx;
+        if tcx.def_kind(def_id) != DefKind::AnonConst {
+            return Ok(None);
+        }
+        let Ok(Some(abstract_const)) = tcx.thir_abstract_const(def_id) else {
+            return Ok(None);
+        };
+        // The const generic arguments, in the order of the const generic parameters of the
+        // anonymous constant.
+        let const_args: Vec<&hax::ConstantExpr> = generics
+            .iter()
+            .filter_map(|arg| match arg {
+                hax::GenericArg::Const(c) => Some(c),
+                _ => None,
+            })
+            .collect();
+        let params = tcx.generics_of(def_id);
+        let const_params: Vec<u32> = (0..params.count() as u32)
+            .filter(|&i| {
+                matches!(
+                    params.param_at(i as usize, tcx).kind,
+                    ty::GenericParamDefKind::Const { .. }
+                )
+            })
+            .collect();
+        let cg = self.translate_abstract_const_aux(
+            span,
+            def_id,
+            &const_params,
+            &const_args,
+            abstract_const.instantiate_identity(),
+        )?;
+        Ok(Some(cg))
+    }
+
+    fn translate_abstract_const_aux(
+        &mut self,
+        span: Span,
+        def_id: DefId,
+        const_params: &[u32],
+        const_args: &[&hax::ConstantExpr],
+        ct: ty::Const<'tcx>,
+    ) -> Result<ConstGeneric, Error> {
+        match ct.kind() {
+            // A parameter of the anonymous constant: replace it with the corresponding argument.
+            ty::ConstKind::Param(param) => {
+                let Some(i) = const_params.iter().position(|&p| p == param.index) else {
+                    raise_error!(
+                        self,
+                        span,
+                        "Unexpected const generic parameter: {}",
+                        param.name
+                    )
+                };
+                self.translate_constant_expr_to_const_generic(span, const_args[i])
+            }
+            ty::ConstKind::Expr(expr) => {
+                let (op, args) = match expr.kind {
+                    ty::ExprKind::Binop(binop) => {
+                        let (_, _, lhs, rhs) = expr.binop_args();
+                        let state = self.t_ctx.hax_state.clone().with_owner_id(def_id);
+                        let binop: hax::BinOp = self.t_ctx.catch_sinto(&state, span, &binop)?;
+                        let binop = self.t_ctx.translate_binaryop_kind(span, binop)?;
+                        (ConstGenericOp::BinOp(binop), vec![lhs, rhs])
+                    }
+                    ty::ExprKind::UnOp(unop) => {
+                        let (_, arg) = expr.unop_args();
+                        let op = match unop {
+                            mir::UnOp::Neg => ConstGenericOp::Neg,
+                            mir::UnOp::Not => ConstGenericOp::Not,
+                            mir::UnOp::PtrMetadata => raise_error!(
+                                self,
+                                span,
+                                "Unsupported constant generic expression: {ct}"
+                            ),
+                        };
+                        (op, vec![arg])
+                    }
+                    _ => raise_error!(self, span, "Unsupported constant generic expression: {ct}"),
+                };
+                let args: Vec<ConstGeneric> = args
+                    .into_iter()
+                    .map(|arg| {
+                        self.translate_abstract_const_aux(
+                            span,
+                            def_id,
+                            const_params,
+                            const_args,
+                            arg,
+                        )
+                    })
+                    .try_collect()?;
+                Ok(ConstGeneric::App(op, args))
+            }
+            _ => {
+                let state = self.t_ctx.hax_state.clone().with_owner_id(def_id);
+                let ct: hax::ConstantExpr = self.t_ctx.catch_sinto(&state, span, &ct)?;
+                self.translate_constant_expr_to_const_generic(span, &ct)
+            }
+        }
+    }
 }
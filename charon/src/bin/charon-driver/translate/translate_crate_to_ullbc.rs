@@ -23,11 +23,21 @@ impl<'tcx, 'ctx> TranslateCtx<'tcx> {
             return; // Error has already been emitted
         };
         let opacity = self.opacity_for_name(&name);
+        let is_root = self.options.is_translation_root(&self.translated, &name);
         // Use `item_meta` to take into account the `charon::opaque` attribute.
         let opacity = self.translate_item_meta(&def, name, opacity).opacity;
         let explore_inside = !(opacity.is_opaque() || opacity.is_invisible());
 
         match def.kind() {
+            // With `--only`, we only register the requested items; the items they depend on get
+            // registered as we translate them. We still explore the modules and impl blocks.
+            _ if !is_root
+                && !matches!(
+                    def.kind(),
+                    FullDefKind::Mod { .. }
+                        | FullDefKind::ForeignMod { .. }
+                        | FullDefKind::InherentImpl { .. }
+                ) => {}
             FullDefKind::Enum { .. }
             | FullDefKind::Struct { .. }
             | FullDefKind::Union { .. }
@@ -247,7 +257,11 @@ pub fn translate<'tcx, 'ctx>(
         .clone();
     trace!("# Crate: {}", requested_crate_name);
 
-    let mut error_ctx = ErrorCtx::new(!options.abort_on_error, options.error_on_warnings);
+    let mut error_ctx = ErrorCtx::new(
+        !options.abort_on_error,
+        options.error_on_warnings,
+        options.error_format,
+    );
     let translate_options = TranslateOptions::new(&mut error_ctx, options);
     let mut ctx = TranslateCtx {
         tcx,
@@ -1,6 +1,7 @@
 //! The translation contexts.
 use super::translate_types::translate_bound_region_kind_name;
 use charon_lib::ast::*;
+use charon_lib::common::ensure_sufficient_stack;
 use charon_lib::common::hash_by_addr::HashByAddr;
 use charon_lib::formatter::{FmtCtx, IntoFormatter};
 use charon_lib::ids::{MapGenerator, Vector};
@@ -190,11 +191,22 @@ impl BindingLevel {
         var_id
     }
 
-    pub(crate) fn push_const_generic_var(&mut self, rid: u32, ty: LiteralTy, name: String) {
+    pub(crate) fn push_const_generic_var(
+        &mut self,
+        rid: u32,
+        ty: LiteralTy,
+        name: String,
+        default: Option<ConstGeneric>,
+    ) {
         let var_id = self
             .params
             .const_generics
-            .push_with(|index| ConstGenericVar { index, name, ty });
+            .push_with(|index| ConstGenericVar {
+                index,
+                name,
+                ty,
+                default,
+            });
         self.const_generic_vars_map.insert(rid, var_id);
     }
 
@@ -278,7 +290,11 @@ where
 {
     let unwind_safe_s = std::panic::AssertUnwindSafe(s);
     let unwind_safe_x = std::panic::AssertUnwindSafe(x);
-    std::panic::catch_unwind(move || unwind_safe_x.sinto(*unwind_safe_s)).or_else(|_| {
+    // Hax recurses on the structure of the value, which can be arbitrarily deep.
+    std::panic::catch_unwind(move || {
+        ensure_sufficient_stack(|| unwind_safe_x.sinto(*unwind_safe_s))
+    })
+    .or_else(|_| {
         raise_error!(
             err,
             crate(krate),
@@ -322,7 +338,9 @@ impl<'tcx, 'ctx> TranslateCtx<'tcx> {
     ) -> Result<Option<PathElem>, Error> {
         let path_elem = def.path_item();
         // Disambiguator disambiguates identically-named (but distinct) identifiers. This happens
-        // notably with macros and inherent impl blocks.
+        // notably with macros, inherent impl blocks and closures. Rustc numbers the items that
+        // share a parent and a name in source order, so these names are stable across runs and
+        // don't depend on the order in which we translate items.
         let disambiguator = Disambiguator::new(path_elem.disambiguator as usize);
         // Match over the key data
         let path_elem = match path_elem.data {
@@ -526,11 +544,20 @@ impl<'tcx, 'ctx> TranslateCtx<'tcx> {
         }
         let span = def.source_span.as_ref().unwrap_or(&def.span);
         let span = self.translate_span_from_hax(span);
+        let name_span = match self.tcx.def_ident_span(def.rust_def_id()) {
+            Some(ident_span) => {
+                let ident_span = ident_span.sinto(&self.hax_state);
+                self.translate_span_from_hax(&ident_span)
+            }
+            None => span,
+        };
         let attr_info = self.translate_attr_info(def);
         let is_local = def.def_id.is_local;
 
-        let opacity = if self.is_extern_item(def)
+        let is_foreign = self.is_extern_item(def);
+        let opacity = if is_foreign
             || attr_info.attributes.iter().any(|attr| attr.is_opaque())
+            || self.is_in_opaque_module(def)
         {
             // Force opaque in these cases.
             ItemOpacity::Opaque.max(name_opacity)
@@ -538,13 +565,68 @@ impl<'tcx, 'ctx> TranslateCtx<'tcx> {
             name_opacity
         };
 
+        // Symbol metadata. We don't parse these attributes ourselves, so they show up as unknown
+        // attributes.
+        let unknown_attr = |path: &str| {
+            attr_info
+                .attributes
+                .iter()
+                .filter_map(|attr| attr.as_unknown())
+                .find(|attr| attr.path == path)
+        };
+        let no_mangle = unknown_attr("no_mangle").is_some();
+        let export_name = unknown_attr("export_name")
+            .and_then(|attr| attr.args.as_deref())
+            .map(|args| args.trim_matches('"').to_owned());
+        let must_use = unknown_attr("must_use").is_some();
+        let cold = unknown_attr("cold").is_some();
+        let non_exhaustive = unknown_attr("non_exhaustive").is_some();
+        // The derived impls are marked `#[automatically_derived]`; their items inherit the flag.
+        let is_derived = {
+            let tcx = self.tcx;
+            let is_derived = |id: DefId| tcx.has_attr(id, rustc_span::sym::automatically_derived);
+            let rust_id = def.rust_def_id();
+            is_derived(rust_id) || tcx.opt_parent(rust_id).is_some_and(is_derived)
+        };
+        let foreign_abi = {
+            let rust_id = def.rust_def_id();
+            if is_foreign && matches!(self.tcx.def_kind(rust_id), rustc_hir::def::DefKind::Fn) {
+                let abi = self.tcx.fn_sig(rust_id).skip_binder().abi();
+                Some(abi.name().to_owned())
+            } else {
+                None
+            }
+        };
+        // For items generated by a macro, the span of the outermost macro invocation.
+        let from_macro = {
+            let rust_span = self.tcx.def_span(def.rust_def_id());
+            if let rustc_span::hygiene::ExpnKind::Macro(..) =
+                rust_span.ctxt().outer_expn_data().kind
+            {
+                let call_site = rust_span.source_callsite().sinto(&self.hax_state);
+                Some(self.translate_span_from_hax(&call_site))
+            } else {
+                None
+            }
+        };
+
         let item_meta = ItemMeta {
             name,
             span,
+            name_span,
             source_text: def.source_text.clone(),
             attr_info,
             is_local,
             opacity,
+            no_mangle,
+            export_name,
+            must_use,
+            cold,
+            non_exhaustive,
+            is_derived,
+            is_foreign,
+            foreign_abi,
+            from_macro,
         };
         self.cached_item_metas
             .insert(def.rust_def_id(), item_meta.clone());
@@ -741,6 +823,34 @@ impl<'tcx, 'ctx> TranslateCtx<'tcx> {
         })
     }
 
+    /// Whether the item is (transitively) inside a module marked `#[charon::opaque]`. We look at
+    /// the raw attributes: parsing them would report the errors in the module attributes once per
+    /// item inside the module.
+    pub(crate) fn is_in_opaque_module(&mut self, def: &hax::FullDef) -> bool {
+        let is_opaque_attr = |attr: &hax::Attribute| match &attr.kind {
+            hax::AttrKind::Normal(normal_attr) => {
+                matches!(
+                    normal_attr.item.path.as_str(),
+                    "charon::opaque" | "aeneas::opaque"
+                ) && matches!(normal_attr.item.args, hax::AttrArgs::Empty)
+            }
+            hax::AttrKind::DocComment(..) => false,
+        };
+        let mut parent = def.parent.clone();
+        while let Some(parent_id) = parent {
+            let Ok(parent_def) = self.hax_def(&parent_id) else {
+                return false;
+            };
+            if matches!(parent_def.kind(), hax::FullDefKind::Mod { .. })
+                && parent_def.attributes.iter().any(is_opaque_attr)
+            {
+                return true;
+            }
+            parent = parent_def.parent.clone();
+        }
+        false
+    }
+
     pub(crate) fn opacity_for_name(&self, name: &Name) -> ItemOpacity {
         self.options.opacity_for_name(&self.translated, name)
     }
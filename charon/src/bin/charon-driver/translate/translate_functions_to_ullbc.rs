@@ -19,6 +19,15 @@ use itertools::Itertools;
 use rustc_hir::def_id::DefId;
 use rustc_middle::mir::START_BLOCK;
 
+/// The type of the keys defined by `thread_local!`.
+static LOCAL_KEY: &[&str] = &["std", "thread", "local", "LocalKey"];
+/// The functions in which `unwrap`/`expect` panic. We treat the calls to these as panics.
+static UNWRAP_FAILED_NAMES: &[&[&str]] = &[
+    &["core", "option", "unwrap_failed"],
+    &["core", "option", "expect_failed"],
+    &["core", "result", "unwrap_failed"],
+];
+
 pub(crate) struct SubstFunId {
     pub func: FnPtr,
     pub args: Option<Vec<Operand>>,
@@ -53,8 +62,24 @@ fn translate_borrow_kind(borrow_kind: hax::BorrowKind) -> BorrowKind {
     }
 }
 
+/// Translate the message of a rustc-inserted `Assert` terminator.
+fn translate_assert_kind(msg: &hax::AssertKind) -> AssertKind {
+    match msg {
+        hax::AssertKind::BoundsCheck { .. } => AssertKind::BoundsCheck,
+        hax::AssertKind::Overflow(..) | hax::AssertKind::OverflowNeg(..) => AssertKind::Overflow,
+        hax::AssertKind::DivisionByZero(..) | hax::AssertKind::RemainderByZero(..) => {
+            AssertKind::DivisionByZero
+        }
+        _ => AssertKind::Compiler,
+    }
+}
+
 impl<'tcx, 'ctx> TranslateCtx<'tcx> {
-    fn translate_binaryop_kind(&mut self, span: Span, binop: hax::BinOp) -> Result<BinOp, Error> {
+    pub(crate) fn translate_binaryop_kind(
+        &mut self,
+        span: Span,
+        binop: hax::BinOp,
+    ) -> Result<BinOp, Error> {
         Ok(match binop {
             hax::BinOp::BitXor => BinOp::BitXor,
             hax::BinOp::BitAnd => BinOp::BitAnd,
@@ -637,7 +662,8 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                 // p.y = yv;
                 // ```
 
-                // First translate the operands
+                // First translate the operands. MIR indexes them by field, hence for ADTs they
+                // are in the order of the fields in the type declaration.
                 let operands_t: Vec<Operand> = operands
                     .raw
                     .iter()
@@ -747,6 +773,23 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
         }
     }
 
+    /// Recognize `Rc::new` and `Arc::new`, i.e. a method called `new` in an inherent impl block
+    /// of one of these types.
+    fn recognize_rc_new(&self, def_id: &hax::DefId) -> Option<BuiltinFun> {
+        let tcx = self.t_ctx.tcx;
+        let def_id: DefId = def_id.into();
+        let impl_id = tcx.impl_of_method(def_id)?;
+        if tcx.trait_id_of_impl(impl_id).is_some() || tcx.item_name(def_id).as_str() != "new" {
+            return None;
+        }
+        let adt = tcx.type_of(impl_id).instantiate_identity().ty_adt_def()?;
+        match tcx.get_diagnostic_name(adt.did())?.as_str() {
+            "Rc" => Some(BuiltinFun::RcNew),
+            "Arc" => Some(BuiltinFun::ArcNew),
+            _ => None,
+        }
+    }
+
     /// Checks whether the given id corresponds to a built-in function.
     fn recognize_builtin_fun(&mut self, def: &hax::FullDef) -> Result<Option<BuiltinFun>, Error> {
         let name = self.t_ctx.hax_def_id_to_name(&def.def_id)?;
@@ -755,11 +798,20 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
 
         if def.diagnostic_item.as_deref() == Some("box_new") {
             Ok(Some(BuiltinFun::BoxNew))
+        } else if let Some(fun) = self.recognize_rc_new(&def.def_id) {
+            Ok(Some(fun))
+        } else if !self.t_ctx.options.no_cleanup
+            && let Some(fun) = recognize_primitive_method(&name)
+        {
+            Ok(Some(fun))
         } else if def
             .lang_item
             .as_deref()
             .is_some_and(|lang_it| panic_lang_items.iter().contains(&lang_it))
             || panic_names.iter().any(|panic| name.equals_ref_name(panic))
+            || UNWRAP_FAILED_NAMES
+                .iter()
+                .any(|unwrap_fn| name.equals_ref_name(unwrap_fn))
         {
             Ok(Some(BuiltinFun::Panic))
         } else {
@@ -842,9 +894,16 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
             // We have to retrieve the type `Box<u32>` and check that it is of the
             // form `Box<T>` (and we generate `box_deref<u32>`).
             match aid {
-                BuiltinFunId::BoxNew => {
+                BuiltinFunId::BoxNew
+                | BuiltinFunId::RcNew
+                | BuiltinFunId::ArcNew
+                | BuiltinFunId::PrimitiveMethod(_) => {
                     // Nothing to do
                 }
+                BuiltinFunId::RcClone | BuiltinFunId::ArcClone => {
+                    // Those are introduced later by the [reconstruct_rc] micro-pass.
+                    unreachable!()
+                }
                 BuiltinFunId::Index { .. }
                 | BuiltinFunId::ArrayToSliceShared
                 | BuiltinFunId::ArrayToSliceMut
@@ -873,13 +932,17 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
         };
 
         // Translate the type parameters
-        let generics = self.translate_generic_args(
+        let mut generics = self.translate_generic_args(
             span,
             substs,
             trait_refs,
             binder,
             fun_id.generics_target(),
         )?;
+        // The primitive methods don't have generics: we give them the type of `self`.
+        if let Some(BuiltinFun::PrimitiveMethod(_, lit_ty)) = builtin_fun {
+            generics.types.push(TyKind::Literal(lit_ty).into_ty());
+        }
 
         // Translate the arguments
         let args = args
@@ -952,6 +1015,7 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                 Some(RawStatement::Assert(Assert {
                     cond: op,
                     expected: true,
+                    kind: AssertKind::Assume,
                 }))
             }
             StatementKind::Intrinsic(hax::NonDivergingIntrinsic::CopyNonOverlapping(..)) => {
@@ -999,6 +1063,20 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
             TerminatorKind::SwitchInt { discr, targets } => {
                 // Translate the operand which gives the discriminant
                 let (discr, discr_ty) = self.translate_operand_with_type(span, discr)?;
+                // `SwitchInt` only takes integers: we switch on the scalar value of a `char`.
+                let (discr, discr_ty) = if let TyKind::Literal(LiteralTy::Char) = discr_ty.kind() {
+                    let u32_ty = LiteralTy::Integer(IntegerTy::U32);
+                    let tmp = self.locals.new_var(None, TyKind::Literal(u32_ty).into_ty());
+                    let cast = UnOp::Cast(CastKind::Scalar(LiteralTy::Char, u32_ty));
+                    statements.push(Statement::new(
+                        span,
+                        RawStatement::Assign(tmp.clone(), Rvalue::UnaryOp(cast, discr)),
+                    ));
+                    let ty = tmp.ty().clone();
+                    (Operand::Move(tmp), ty)
+                } else {
+                    (discr, discr_ty)
+                };
 
                 // Translate the switch targets
                 let targets = self.translate_switch_targets(&discr_ty, targets)?;
@@ -1040,13 +1118,14 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
             TerminatorKind::Assert {
                 cond,
                 expected,
-                msg: _,
+                msg,
                 target,
                 unwind: _, // We model unwinding as an effet, we don't represent it in control flow
             } => {
                 let assert = Assert {
                     cond: self.translate_operand(span, cond)?,
                     expected: *expected,
+                    kind: translate_assert_kind(msg),
                 };
                 statements.push(Statement::new(span, RawStatement::Assert(assert)));
                 let target = self.translate_basic_block_id(*target);
@@ -1168,12 +1247,40 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                         // If the call is `panic!`, then the target is `None`.
                         // I don't know in which other cases it can be `None`.
                         assert!(target.is_none());
-                        // We ignore the arguments
-                        return Ok(RawTerminator::Abort(AbortKind::Panic(name)));
+                        let kind = self.translate_panic_kind(&name);
+                        // We only look at the arguments to recover the panic message.
+                        let args = self.translate_arguments(span, args)?;
+                        let message = match kind {
+                            // The message is the last argument of `assert_failed`, and is
+                            // formatted with the values being compared.
+                            PanicKind::AssertFailed => None,
+                            PanicKind::Explicit | PanicKind::Unwrap => args
+                                .first()
+                                .and_then(|arg| self.find_panic_message(statements, arg)),
+                        };
+                        return Ok(RawTerminator::Abort(AbortKind::Panic {
+                            name,
+                            kind,
+                            message,
+                        }));
                     }
                     SubstFunIdOrPanic::Fun(fid) => {
-                        let fn_operand = FnOperand::Regular(fid.func);
-                        let args = fid.args.unwrap();
+                        let mut args = fid.args.unwrap();
+                        // Methods called on a trait object are dispatched through its vtable.
+                        let fn_operand = if let Some(fn_ptr) = self
+                            .translate_boxed_closure_call(span, statements, &fid.func, &mut args)
+                        {
+                            FnOperand::Dynamic(fn_ptr)
+                        } else {
+                            match &fid.func.func {
+                                FunIdOrTraitMethodRef::Trait(trait_ref, ..)
+                                    if matches!(trait_ref.kind, TraitRefKind::Dyn(_)) =>
+                                {
+                                    FnOperand::Dynamic(fid.func)
+                                }
+                                _ => FnOperand::Regular(fid.func),
+                            }
+                        };
                         (fn_operand, args)
                     }
                 }
@@ -1206,6 +1313,171 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
         })
     }
 
+    /// Calling a boxed closure (`Box<dyn Fn(..)>` or `Box<dyn FnMut(..)>`) goes through the impl
+    /// of `Fn`/`FnMut` for `Box`, which forwards the call to the trait object. We instead make the
+    /// call dispatch on the trait object directly:
+    /// ```text
+    /// @3 := <Box<dyn Fn(u32) -> u32> as Fn<(u32,)>>::call(move @4, move @5)
+    /// ```
+    /// becomes
+    /// ```text
+    /// @6 := &*(*@4)
+    /// @3 := (dyn Fn<(u32,)>::call)(move @6, move @5)
+    /// ```
+    /// Returns the function to call dynamically, after updating the receiver in `args`, or `None`
+    /// if this is not such a call. We leave the calls to `FnOnce::call_once` untouched since we
+    /// can't pass an unsized trait object by value.
+    fn translate_boxed_closure_call(
+        &mut self,
+        span: Span,
+        statements: &mut Vec<Statement>,
+        fn_ptr: &FnPtr,
+        args: &mut [Operand],
+    ) -> Option<FnPtr> {
+        if boxes_are_desugared(self.t_ctx.options.mir_level) {
+            return None;
+        }
+        let FunIdOrTraitMethodRef::Trait(trait_ref, method_name, method_id) = &fn_ptr.func else {
+            return None;
+        };
+        let TraitRefKind::TraitImpl(_, impl_args) = &trait_ref.kind else {
+            return None;
+        };
+        let trait_id = trait_ref.trait_decl_ref.skip_binder.trait_id;
+        let trait_name = self.t_ctx.translated.item_name(trait_id)?;
+        let borrow_kind = if trait_name.equals_ref_name(&["core", "ops", "function", "Fn"]) {
+            BorrowKind::Shared
+        } else if trait_name.equals_ref_name(&["core", "ops", "function", "FnMut"]) {
+            BorrowKind::Mut
+        } else {
+            return None;
+        };
+        // The receiver is a reference to the box.
+        let (Operand::Move(receiver) | Operand::Copy(receiver)) = args.first()? else {
+            return None;
+        };
+        let TyKind::Ref(_, box_ty, ref_kind) = receiver.ty().kind() else {
+            return None;
+        };
+        let TyKind::Adt(TypeId::Builtin(BuiltinTy::Box), box_args) = box_ty.kind() else {
+            return None;
+        };
+        let dyn_ty = box_args.types.iter().next()?;
+        if !matches!(dyn_ty.kind(), TyKind::DynTrait(_)) {
+            return None;
+        }
+        // The impl for `Box<F>` requires `F` to implement the trait: for `F = dyn Trait`, this
+        // clause is the trait object itself.
+        let dyn_trait_ref = impl_args.trait_refs.iter().find(|tref| {
+            matches!(tref.kind, TraitRefKind::Dyn(_))
+                && tref.trait_decl_ref.skip_binder.trait_id == trait_id
+        })?;
+
+        let dyn_place = receiver
+            .clone()
+            .project(ProjectionElem::Deref, box_ty.clone())
+            .project(ProjectionElem::Deref, dyn_ty.clone());
+        let new_receiver = self.locals.new_var(
+            None,
+            TyKind::Ref(Region::Erased, dyn_ty.clone(), *ref_kind).into_ty(),
+        );
+        statements.push(Statement::new(
+            span,
+            RawStatement::Assign(new_receiver.clone(), Rvalue::Ref(dyn_place, borrow_kind)),
+        ));
+        args[0] = Operand::Move(new_receiver);
+        Some(FnPtr {
+            func: FunIdOrTraitMethodRef::Trait(
+                dyn_trait_ref.clone(),
+                method_name.clone(),
+                *method_id,
+            ),
+            generics: fn_ptr.generics.clone(),
+        })
+    }
+
+    /// Compute the kind of a panic, given the name of the panicking function.
+    fn translate_panic_kind(&self, name: &Name) -> PanicKind {
+        if name.equals_ref_name(&["core", "panicking", "assert_failed"]) {
+            PanicKind::AssertFailed
+        } else if UNWRAP_FAILED_NAMES
+            .iter()
+            .any(|unwrap_fn| name.equals_ref_name(unwrap_fn))
+        {
+            PanicKind::Unwrap
+        } else {
+            PanicKind::Explicit
+        }
+    }
+
+    /// Try to recover the message of a panic from the argument given to the panicking function.
+    /// This only succeeds if the message is a constant string. We follow the assignments that
+    /// built the argument; e.g. for `panic!("boom")` we have:
+    /// ```text
+    /// @3 := [const ("boom")]
+    /// @2 := &@3
+    /// @1 := core::fmt::{core::fmt::Arguments<'a>}::new_const(move @2)
+    /// panic(core::panicking::panic_fmt)
+    /// ```
+    /// `statements` are the statements of the block being translated; the previous blocks are
+    /// already in `self.blocks`.
+    fn find_panic_message(&self, statements: &[Statement], arg: &Operand) -> Option<String> {
+        use itertools::Either;
+        let mut op = arg.clone();
+        loop {
+            let var_id = match &op {
+                Operand::Const(ConstantExpr {
+                    value: RawConstantExpr::Literal(Literal::Str(msg)),
+                    ..
+                }) => return Some(msg.clone()),
+                Operand::Move(place) | Operand::Copy(place) => place.as_local()?,
+                Operand::Const(_) => return None,
+            };
+            // Find the unique assignment to this variable.
+            let assignment = self
+                .blocks
+                .values()
+                .flat_map(|block| block.statements.iter())
+                .chain(statements)
+                .filter_map(|st| match &st.content {
+                    RawStatement::Assign(dest, rvalue) if dest.as_local() == Some(var_id) => {
+                        Some(Either::Left(rvalue))
+                    }
+                    RawStatement::Call(call) if call.dest.as_local() == Some(var_id) => {
+                        Some(Either::Right(call))
+                    }
+                    _ => None,
+                })
+                .exactly_one()
+                .ok()?;
+            op = match assignment {
+                Either::Left(Rvalue::Use(op) | Rvalue::UnaryOp(UnOp::Cast(_), op)) => op.clone(),
+                Either::Left(Rvalue::Ref(place, _)) => Operand::Copy(place.clone()),
+                Either::Left(Rvalue::Aggregate(AggregateKind::Array(..), ops)) => {
+                    ops.iter().exactly_one().ok()?.clone()
+                }
+                // The constructor of `core::fmt::Arguments` used for constant messages.
+                Either::Right(Call {
+                    func:
+                        FnOperand::Regular(FnPtr {
+                            func: FunIdOrTraitMethodRef::Fun(FunId::Regular(fun_id)),
+                            ..
+                        }),
+                    args,
+                    ..
+                }) => {
+                    let name = self.t_ctx.translated.item_name(*fun_id)?;
+                    match name.name.last()? {
+                        PathElem::Ident(ident, _) if ident == "new_const" => {}
+                        _ => return None,
+                    }
+                    args.iter().exactly_one().ok()?.clone()
+                }
+                _ => return None,
+            };
+        }
+    }
+
     /// Evaluate function arguments in a context, and return the list of computed
     /// values.
     fn translate_arguments(
@@ -1295,10 +1567,30 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
         sig: &FunSig,
         item_meta: &ItemMeta,
     ) -> Result<Result<Body, Opaque>, Error> {
-        if item_meta.opacity.with_private_contents().is_opaque() {
+        // The constructors of enum variants are public like the variants themselves, so we
+        // translate them even for foreign enums. This lets consumers see through e.g. `map(Some)`.
+        let is_variant_ctor = matches!(
+            def.kind(),
+            hax::FullDefKind::Ctor {
+                ctor_of: hax::CtorOf::Variant,
+                ..
+            }
+        );
+        if item_meta
+            .opacity
+            .with_content_visibility(is_variant_ctor)
+            .is_opaque()
+        {
             // The bodies of foreign functions are opaque by default.
             return Ok(Err(Opaque));
         }
+        // `Rc` and `Arc` are builtin types for us, so we can't express the accesses to their
+        // fields which their implementation does.
+        if item_meta.name.starts_with(&["alloc", "rc"])
+            || item_meta.name.starts_with(&["alloc", "sync"])
+        {
+            return Ok(Err(Opaque));
+        }
 
         if let hax::FullDefKind::Ctor {
             adt_def_id,
@@ -1586,6 +1878,7 @@ impl BodyTransCtx<'_, '_> {
             kind,
             is_global_initializer,
             body: body_id,
+            calls: Default::default(),
         })
     }
 
@@ -1611,7 +1904,7 @@ impl BodyTransCtx<'_, '_> {
         self.translate_def_generics(span, def)?;
 
         // Retrieve the kind
-        let global_kind = self.get_item_kind(span, def)?;
+        let item_kind = self.get_item_kind(span, def)?;
 
         trace!("Translating global type");
         let ty = match &def.kind {
@@ -1622,6 +1915,8 @@ impl BodyTransCtx<'_, '_> {
         };
         let ty = self.translate_ty(span, ty)?;
 
+        let global_kind = self.translate_global_kind(rust_id, def, &ty);
+
         let initializer = self.register_fun_decl_id(span, rust_id);
 
         Ok(GlobalDecl {
@@ -1629,8 +1924,36 @@ impl BodyTransCtx<'_, '_> {
             item_meta,
             generics: self.into_generics(),
             ty,
-            kind: global_kind,
+            kind: item_kind,
+            global_kind,
             init: initializer,
         })
     }
+
+    /// Compute the kind of a global. `thread_local!` expands to a constant of type
+    /// `std::thread::LocalKey<T>`, which we flag as thread-local like `#[thread_local]` statics.
+    fn translate_global_kind(&self, rust_id: DefId, def: &hax::FullDef, ty: &Ty) -> GlobalKind {
+        let tcx = self.t_ctx.tcx;
+        let is_local_key = match ty.kind() {
+            TyKind::Adt(TypeId::Adt(id), _) => self
+                .t_ctx
+                .translated
+                .item_name(*id)
+                .is_some_and(|name| name.equals_ref_name(LOCAL_KEY)),
+            _ => false,
+        };
+        if is_local_key {
+            return GlobalKind::ThreadLocal;
+        }
+        match &def.kind {
+            hax::FullDefKind::Static { .. } if tcx.is_thread_local_static(rust_id) => {
+                GlobalKind::ThreadLocal
+            }
+            hax::FullDefKind::Static { .. } => match tcx.static_mutability(rust_id) {
+                Some(rustc_hir::Mutability::Mut) => GlobalKind::StaticMut,
+                _ => GlobalKind::Static,
+            },
+            _ => GlobalKind::NamedConst,
+        }
+    }
 }
@@ -5,6 +5,9 @@ use charon_lib::formatter::IntoFormatter;
 use charon_lib::ids::Vector;
 use charon_lib::pretty::FmtWithCtx;
 use hax_frontend_exporter as hax;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::DefId;
+use rustc_middle::ty;
 
 impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
     /// This function should be called **after** we translated the generics (type parameters,
@@ -68,6 +71,63 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
         Ok(TraitDeclRef { trait_id, generics })
     }
 
+    /// Whether the bound on `trait_id` at that span is a `~const` or `const` bound. Hax doesn't
+    /// tell us the constness of bounds, so we look at how rustc desugars them with the `effects`
+    /// feature: `T: ~const Trait` comes with a `<T as Trait>::Effects: EffectsCompat<HOST>` clause
+    /// with the same span, where `HOST` is not `true`; a `~const` supertrait comes with an
+    /// `EffectsTyCompat` clause instead. We look for such a clause among the predicates of the
+    /// current item and of its parents.
+    fn is_const_bound(&self, hspan: &hax::Span, trait_id: &hax::DefId) -> bool {
+        let Some(span) = &hspan.rust_span_data else {
+            return false;
+        };
+        let span = span.span();
+        let tcx = self.t_ctx.tcx;
+        let lang_items = tcx.lang_items();
+        let (Some(compat), Some(ty_compat)) =
+            (lang_items.effects_compat(), lang_items.effects_ty_compat())
+        else {
+            return false;
+        };
+        let trait_id: DefId = trait_id.into();
+        // Whether this is `<_ as Trait>::Effects`.
+        let is_trait_effects = |ty: ty::Ty<'_>| match ty.kind() {
+            ty::Alias(ty::Projection, alias) => alias.trait_def_id(tcx) == trait_id,
+            _ => false,
+        };
+        let mut def_id = Some(self.def_id);
+        while let Some(id) = def_id {
+            let predicates = tcx.predicates_of(id);
+            let implied = if let DefKind::Trait = tcx.def_kind(id) {
+                tcx.explicit_implied_predicates_of(id).skip_binder()
+            } else {
+                &[]
+            };
+            for (clause, clause_span) in predicates.predicates.iter().chain(implied) {
+                if *clause_span != span {
+                    continue;
+                }
+                let Some(pred) = clause.as_trait_clause() else {
+                    continue;
+                };
+                let trait_ref = pred.skip_binder().trait_ref;
+                let args = trait_ref.args;
+                let is_const = if trait_ref.def_id == compat {
+                    is_trait_effects(args.type_at(0)) && args.const_at(1) != tcx.consts.true_
+                } else if trait_ref.def_id == ty_compat {
+                    is_trait_effects(args.type_at(1))
+                } else {
+                    false
+                };
+                if is_const {
+                    return true;
+                }
+            }
+            def_id = predicates.parent;
+        }
+        false
+    }
+
     pub(crate) fn register_predicate(
         &mut self,
         clause: &hax::Clause,
@@ -83,6 +143,7 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                 let pred = self.translate_region_binder(span, &clause.kind, |ctx, _| {
                     ctx.translate_trait_predicate(span, trait_pred)
                 })?;
+                let is_const = self.is_const_bound(hspan, &trait_pred.trait_ref.def_id);
                 let location = match location {
                     PredicateLocation::Base => &mut self.innermost_generics_mut().trait_clauses,
                     PredicateLocation::Parent => &mut self.parent_trait_clauses,
@@ -94,6 +155,7 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                 location.push_with(|clause_id| TraitClause {
                     clause_id,
                     origin,
+                    is_const,
                     span: Some(span),
                     trait_: pred,
                 });
@@ -143,8 +205,10 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
             ClauseKind::WellFormed(_) => {
                 raise_error!(self, span, "Well-formedness clauses are unsupported")
             }
-            kind @ ClauseKind::ConstEvaluatable(_) => {
-                raise_error!(self, span, "Unsupported clause: {:?}", kind)
+            ClauseKind::ConstEvaluatable(_) => {
+                // These come from `where [(); N + 1]:` bounds, which `generic_const_exprs`
+                // requires for the constant expressions that appear in the signature. They only
+                // state that the constant can be evaluated, so we ignore them.
             }
         }
         Ok(())
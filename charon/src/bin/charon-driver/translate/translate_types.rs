@@ -3,11 +3,13 @@ use crate::translate::translate_traits::PredicateLocation;
 use super::translate_ctx::*;
 use charon_lib::ast::*;
 use charon_lib::builtins;
+use charon_lib::common::ensure_sufficient_stack;
 use charon_lib::common::hash_by_addr::HashByAddr;
 use charon_lib::ids::Vector;
 use core::convert::*;
 use hax::Visibility;
 use hax_frontend_exporter as hax;
+use rustc_hir::def_id::DefId;
 
 /// Small helper: we ignore some region names (when they are equal to "'_")
 fn check_region_name(s: String) -> Option<String> {
@@ -76,6 +78,12 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
     /// regions), in which case the return type is different.
     #[tracing::instrument(skip(self, span))]
     pub(crate) fn translate_ty(&mut self, span: Span, ty: &hax::Ty) -> Result<Ty, Error> {
+        // Types can be nested arbitrarily deep (e.g. long chains of iterator adapters or
+        // futures), so we grow the stack as needed.
+        ensure_sufficient_stack(|| self.translate_ty_inner(span, ty))
+    }
+
+    fn translate_ty_inner(&mut self, span: Span, ty: &hax::Ty) -> Result<Ty, Error> {
         trace!("{:?}", ty);
         let cache_key = HashByAddr(ty.inner().clone());
         if let Some(ty) = self.lookup_cached_type(&cache_key) {
@@ -377,7 +385,11 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
         let ty = if def.lang_item.as_deref() == Some("owned_box") {
             Some(BuiltinTy::Box)
         } else {
-            None
+            match def.diagnostic_item.as_deref() {
+                Some("Rc") => Some(BuiltinTy::Rc),
+                Some("Arc") => Some(BuiltinTy::Arc),
+                _ => None,
+            }
         };
         Ok(ty)
     }
@@ -472,11 +484,17 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                     }
                 };
 
+                // Give the fields of tuple structs and variants their index as name, if asked.
+                let field_name = match field_name {
+                    None if self.t_ctx.options.name_tuple_fields => Some(j.to_string()),
+                    name => name,
+                };
+
                 // Store the field
                 let field = Field {
                     span: field_span,
                     attr_info: field_attrs,
-                    name: field_name.clone(),
+                    name: field_name,
                     ty,
                 };
                 fields.push(field);
@@ -487,12 +505,18 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
             let variant_name = var_def.name.clone();
             let variant_full_def = self.t_ctx.hax_def(&var_def.def_id)?;
             let variant_attrs = self.t_ctx.translate_attr_info(&variant_full_def);
+            let non_exhaustive = variant_attrs
+                .attributes
+                .iter()
+                .filter_map(|attr| attr.as_unknown())
+                .any(|attr| attr.path == "non_exhaustive");
 
             let mut variant = Variant {
                 span: variant_span,
                 attr_info: variant_attrs,
                 name: variant_name,
                 fields,
+                non_exhaustive,
                 discriminant,
             };
             // Propagate a `#[charon::variants_prefix(..)]` or `#[charon::variants_suffix(..)]` attribute to the variants.
@@ -731,16 +755,24 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
                     .innermost_binder_mut()
                     .push_type_var(param.index, param.name.clone());
             }
-            hax::GenericParamDefKind::Const { ty, .. } => {
+            hax::GenericParamDefKind::Const {
+                ty, has_default, ..
+            } => {
                 let span = self.def_span(&param.def_id);
                 // The type should be primitive, meaning it shouldn't contain variables,
                 // non-primitive adts, etc. As a result, we can use an empty context.
                 let ty = self.translate_ty(span, ty)?;
+                let default = if *has_default {
+                    Some(self.translate_const_param_default(span, &param.def_id)?)
+                } else {
+                    None
+                };
                 match ty.kind().as_literal() {
                     Some(ty) => self.innermost_binder_mut().push_const_generic_var(
                         param.index,
                         *ty,
                         param.name.clone(),
+                        default,
                     ),
                     None => raise_error!(
                         self,
@@ -753,6 +785,20 @@ impl<'tcx, 'ctx> BodyTransCtx<'tcx, 'ctx> {
 
         Ok(())
     }
+
+    /// Translate the default value of a const generic parameter, e.g. `4` in `const N: usize = 4`.
+    fn translate_const_param_default(
+        &mut self,
+        span: Span,
+        param_def_id: &hax::DefId,
+    ) -> Result<ConstGeneric, Error> {
+        let param_def_id: DefId = param_def_id.into();
+        let tcx = self.t_ctx.tcx;
+        let default = tcx.const_param_default(param_def_id).instantiate_identity();
+        let state = self.t_ctx.hax_state.clone().with_owner_id(self.def_id);
+        let default: hax::ConstantExpr = self.t_ctx.catch_sinto(&state, span, &default)?;
+        self.translate_constant_expr_to_const_generic(span, &default)
+    }
 }
 
 impl BodyTransCtx<'_, '_> {
@@ -796,13 +842,75 @@ impl BodyTransCtx<'_, '_> {
             Ok(kind) => kind,
             Err(err) => TypeDeclKind::Error(err.msg),
         };
+        let align = match &def.kind {
+            hax::FullDefKind::Struct { .. }
+            | hax::FullDefKind::Enum { .. }
+            | hax::FullDefKind::Union { .. } => {
+                let adt_def = self.t_ctx.tcx.adt_def(def.rust_def_id());
+                adt_def.repr().align.map(|align| align.bytes())
+            }
+            _ => None,
+        };
+        // Link the type to its destructor. The `impl Drop` of a type lives in the crate of the
+        // type, so we only need to look at local types.
+        let drop_impl = match &def.kind {
+            hax::FullDefKind::Struct { .. }
+            | hax::FullDefKind::Enum { .. }
+            | hax::FullDefKind::Union { .. }
+                if def.rust_def_id().is_local() =>
+            {
+                let tcx = self.t_ctx.tcx;
+                tcx.adt_destructor(def.rust_def_id()).map(|destructor| {
+                    let impl_id = tcx.parent(destructor.did);
+                    self.register_trait_impl_id(span, impl_id)
+                })
+            }
+            _ => None,
+        };
+        let simd = match &def.kind {
+            hax::FullDefKind::Struct { .. }
+                if self.t_ctx.tcx.adt_def(def.rust_def_id()).repr().simd() =>
+            {
+                translate_simd_repr(&kind)
+            }
+            _ => None,
+        };
         let type_def = TypeDecl {
             def_id: trans_id,
             item_meta,
             generics: self.into_generics(),
             kind,
+            align,
+            drop_impl,
+            simd,
         };
 
         Ok(type_def)
     }
 }
+
+/// Compute the lanes of a `#[repr(simd)]` struct. The vector is either a single array field, e.g.
+/// `struct f32x4([f32; 4])`, or (in older code) a list of fields of the same type, e.g.
+/// `struct f32x4(f32, f32, f32, f32)`.
+fn translate_simd_repr(kind: &TypeDeclKind) -> Option<SimdRepr> {
+    let TypeDeclKind::Struct(fields) = kind else {
+        return None;
+    };
+    let first = fields.iter().next()?;
+    if fields.elem_count() == 1
+        && let TyKind::Adt(TypeId::Builtin(BuiltinTy::Array), generics) = first.ty.kind()
+    {
+        return Some(SimdRepr {
+            elem_ty: generics.types[0].clone(),
+            lanes: generics.const_generics[0].clone(),
+        });
+    }
+    if fields.iter().all(|field| field.ty == first.ty) {
+        let lanes = ScalarValue::Usize(fields.elem_count() as u64);
+        return Some(SimdRepr {
+            elem_ty: first.ty.clone(),
+            lanes: ConstGeneric::Value(Literal::Scalar(lanes)),
+        });
+    }
+    None
+}
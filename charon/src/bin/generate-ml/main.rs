@@ -27,6 +27,7 @@ fn repr_name(_crate_data: &TranslatedCrate, n: &Name) -> String {
         .map(|path_elem| match path_elem {
             PathElem::Ident(i, _) => i.clone(),
             PathElem::Impl(..) => "<impl>".to_string(),
+            PathElem::Monomorphized(..) => "<mono>".to_string(),
         })
         .join("::")
 }
@@ -1065,13 +1066,7 @@ fn generate_ml(
         ),
     ];
     // Types for which we don't want to generate a type at all.
-    let dont_generate_ty = &[
-        "ItemOpacity",
-        "PredicateOrigin",
-        "TraitTypeConstraintId",
-        "Ty",
-        "Vector",
-    ];
+    let dont_generate_ty = &["ItemOpacity", "TraitTypeConstraintId", "Ty", "Vector"];
     // Types that we don't want visitors to go into.
     let opaque_for_visitor = &["Name"];
     let ctx = GenerateCtx::new(
@@ -1084,7 +1079,6 @@ fn generate_ml(
     // Compute the sets of types to be put in each module.
     let manually_implemented: HashSet<_> = [
         "ItemOpacity",
-        "PredicateOrigin",
         "Ty", // We exclude it since `TyKind` is renamed to `ty`
         "Opaque",
         "Body",
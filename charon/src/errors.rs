@@ -1,11 +1,13 @@
 //! Utilities to generate error reports about the external dependencies.
 use crate::ast::*;
 use crate::formatter::{Formatter, IntoFormatter};
+use crate::options::ErrorFormat;
 pub use annotate_snippets::Level;
 use itertools::Itertools;
 use macros::VariantIndexArity;
 use petgraph::algo::dijkstra::dijkstra;
 use petgraph::prelude::DiGraphMap;
+use serde::Serialize;
 use std::cmp::{Ord, PartialOrd};
 use std::collections::{HashMap, HashSet};
 
@@ -111,6 +113,54 @@ impl Error {
         let out = Renderer::styled().render(message).to_string();
         out
     }
+
+    /// Render the error as a one-line JSON object, for `--error-format=json`.
+    pub(crate) fn render_json(&self, krate: &TranslatedCrate, level: Level) -> String {
+        JsonDiagnostic::new(krate, level, &self.msg, &[self.span]).to_string()
+    }
+}
+
+/// A diagnostic, as printed with `--error-format=json`.
+#[derive(Serialize)]
+struct JsonDiagnostic<'a> {
+    /// `error`, `warning` or `note`.
+    level: String,
+    message: &'a str,
+    spans: Vec<JsonSpan>,
+}
+
+#[derive(Serialize)]
+struct JsonSpan {
+    file: Option<String>,
+    beg: Loc,
+    end: Loc,
+}
+
+impl<'a> JsonDiagnostic<'a> {
+    fn new(krate: &TranslatedCrate, level: Level, message: &'a str, spans: &[Span]) -> Self {
+        let spans = spans
+            .iter()
+            .map(|span| JsonSpan {
+                file: krate
+                    .files
+                    .get(span.span.file_id)
+                    .map(|file| file.name.to_string()),
+                beg: span.span.beg,
+                end: span.span.end,
+            })
+            .collect();
+        JsonDiagnostic {
+            level: format!("{level:?}").to_lowercase(),
+            message,
+            spans,
+        }
+    }
+}
+
+impl std::fmt::Display for JsonDiagnostic<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::to_string(self).unwrap())
+    }
 }
 
 /// We use this to save the origin of an id. This is useful for the external
@@ -176,6 +226,8 @@ pub struct ErrorCtx {
     pub continue_on_failure: bool,
     /// If true, print the warnings as errors, and abort if any errors were raised.
     pub error_on_warnings: bool,
+    /// The format in which to print the errors.
+    pub error_format: ErrorFormat,
 
     /// The ids of the external_declarations for which extraction we encountered errors.
     pub external_decls_with_errors: HashSet<AnyTransId>,
@@ -194,10 +246,15 @@ pub struct ErrorCtx {
 }
 
 impl ErrorCtx {
-    pub fn new(continue_on_failure: bool, error_on_warnings: bool) -> Self {
+    pub fn new(
+        continue_on_failure: bool,
+        error_on_warnings: bool,
+        error_format: ErrorFormat,
+    ) -> Self {
         Self {
             continue_on_failure,
             error_on_warnings,
+            error_format,
             external_decls_with_errors: HashSet::new(),
             ignored_failed_decls: HashSet::new(),
             external_dep_graph: DepGraph::new(),
@@ -223,7 +280,10 @@ impl ErrorCtx {
         msg: String,
     ) -> Error {
         let error = Error { span, msg };
-        anstream::eprintln!("{}\n", error.render(krate, level));
+        match self.error_format {
+            ErrorFormat::Human => anstream::eprintln!("{}\n", error.render(krate, level)),
+            ErrorFormat::Json => eprintln!("{}", error.render_json(krate, level)),
+        }
         error
     }
 
@@ -302,6 +362,18 @@ impl ErrorCtx {
             })
             .into_group_map_by(|span| span.span.file_id);
 
+        let msg = format!(
+            "the error occurred when translating `{}`, \
+             which is (transitively) used at the following location(s):",
+            krate.into_fmt().format_object(id)
+        );
+        let level = Level::Note;
+        if self.error_format == ErrorFormat::Json {
+            let spans = by_file.into_values().flatten().sorted().collect_vec();
+            eprintln!("{}", JsonDiagnostic::new(krate, level, &msg, &spans));
+            return;
+        }
+
         // Collect to a `Vec` to be able to sort it and to borrow `origin` (needed by
         // `Snippet::source`).
         let mut by_file: Vec<(FileId, _, _, Vec<Span>)> = by_file
@@ -317,7 +389,6 @@ impl ErrorCtx {
         // Sort by file id to avoid output instability.
         by_file.sort_by_key(|(file_id, ..)| *file_id);
 
-        let level = Level::Note;
         let snippets = by_file.iter().map(|(_, origin, source, spans)| {
             Snippet::source(source)
                 .origin(&origin)
@@ -329,11 +400,6 @@ impl ErrorCtx {
                 )
         });
 
-        let msg = format!(
-            "the error occurred when translating `{}`, \
-             which is (transitively) used at the following location(s):",
-            krate.into_fmt().format_object(id)
-        );
         let message = level.title(&msg).snippets(snippets);
         let out = Renderer::styled().render(message).to_string();
         anstream::eprintln!("{}", out);
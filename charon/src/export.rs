@@ -13,17 +13,30 @@ pub struct CrateData {
     /// trying to read an incompatible version (for now we compare versions for equality).
     #[serde(deserialize_with = "ensure_version")]
     pub charon_version: String,
+    /// The git commit charon was built from, or `"unknown"`.
+    pub charon_commit: String,
+    /// The version of the compiler charon used to translate the crate, e.g. `1.84.0-nightly`.
+    pub rustc_version: String,
     pub translated: TranslatedCrate,
+    /// The external crates that the translated items refer to, sorted by name.
+    pub external_crates: Vec<String>,
+    /// The items from external crates whose contents we didn't translate: functions and globals
+    /// without a body, and types without fields/variants.
+    pub external_opaque_items: Vec<AnyTransId>,
     #[serde(skip)]
     /// If there were errors, this contains only a partial description of the input crate.
     pub has_errors: bool,
 }
 
 impl CrateData {
-    pub fn new(ctx: &TransformCtx) -> Self {
+    pub fn new(ctx: &TransformCtx, rustc_version: &str) -> Self {
         CrateData {
             charon_version: crate::VERSION.to_owned(),
+            charon_commit: crate::COMMIT_HASH.to_owned(),
+            rustc_version: rustc_version.to_owned(),
             translated: ctx.translated.clone(),
+            external_crates: external_crates(&ctx.translated),
+            external_opaque_items: external_opaque_items(&ctx.translated),
             has_errors: ctx.has_errors(),
         }
     }
@@ -72,6 +85,43 @@ impl CrateData {
     }
 }
 
+/// The names of the crates that the non-local items come from.
+fn external_crates(translated: &TranslatedCrate) -> Vec<String> {
+    let mut crates = translated
+        .all_items()
+        .map(|item| item.item_meta())
+        .filter(|meta| !meta.is_local)
+        .filter_map(|meta| match meta.name.name.first()? {
+            PathElem::Ident(krate, _) => Some(krate.clone()),
+            PathElem::Impl(..) | PathElem::Monomorphized(..) => None,
+        })
+        .collect::<Vec<_>>();
+    crates.sort();
+    crates.dedup();
+    crates
+}
+
+/// The non-local items whose contents weren't translated.
+fn external_opaque_items(translated: &TranslatedCrate) -> Vec<AnyTransId> {
+    let fun_is_opaque = |id: FunDeclId| {
+        translated
+            .fun_decls
+            .get(id)
+            .is_none_or(|decl| decl.body.is_err())
+    };
+    translated
+        .all_items_with_ids()
+        .filter(|(_, item)| !item.item_meta().is_local)
+        .filter(|(_, item)| match item {
+            AnyTransItem::Type(decl) => matches!(decl.kind, TypeDeclKind::Opaque),
+            AnyTransItem::Fun(decl) => decl.body.is_err(),
+            AnyTransItem::Global(decl) => fun_is_opaque(decl.init),
+            AnyTransItem::TraitDecl(_) | AnyTransItem::TraitImpl(_) => false,
+        })
+        .map(|(id, _)| id)
+        .collect()
+}
+
 fn ensure_version<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
     use serde::de::Error;
     let version = String::deserialize(d)?;
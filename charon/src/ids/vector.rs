@@ -226,7 +226,7 @@ where
             .flat_map(|(i, opt)| Some((i, opt.as_ref()?)))
     }
 
-    pub fn iter_mut_indexed(&mut self) -> impl Iterator<Item = (I, &mut T)> {
+    pub fn iter_indexed_mut(&mut self) -> impl Iterator<Item = (I, &mut T)> {
         self.vector
             .iter_mut_enumerated()
             .flat_map(|(i, opt)| Some((i, opt.as_mut()?)))
@@ -44,6 +44,8 @@ pub use transform::{graphs, reorder_decls, ullbc_to_llbc};
 
 /// The version of the crate, as defined in `Cargo.toml`.
 const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// The git commit charon was built from, or `"unknown"` if it was not built from a git checkout.
+const COMMIT_HASH: &str = env!("CHARON_COMMIT_HASH");
 
 /// Read a `.llbc` file.
 pub fn deserialize_llbc(path: &std::path::Path) -> anyhow::Result<ast::TranslatedCrate> {
@@ -241,6 +241,139 @@ pub struct CliOpts {
     "))]
     #[serde(default)]
     pub no_merge_goto_chains: bool,
+    /// Replace calls to `is_some`/`is_none`/`is_ok`/`is_err` (and the equivalent matches) with a
+    /// comparison on the discriminant of the `Option`/`Result`.
+    #[clap(long = "lower-discriminant-checks")]
+    #[serde(default)]
+    pub lower_discriminant_checks: bool,
+    /// Measure the time spent in each transformation pass, and print a summary to stderr.
+    #[clap(long = "time-passes")]
+    #[serde(default)]
+    pub time_passes: bool,
+    /// Reconstruct `let`-else statements (e.g. `let Some(x) = opt else { return };`) instead of
+    /// leaving them as a match with a diverging branch.
+    #[clap(long = "reconstruct-let-else")]
+    #[serde(default)]
+    pub reconstruct_let_else: bool,
+    /// Remove the drops of values whose type is `Copy`, since these are no-ops.
+    #[clap(long = "remove-copy-drops")]
+    #[serde(default)]
+    pub remove_copy_drops: bool,
+    /// Only translate the items that match one of these patterns, along with their dependencies.
+    #[clap(
+        long = "only",
+        help = indoc!("
+            Only translate the items that match one of these patterns, along with the items they
+            depend on. This is useful to iterate quickly on a single function of a large crate.
+            The syntax is like `--include`, see the doc there.
+    "))]
+    #[serde(default)]
+    pub only: Vec<String>,
+    /// Conditional compilation options to pass to rustc, e.g. `--cfg feature=x`.
+    #[clap(
+        long = "cfg",
+        help = indoc!("
+            Set a conditional compilation option for the translated crate, e.g. `--cfg
+            feature=x` or `--cfg my_cfg`. The value gets quoted if needed, like rustc expects.
+    "))]
+    #[serde(default)]
+    pub cfg: Vec<String>,
+    /// Substitute the temporaries which are assigned once and used once in the statement that
+    /// uses them, e.g. `t := a + b; @0 := move t` becomes `@0 := a + b`.
+    #[clap(long = "substitute-single-use")]
+    #[serde(default)]
+    pub substitute_single_use: bool,
+    /// Reconstruct the `for` loops (e.g. `for x in iter { ... }`) instead of leaving them as a
+    /// loop which calls `Iterator::next`.
+    #[clap(long = "reconstruct-for-loops")]
+    #[serde(default)]
+    pub reconstruct_for_loops: bool,
+    /// The format in which to print the errors and warnings: `human` (the default) or `json`,
+    /// which prints each of them as a JSON object on its own line.
+    #[clap(long = "error-format", value_enum, default_value_t)]
+    #[serde(default)]
+    pub error_format: ErrorFormat,
+    /// Print statistics about the translated crate (number of items, of statements, etc.) at the
+    /// end of the transformations.
+    #[clap(long = "print-stats")]
+    #[serde(default)]
+    pub print_stats: bool,
+    /// Move the statements which end all the branches of a switch to the block where the branches
+    /// join, e.g. `if b { x := 1; y := 0 } else { x := 2; y := 0 }` becomes
+    /// `if b { x := 1 } else { x := 2 }; y := 0`.
+    #[clap(long = "sink-common-statements")]
+    #[serde(default)]
+    pub sink_common_statements: bool,
+    /// Hoist the non-trivial constants which appear several times in the crate (e.g. borrowed
+    /// arrays) into shared globals, and replace them with reads of those globals.
+    #[clap(long = "dedup-constants")]
+    #[serde(default)]
+    pub dedup_constants: bool,
+    /// Name the fields of tuple structs and tuple variants after their index, i.e. `"0"`, `"1"`,
+    /// etc., instead of leaving them unnamed.
+    #[clap(long = "name-tuple-fields")]
+    #[serde(default)]
+    pub name_tuple_fields: bool,
+    /// Reconstruct the `if let` chains, e.g. `if let Some(x) = a && let Ok(y) = b { .. }`, instead
+    /// of leaving them as nested matches.
+    #[clap(long = "reconstruct-let-chains")]
+    #[serde(default)]
+    pub reconstruct_let_chains: bool,
+    /// Keep the discriminant reads (`_x = discriminant(_y)`) and the switches on their value,
+    /// instead of turning them into `match`es on the enum variants.
+    #[clap(long = "keep-discriminant-reads")]
+    #[serde(default)]
+    pub keep_discriminant_reads: bool,
+    /// Set a coherent group of the transformation options at once, see [Preset]. The preset only
+    /// turns options on: the options passed individually are kept. The driver expands it before
+    /// using the options, hence the exported options show the ones it set.
+    #[clap(long = "preset", value_enum)]
+    #[serde(default)]
+    pub preset: Option<Preset>,
+    /// Keep the arithmetic overflow checks (e.g. the ones enabled with `-C overflow-checks=on`)
+    /// as assertions, instead of removing them like the other dynamic checks.
+    #[clap(long = "keep-overflow-checks")]
+    #[serde(default)]
+    pub keep_overflow_checks: bool,
+    /// Instantiate the generic functions and types at the concrete arguments they are used with,
+    /// starting from the non-generic items. The instances are added to the crate as new items.
+    #[clap(long = "monomorphize")]
+    #[serde(default)]
+    pub monomorphize: bool,
+    /// Translate the bodies of the items of the standard library (`core`, `alloc` and `std`) we
+    /// encounter, when their MIR is available, instead of leaving them opaque. This is equivalent
+    /// to `--include core --include alloc --include std`.
+    #[clap(long = "include-std")]
+    #[serde(default)]
+    pub include_std: bool,
+    /// Don't run the cleanup passes which are not needed to get well-formed (U)LLBC: the removal of
+    /// the unused `PhantomData` locals, of the overwritten `SetDiscriminant`s, of the trivial
+    /// assertions and of the match arms of the uninhabited variants, and the folding of
+    /// `size_of`/`align_of`. The calls to `black_box` and to the methods of the primitive types are
+    /// also kept as regular calls.
+    #[clap(long = "no-cleanup")]
+    #[serde(default)]
+    pub no_cleanup: bool,
+}
+
+/// The format in which to print the errors and warnings.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Serialize, Deserialize)]
+pub enum ErrorFormat {
+    /// Human-readable messages with source snippets.
+    #[default]
+    Human,
+    /// One JSON object per message, with the level, the message and the spans.
+    Json,
+}
+
+/// A named group of transformation options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, Serialize, Deserialize)]
+pub enum Preset {
+    /// The output expected by Aeneas: sets `--hide-marker-traits` and `--remove-copy-drops`.
+    Aeneas,
+    /// Stay as close to the MIR as possible: sets `--no-merge-goto-chains`,
+    /// `--keep-discriminant-reads` and `--no-cleanup`.
+    Raw,
 }
 
 impl CliOpts {
@@ -256,6 +389,23 @@ impl CliOpts {
             "Can't use --mir_promoted and --mir_optimized at the same time"
         );
     }
+
+    /// Set the options implied by `--preset`, if any. The preset only provides defaults, hence we
+    /// never turn off an option.
+    pub fn apply_preset(&mut self) {
+        match self.preset {
+            None => {}
+            Some(Preset::Aeneas) => {
+                self.hide_marker_traits = true;
+                self.remove_copy_drops = true;
+            }
+            Some(Preset::Raw) => {
+                self.no_merge_goto_chains = true;
+                self.keep_discriminant_reads = true;
+                self.no_cleanup = true;
+            }
+        }
+    }
 }
 
 /// TODO: maybe we should always target MIR Built, this would make things
@@ -287,6 +437,36 @@ pub struct TranslateOptions {
     pub hide_marker_traits: bool,
     /// Do not merge the chains of gotos.
     pub no_merge_goto_chains: bool,
+    /// Replace checks of the variant of an `Option`/`Result` with a discriminant comparison.
+    pub lower_discriminant_checks: bool,
+    /// Record the time spent in each transformation pass.
+    pub time_passes: bool,
+    /// Reconstruct `let`-else statements.
+    pub reconstruct_let_else: bool,
+    /// Remove the drops of `Copy` values.
+    pub remove_copy_drops: bool,
+    /// Substitute the single-use temporaries.
+    pub substitute_single_use: bool,
+    /// Reconstruct the `for` loops.
+    pub reconstruct_for_loops: bool,
+    /// Print statistics about the translated crate.
+    pub print_stats: bool,
+    /// Sink the common trailing statements of the branches of a switch.
+    pub sink_common_statements: bool,
+    /// Hoist the constants which appear several times into shared globals.
+    pub dedup_constants: bool,
+    /// Name the fields of tuple structs and variants after their index.
+    pub name_tuple_fields: bool,
+    /// Reconstruct the `if let` chains.
+    pub reconstruct_let_chains: bool,
+    /// Keep the discriminant reads instead of turning them into `match`es.
+    pub keep_discriminant_reads: bool,
+    /// Keep the arithmetic overflow checks.
+    pub keep_overflow_checks: bool,
+    /// Instantiate the generic functions and types at their concrete uses.
+    pub monomorphize: bool,
+    /// Don't run the cleanup passes we can do without.
+    pub no_cleanup: bool,
     /// Print the llbc just after control-flow reconstruction.
     pub print_built_llbc: bool,
     /// List of patterns to assign a given opacity to. Same as the corresponding `TranslateOptions`
@@ -294,6 +474,9 @@ pub struct TranslateOptions {
     pub item_opacities: Vec<(NamePattern, ItemOpacity)>,
     /// List of traits for which we transform associated types to type parameters.
     pub remove_associated_types: Vec<NamePattern>,
+    /// If non-empty, only the items that match one of these patterns (and their dependencies) are
+    /// translated.
+    pub only: Vec<NamePattern>,
 }
 
 impl TranslateOptions {
@@ -332,6 +515,12 @@ impl TranslateOptions {
             // We always include the items from the crate.
             opacities.push(("crate".to_owned(), Transparent));
 
+            if options.include_std {
+                for krate in ["core", "alloc", "std"] {
+                    opacities.push((krate.to_owned(), Transparent));
+                }
+            }
+
             for pat in options.include.iter() {
                 opacities.push((pat.to_string(), Transparent));
             }
@@ -361,18 +550,46 @@ impl TranslateOptions {
             .filter_map(|s| parse_pattern(&s).ok())
             .collect();
 
+        let only = options
+            .only
+            .iter()
+            .filter_map(|s| parse_pattern(&s).ok())
+            .collect();
+
         TranslateOptions {
             mir_level,
             no_code_duplication: options.no_code_duplication,
             hide_marker_traits: options.hide_marker_traits,
             no_merge_goto_chains: options.no_merge_goto_chains,
+            lower_discriminant_checks: options.lower_discriminant_checks,
+            time_passes: options.time_passes,
+            reconstruct_let_else: options.reconstruct_let_else,
+            remove_copy_drops: options.remove_copy_drops,
+            substitute_single_use: options.substitute_single_use,
+            reconstruct_for_loops: options.reconstruct_for_loops,
+            print_stats: options.print_stats,
+            sink_common_statements: options.sink_common_statements,
+            dedup_constants: options.dedup_constants,
+            name_tuple_fields: options.name_tuple_fields,
+            reconstruct_let_chains: options.reconstruct_let_chains,
+            keep_discriminant_reads: options.keep_discriminant_reads,
+            keep_overflow_checks: options.keep_overflow_checks,
+            monomorphize: options.monomorphize,
+            no_cleanup: options.no_cleanup,
             print_built_llbc: options.print_built_llbc,
             item_opacities,
             remove_associated_types,
             translate_all_methods: options.translate_all_methods,
+            only,
         }
     }
 
+    /// Whether we should start translation from this item. This is the case of all the items of
+    /// the crate, unless `--only` was passed.
+    pub fn is_translation_root(&self, krate: &TranslatedCrate, name: &Name) -> bool {
+        self.only.is_empty() || self.only.iter().any(|pat| pat.matches(krate, name))
+    }
+
     /// Find the opacity requested for the given name. This does not take into account
     /// `#[charon::opaque]` annotations, only cli parameters.
     pub fn opacity_for_name(&self, krate: &TranslatedCrate, name: &Name) -> ItemOpacity {
@@ -52,7 +52,7 @@ pub trait FmtWithCtx<C> {
 impl<C: AstFormatter> FmtWithCtx<C> for AbortKind {
     fn fmt_with_ctx_and_indent(&self, tab: &str, ctx: &C) -> String {
         match self {
-            AbortKind::Panic(name) => format!("{tab}panic({})", name.fmt_with_ctx(ctx)),
+            AbortKind::Panic { name, .. } => format!("{tab}panic({})", name.fmt_with_ctx(ctx)),
             AbortKind::UndefinedBehavior => format!("{tab}undefined_behavior"),
         }
     }
@@ -184,6 +184,17 @@ impl<C: AstFormatter> FmtWithCtx<C> for ConstGeneric {
             ConstGeneric::Var(id) => ctx.format_object(*id),
             ConstGeneric::Value(v) => v.to_string(),
             ConstGeneric::Global(id) => ctx.format_object(*id),
+            ConstGeneric::App(op, args) => match (op, args.as_slice()) {
+                (ConstGenericOp::BinOp(binop), [x, y]) => {
+                    format!("({} {binop} {})", x.fmt_with_ctx(ctx), y.fmt_with_ctx(ctx))
+                }
+                (ConstGenericOp::Neg, [x]) => format!("(-{})", x.fmt_with_ctx(ctx)),
+                (ConstGenericOp::Not, [x]) => format!("(!{})", x.fmt_with_ctx(ctx)),
+                _ => format!(
+                    "{op:?}({})",
+                    args.iter().map(|arg| arg.fmt_with_ctx(ctx)).format(", ")
+                ),
+            },
         }
     }
 }
@@ -191,7 +202,13 @@ impl<C: AstFormatter> FmtWithCtx<C> for ConstGeneric {
 impl<C: AstFormatter> FmtWithCtx<C> for ConstGenericVar {
     fn fmt_with_ctx(&self, ctx: &C) -> String {
         let ty = self.ty.fmt_with_ctx(ctx);
-        format!("const {} : {}", self.name, ty)
+        match &self.default {
+            Some(default) => {
+                let default = default.fmt_with_ctx(ctx);
+                format!("const {} : {} = {}", self.name, ty, default)
+            }
+            None => format!("const {} : {}", self.name, ty),
+        }
     }
 }
 
@@ -228,6 +245,7 @@ impl<C: AstFormatter> FmtWithCtx<C> for FnOperand {
     fn fmt_with_ctx(&self, ctx: &C) -> String {
         match self {
             FnOperand::Regular(func) => func.fmt_with_ctx(ctx),
+            FnOperand::Dynamic(func) => format!("(dyn {})", func.fmt_with_ctx(ctx)),
             FnOperand::Move(p) => format!("(move {})", p.fmt_with_ctx(ctx)),
         }
     }
@@ -693,6 +711,7 @@ impl<C: AstFormatter> FmtWithCtx<C> for PathElem {
                 };
                 format!("{impl_elem}{d}")
             }
+            PathElem::Monomorphized(args) => args.fmt_with_ctx(ctx),
         }
     }
 }
@@ -946,6 +965,7 @@ impl<C: AstFormatter> FmtWithCtx<C> for Rvalue {
                 format!("&raw mut {}", global_ref.fmt_with_ctx(ctx))
             }
             Rvalue::Len(place, ..) => format!("len({})", place.fmt_with_ctx(ctx)),
+            Rvalue::BlackBox(op) => format!("black_box({})", op.fmt_with_ctx(ctx)),
             Rvalue::Repeat(op, _ty, cg) => {
                 format!("[{}; {}]", op.fmt_with_ctx(ctx), cg.fmt_with_ctx(ctx))
             }
@@ -1132,6 +1152,46 @@ impl<C: AstFormatter> FmtWithCtx<C> for llbc::Statement {
                     body.fmt_with_ctx_and_indent(&inner_tab, ctx),
                 )
             }
+            RawStatement::LetElse(place, variant_id, bindings, else_block) => {
+                let inner_tab = format!("{tab}{TAB_INCR}");
+                write!(
+                    &mut out,
+                    "{tab}let {variant_id} = {} {{\n{}{tab}}}\n{tab}else {{\n{}{tab}}}",
+                    place.fmt_with_ctx(ctx),
+                    bindings.fmt_with_ctx_and_indent(&inner_tab, ctx),
+                    else_block.fmt_with_ctx_and_indent(&inner_tab, ctx),
+                )
+            }
+            RawStatement::ForLoop(iter, item, body) => {
+                let inner_tab = format!("{tab}{TAB_INCR}");
+                write!(
+                    &mut out,
+                    "{tab}for {} in {} {{\n{}{tab}}}",
+                    item.fmt_with_ctx(ctx),
+                    iter.fmt_with_ctx(ctx),
+                    body.fmt_with_ctx_and_indent(&inner_tab, ctx),
+                )
+            }
+            RawStatement::IfLet(conditions, then_block) => {
+                let inner_tab = format!("{tab}{TAB_INCR}");
+                for (i, cond) in conditions.iter().enumerate() {
+                    let prefix = if i == 0 { "if" } else { "&&" };
+                    writeln!(
+                        &mut out,
+                        "{tab}{prefix} let {} = {} {{\n{}{tab}}}\n{tab}else {{\n{}{tab}}}",
+                        cond.variant,
+                        cond.scrutinee.fmt_with_ctx(ctx),
+                        cond.bindings.fmt_with_ctx_and_indent(&inner_tab, ctx),
+                        cond.else_block.fmt_with_ctx_and_indent(&inner_tab, ctx),
+                    )
+                    .unwrap();
+                }
+                write!(
+                    &mut out,
+                    "{tab}then {{\n{}{tab}}}",
+                    then_block.fmt_with_ctx_and_indent(&inner_tab, ctx),
+                )
+            }
             RawStatement::Error(s) => write!(&mut out, "{tab}@ERROR({})", s),
         };
         out
@@ -1593,6 +1653,10 @@ impl std::fmt::Display for BuiltinFunId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
         let name = match *self {
             BuiltinFunId::BoxNew => "BoxNew",
+            BuiltinFunId::RcNew => "RcNew",
+            BuiltinFunId::ArcNew => "ArcNew",
+            BuiltinFunId::RcClone => "RcClone",
+            BuiltinFunId::ArcClone => "ArcClone",
             BuiltinFunId::ArrayToSliceShared => "ArrayToSliceShared",
             BuiltinFunId::ArrayToSliceMut => "ArrayToSliceMut",
             BuiltinFunId::ArrayRepeat => "ArrayRepeat",
@@ -1606,6 +1670,7 @@ impl std::fmt::Display for BuiltinFunId {
                 let mutability = mutability.variant_name();
                 &format!("{ty}{op}{mutability}")
             }
+            BuiltinFunId::PrimitiveMethod(method) => method.variant_name(),
         };
         f.write_str(name)
     }
@@ -0,0 +1,34 @@
+//! Record, for each function, the set of functions its body calls directly. This spares consumers
+//! (e.g. call-graph analyses) from having to walk the bodies themselves.
+use std::collections::BTreeSet;
+
+use crate::ast::*;
+use crate::transform::TransformCtx;
+
+use super::ctx::TransformPass;
+
+fn compute_callees(body: &Body) -> BTreeSet<FunDeclId> {
+    let mut calls = BTreeSet::new();
+    body.dyn_visit_in_body(|call: &Call| {
+        if let FnOperand::Regular(FnPtr {
+            func: FunIdOrTraitMethodRef::Fun(FunId::Regular(fun_id)),
+            ..
+        }) = &call.func
+        {
+            calls.insert(*fun_id);
+        }
+    });
+    calls
+}
+
+pub struct Transform;
+impl TransformPass for Transform {
+    fn transform_ctx(&self, ctx: &mut TransformCtx) {
+        for decl in ctx.translated.fun_decls.iter_mut() {
+            decl.calls = match &decl.body {
+                Ok(body) => compute_callees(body),
+                Err(Opaque) => BTreeSet::new(),
+            };
+        }
+    }
+}
@@ -225,6 +225,68 @@ impl<'ctx> TransformCtx {
         for_each!(trait_decls, TraitDecl);
         for_each!(trait_impls, TraitImpl);
     }
+
+    /// Compute statistics about the shape of the crate.
+    pub fn stats(&self) -> CrateStats {
+        CrateStats::new(&self.translated)
+    }
+}
+
+/// Statistics about the shape of a crate, printed with `--print-stats`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CrateStats {
+    pub functions: usize,
+    pub types: usize,
+    pub globals: usize,
+    pub trait_decls: usize,
+    pub trait_impls: usize,
+    /// The number of statements in all the bodies, including the nested ones for structured
+    /// bodies. This doesn't count the terminators of unstructured bodies.
+    pub statements: usize,
+    /// The largest number of blocks of an unstructured body, or 0 if all the bodies are
+    /// structured.
+    pub max_cfg_size: usize,
+    /// The number of functions whose body we didn't translate.
+    pub opaque_bodies: usize,
+}
+
+impl CrateStats {
+    pub fn new(krate: &TranslatedCrate) -> Self {
+        let mut stats = CrateStats {
+            functions: krate.fun_decls.elem_count(),
+            types: krate.type_decls.elem_count(),
+            globals: krate.global_decls.elem_count(),
+            trait_decls: krate.trait_decls.elem_count(),
+            trait_impls: krate.trait_impls.elem_count(),
+            ..CrateStats::default()
+        };
+        for decl in krate.fun_decls.iter() {
+            match &decl.body {
+                Ok(Body::Unstructured(body)) => {
+                    stats.statements += body.body.iter().map(|b| b.statements.len()).sum::<usize>();
+                    stats.max_cfg_size = stats.max_cfg_size.max(body.body.elem_count());
+                }
+                Ok(Body::Structured(body)) => body.visit_statements(|_| stats.statements += 1),
+                Err(Opaque) => stats.opaque_bodies += 1,
+            }
+        }
+        stats
+    }
+}
+
+impl fmt::Display for CrateStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# Crate statistics:")?;
+        writeln!(f, "{:>8}  functions", self.functions)?;
+        writeln!(f, "{:>8}  types", self.types)?;
+        writeln!(f, "{:>8}  globals", self.globals)?;
+        writeln!(f, "{:>8}  trait declarations", self.trait_decls)?;
+        writeln!(f, "{:>8}  trait implementations", self.trait_impls)?;
+        writeln!(f, "{:>8}  statements", self.statements)?;
+        writeln!(f, "{:>8}  blocks in the largest CFG", self.max_cfg_size)?;
+        writeln!(f, "{:>8}  opaque bodies", self.opaque_bodies)?;
+        Ok(())
+    }
 }
 
 impl<'a> IntoFormatter for &'a TransformCtx {
@@ -0,0 +1,209 @@
+//! # Micro-pass: hoist the non-trivial constants which appear several times in the crate into
+//! synthetic globals, and replace their occurrences with references to those globals. E.g. with:
+//! ```text
+//! fn f() -> &'static [u32; 8] { &[1, 2, 3, 4, 5, 6, 7, 8] }
+//! fn g() -> &'static [u32; 8] { &[1, 2, 3, 4, 5, 6, 7, 8] }
+//! ```
+//! both functions end up reading the same global `<shared_const>#0`. The `<shared_const>` name
+//! can't be written in Rust, hence it can't collide with the items of the crate.
+//!
+//! This must happen before [super::simplify_constants], which desugars the constants into
+//! statements.
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+use indexmap::IndexMap;
+
+use super::ctx::TransformPass;
+
+/// The size in bytes of the largest literal (`u128`): raw memory constants which are not bigger
+/// than this are left as they are.
+const MAX_TRIVIAL_RAW_MEMORY: usize = 16;
+
+/// Whether the constant is small enough that sharing it isn't worth it.
+fn is_trivial(c: &ConstantExpr) -> bool {
+    match &c.value {
+        RawConstantExpr::Literal(_)
+        | RawConstantExpr::Var(_)
+        | RawConstantExpr::Global(_)
+        | RawConstantExpr::TraitConst(..)
+        | RawConstantExpr::FnPtr(_)
+        // The address of the pointee matters for raw pointers, so we don't share it.
+        | RawConstantExpr::MutPtr(_) => true,
+        RawConstantExpr::RawMemory(bytes) => bytes.len() <= MAX_TRIVIAL_RAW_MEMORY,
+        RawConstantExpr::Ref(c) => is_trivial(c),
+        RawConstantExpr::Adt(_, fields) => fields.is_empty(),
+    }
+}
+
+/// Whether the constant refers to generic variables, in which case it can't be moved to a global
+/// without generics.
+fn mentions_generics(c: &ConstantExpr) -> bool {
+    let mut found = false;
+    c.dyn_visit(|_: &TypeDbVar| found = true);
+    c.dyn_visit(|_: &ConstGenericDbVar| found = true);
+    c.dyn_visit(|_: &RegionDbVar| found = true);
+    c.dyn_visit(|_: &ClauseDbVar| found = true);
+    found
+}
+
+/// The occurrences of a constant which we found in the bodies, with the first function where it
+/// appears.
+struct Occurrences {
+    count: usize,
+    first_user: FunDeclId,
+    /// The global we hoisted the constant into, if it appears several times.
+    global: Option<GlobalDeclId>,
+}
+
+pub struct Transform;
+impl TransformPass for Transform {
+    fn transform_ctx(&self, ctx: &mut TransformCtx) {
+        if !ctx.options.dedup_constants {
+            return;
+        }
+
+        // Count the occurrences of the constants.
+        let mut occurrences: IndexMap<ConstantExpr, Occurrences> = IndexMap::new();
+        for decl in ctx.translated.fun_decls.iter() {
+            let Some(body) = decl.body.as_ref().ok().and_then(|b| b.as_unstructured()) else {
+                continue;
+            };
+            body.body.dyn_visit_in_body(|op: &Operand| {
+                if let Operand::Const(c) = op
+                    && !is_trivial(c)
+                    && !mentions_generics(c)
+                {
+                    occurrences
+                        .entry(c.clone())
+                        .or_insert(Occurrences {
+                            count: 0,
+                            first_user: decl.def_id,
+                            global: None,
+                        })
+                        .count += 1;
+                }
+            });
+        }
+        occurrences.retain(|_, occ| occ.count > 1);
+        if occurrences.is_empty() {
+            return;
+        }
+
+        // Reserve the globals and their initializers.
+        let mut new_items = Vec::new();
+        for (i, occ) in occurrences.values_mut().enumerate() {
+            let global_id = ctx.translated.global_decls.reserve_slot();
+            let init_id = ctx.translated.fun_decls.reserve_slot();
+            let name = Name {
+                name: vec![
+                    PathElem::Ident(ctx.translated.crate_name.clone(), Disambiguator::ZERO),
+                    PathElem::Ident("<shared_const>".to_string(), Disambiguator::new(i)),
+                ],
+            };
+            for id in [AnyTransId::Global(global_id), AnyTransId::Fun(init_id)] {
+                ctx.translated.all_ids.insert(id);
+                ctx.translated.item_names.insert(id, name.clone());
+            }
+            occ.global = Some(global_id);
+            new_items.push((global_id, init_id, name));
+        }
+
+        // Replace the occurrences with the globals.
+        for decl in ctx.translated.fun_decls.iter_mut() {
+            let Some(body) = decl
+                .body
+                .as_mut()
+                .ok()
+                .and_then(|b| b.as_unstructured_mut())
+            else {
+                continue;
+            };
+            body.body.dyn_visit_in_body_mut(|op: &mut Operand| {
+                if let Operand::Const(c) = op
+                    && let Some(occ) = occurrences.get(&*c)
+                {
+                    let global_id = occ.global.unwrap();
+                    c.value = RawConstantExpr::Global(GlobalDeclRef {
+                        id: global_id,
+                        generics: GenericArgs::empty(GenericsSource::item(global_id)),
+                    });
+                }
+            });
+        }
+
+        // Add the globals, which are initialized with the constants.
+        for ((constant, occ), (global_id, init_id, name)) in occurrences.into_iter().zip(new_items)
+        {
+            let user = &ctx.translated.fun_decls[occ.first_user];
+            let span = user.item_meta.span;
+            let item_meta = ItemMeta {
+                name,
+                source_text: None,
+                attr_info: AttrInfo {
+                    attributes: vec![],
+                    inline: None,
+                    rename: None,
+                    public: false,
+                },
+                no_mangle: false,
+                export_name: None,
+                must_use: false,
+                cold: false,
+                non_exhaustive: false,
+                is_derived: false,
+                is_foreign: false,
+                foreign_abi: None,
+                from_macro: None,
+                ..user.item_meta.clone()
+            };
+            let ty = constant.ty.clone();
+
+            let mut locals = Locals::default();
+            let ret = locals.new_var(None, ty.clone());
+            let block = BlockData {
+                statements: vec![Statement::new(
+                    span,
+                    RawStatement::Assign(ret, Rvalue::Use(Operand::Const(constant))),
+                )],
+                terminator: Terminator::new(span, RawTerminator::Return),
+            };
+            let body = ExprBody {
+                span,
+                locals,
+                comments: vec![],
+                body: [block].into_iter().collect(),
+            };
+            ctx.translated.fun_decls.set_slot(
+                init_id,
+                FunDecl {
+                    def_id: init_id,
+                    item_meta: item_meta.clone(),
+                    signature: FunSig {
+                        is_unsafe: false,
+                        is_closure: false,
+                        closure_info: None,
+                        generics: GenericParams::empty(),
+                        inputs: vec![],
+                        output: ty.clone(),
+                    },
+                    kind: ItemKind::Regular,
+                    is_global_initializer: Some(global_id),
+                    body: Ok(Body::Unstructured(body)),
+                    calls: Default::default(),
+                },
+            );
+            ctx.translated.global_decls.set_slot(
+                global_id,
+                GlobalDecl {
+                    def_id: global_id,
+                    item_meta,
+                    generics: GenericParams::empty(),
+                    ty,
+                    kind: ItemKind::Regular,
+                    global_kind: GlobalKind::NamedConst,
+                    init: init_id,
+                },
+            );
+        }
+    }
+}
@@ -112,6 +112,7 @@ impl TransformPass for Transform {
                         kind,
                         is_global_initializer,
                         body,
+                        calls,
                     } = fun_decl.clone();
                     let item_meta = ItemMeta {
                         name: new_fun_name,
@@ -166,6 +167,7 @@ impl TransformPass for Transform {
                             kind,
                             is_global_initializer,
                             body,
+                            calls,
                         },
                     );
                 }
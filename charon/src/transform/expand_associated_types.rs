@@ -1112,7 +1112,7 @@ impl VisitAstMut for UpdateItemBody<'_> {
     }
 
     fn enter_trait_decl(&mut self, tdecl: &mut TraitDecl) {
-        for (clause_id, clause) in tdecl.parent_clauses.iter_mut_indexed() {
+        for (clause_id, clause) in tdecl.parent_clauses.iter_indexed_mut() {
             let self_path =
                 TraitRefKind::ParentClause(Box::new(TraitRefKind::SelfId), tdecl.def_id, clause_id);
             self.process_poly_trait_decl_ref(&mut clause.trait_, self_path);
@@ -1120,7 +1120,7 @@ impl VisitAstMut for UpdateItemBody<'_> {
     }
 
     fn enter_generic_params(&mut self, params: &mut GenericParams) {
-        for (clause_id, clause) in params.trait_clauses.iter_mut_indexed() {
+        for (clause_id, clause) in params.trait_clauses.iter_indexed_mut() {
             let self_path = TraitRefKind::Clause(DeBruijnVar::new_at_zero(clause_id));
             self.process_poly_trait_decl_ref(&mut clause.trait_, self_path);
         }
@@ -0,0 +1,101 @@
+//! # Micro-pass: replace the calls to `core::mem::size_of`, `core::mem::align_of` and
+//! `core::mem::size_of_val` with a constant when the type has a layout that doesn't depend on the
+//! target nor on the choices of the compiler. For the sizes, this is the case of the fixed-size
+//! scalar types, of `()`, and of the arrays of such types with a known length. The alignments are
+//! only fixed for the one-byte scalar types, `()` and the arrays of these. The calls on the other
+//! types, e.g. type variables, structs or `usize`, are left untouched.
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcPass;
+
+/// The size in bytes of the type, if it is known.
+fn size_of(ty: &Ty) -> Option<u64> {
+    match ty.kind() {
+        TyKind::Literal(LiteralTy::Integer(IntegerTy::Isize | IntegerTy::Usize)) => None,
+        TyKind::Literal(LiteralTy::Integer(int_ty)) => Some(int_ty.size() as u64),
+        TyKind::Literal(LiteralTy::Float(float_ty)) => Some(match float_ty {
+            FloatTy::F16 => 2,
+            FloatTy::F32 => 4,
+            FloatTy::F64 => 8,
+            FloatTy::F128 => 16,
+        }),
+        TyKind::Literal(LiteralTy::Bool) => Some(1),
+        TyKind::Literal(LiteralTy::Char) => Some(4),
+        TyKind::Adt(TypeId::Tuple, args) if args.types.is_empty() => Some(0),
+        TyKind::Adt(TypeId::Builtin(BuiltinTy::Array), args) => {
+            let Some(ConstGeneric::Value(Literal::Scalar(len))) =
+                args.const_generics.get(ConstGenericVarId::ZERO)
+            else {
+                return None;
+            };
+            let elem_size = size_of(&args.types[TypeVarId::ZERO])?;
+            elem_size.checked_mul(u64::try_from(len.to_bits()).ok()?)
+        }
+        _ => None,
+    }
+}
+
+/// The alignment in bytes of the type, if it is known. The alignment of the scalars of more than
+/// one byte varies across targets (e.g. `u64` is 4-aligned on 32-bit x86, and `u16` is 1-aligned
+/// on AVR), so we only handle the one-byte ones.
+fn align_of(ty: &Ty) -> Option<u64> {
+    match ty.kind() {
+        TyKind::Literal(_) => size_of(ty).filter(|size| *size == 1),
+        TyKind::Adt(TypeId::Tuple, args) if args.types.is_empty() => Some(1),
+        TyKind::Adt(TypeId::Builtin(BuiltinTy::Array), args) => {
+            align_of(&args.types[TypeVarId::ZERO])
+        }
+        _ => None,
+    }
+}
+
+pub struct Transform;
+impl UllbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        if ctx.options.no_cleanup {
+            return;
+        }
+
+        for block in b.body.iter_mut() {
+            for st in &mut block.statements {
+                let RawStatement::Call(Call {
+                    func:
+                        FnOperand::Regular(FnPtr {
+                            func: FunIdOrTraitMethodRef::Fun(FunId::Regular(fun_id)),
+                            generics,
+                        }),
+                    dest,
+                    ..
+                }) = &st.content
+                else {
+                    continue;
+                };
+                let Some(name) = ctx.translated.item_name(*fun_id) else {
+                    continue;
+                };
+                let Some(ty) = generics.types.get(TypeVarId::ZERO) else {
+                    continue;
+                };
+                let value = if name.equals_ref_name(&["core", "mem", "size_of"])
+                    || name.equals_ref_name(&["core", "mem", "size_of_val"])
+                {
+                    size_of(ty)
+                } else if name.equals_ref_name(&["core", "mem", "align_of"]) {
+                    align_of(ty)
+                } else {
+                    None
+                };
+                let Some(value) = value else {
+                    continue;
+                };
+                let constant = ConstantExpr {
+                    value: RawConstantExpr::Literal(Literal::Scalar(ScalarValue::Usize(value))),
+                    ty: TyKind::Literal(LiteralTy::Integer(IntegerTy::Usize)).into_ty(),
+                };
+                st.content =
+                    RawStatement::Assign(dest.clone(), Rvalue::Use(Operand::Const(constant)));
+            }
+        }
+    }
+}
@@ -192,7 +192,7 @@ impl VisitBodyMut for IndexVisitor<'_> {
 
     fn visit_fn_operand(&mut self, x: &mut FnOperand) -> ControlFlow<Infallible> {
         match x {
-            FnOperand::Regular(_) => self.visit_inner(x),
+            FnOperand::Regular(_) | FnOperand::Dynamic(_) => self.visit_inner(x),
             FnOperand::Move(_) => self.visit_inner_with_mutability(x, true),
         }
     }
@@ -212,7 +212,7 @@ impl VisitBodyMut for IndexVisitor<'_> {
             | Len(..) => self.visit_inner_with_mutability(x, false),
 
             Use(_) | NullaryOp(..) | UnaryOp(..) | BinaryOp(..) | Aggregate(..) | Global(..)
-            | GlobalRef(..) | Repeat(..) | ShallowInitBox(..) => self.visit_inner(x),
+            | GlobalRef(..) | BlackBox(..) | Repeat(..) | ShallowInitBox(..) => self.visit_inner(x),
         }
     }
 }
@@ -24,7 +24,8 @@ impl UllbcPass for Transform {
                 if body.body.elem_count() == 1
                     && let Some(block) = body.body.iter().next()
                     && block.statements.is_empty()
-                    && let RawTerminator::Abort(AbortKind::Panic(name)) = &block.terminator.content
+                    && let RawTerminator::Abort(AbortKind::Panic { name, .. }) =
+                        &block.terminator.content
                 {
                     if name.equals_ref_name(builtins::EXPLICIT_PANIC_NAME) {
                         // FIXME: also check that the name of the function is
@@ -36,7 +37,11 @@ impl UllbcPass for Transform {
         });
 
         let panic_name = Name::from_path(builtins::EXPLICIT_PANIC_NAME);
-        let panic_terminator = RawTerminator::Abort(AbortKind::Panic(panic_name));
+        let panic_terminator = RawTerminator::Abort(AbortKind::Panic {
+            name: panic_name,
+            kind: PanicKind::Explicit,
+            message: None,
+        });
 
         // Replace each call to one such function with a `Panic`.
         ctx.for_each_fun_decl(|_ctx, decl| {
@@ -0,0 +1,233 @@
+//! Replace checks of which variant an `Option` or a `Result` is with a direct comparison on its
+//! discriminant. For instance:
+//! ```text
+//! b := core::option::{Option<T>}::is_some(move x); // x: &Option<T>
+//! ```
+//! becomes:
+//! ```text
+//! discr := discriminant(*x);
+//! b := move discr == 1;
+//! ```
+//! We also recognize the equivalent matches, e.g. the one produced by `matches!(x, Some(_))`:
+//! ```text
+//! match x { Some => { b := true }, None => { b := false } }
+//! ```
+//! The matches with a guard, e.g. `matches!(x, Some(n) if n > 0)`, are left untouched: their
+//! arms don't simply assign a boolean constant, and the guard may only be evaluated once we know
+//! the variant.
+//!
+//! This must run after [super::remove_read_discriminant], which would otherwise turn the
+//! discriminant reads we introduce back into matches.
+use std::collections::{HashMap, HashSet};
+
+use crate::llbc_ast::*;
+use crate::name_matcher::NamePattern;
+use crate::transform::TransformCtx;
+
+use super::ctx::LlbcPass;
+
+/// The methods we recognize, along with the variant they check for.
+static VARIANT_CHECKS: &[(&str, &str)] = &[
+    ("core::option::{core::option::Option<_>}::is_some", "Some"),
+    ("core::option::{core::option::Option<_>}::is_none", "None"),
+    ("core::result::{core::result::Result<_, _>}::is_ok", "Ok"),
+    ("core::result::{core::result::Result<_, _>}::is_err", "Err"),
+];
+
+/// The enums whose matches we simplify.
+static ENUMS: &[&str] = &["core::option::Option", "core::result::Result"];
+
+struct Visitor<'a> {
+    ctx: &'a TransformCtx,
+    /// Map from the recognized methods to the variant they check for.
+    checks: &'a HashMap<FunDeclId, &'static str>,
+    enums: &'a HashSet<TypeDeclId>,
+    locals: &'a mut Locals,
+}
+
+impl Visitor<'_> {
+    /// If `ty` is one of the enums we handle, return its id and variants.
+    fn get_enum(&self, ty: &Ty) -> Option<(TypeDeclId, &Vector<VariantId, Variant>)> {
+        let TyKind::Adt(TypeId::Adt(adt_id), _) = ty.kind() else {
+            return None;
+        };
+        if !self.enums.contains(adt_id) {
+            return None;
+        }
+        let variants = self
+            .ctx
+            .translated
+            .type_decls
+            .get(*adt_id)?
+            .kind
+            .as_enum()?;
+        Some((*adt_id, variants))
+    }
+
+    /// Read the discriminant of `scrutinee` and compare it with the discriminant of `variant`.
+    /// Returns the discriminant read to insert, and the boolean rvalue.
+    fn compare_discriminant(
+        &mut self,
+        span: Span,
+        scrutinee: Place,
+        adt_id: TypeDeclId,
+        discr: ScalarValue,
+    ) -> (Statement, Rvalue) {
+        let discr_ty = TyKind::Literal(LiteralTy::Integer(discr.get_integer_ty())).into_ty();
+        let discr_var = self.locals.new_var(None, discr_ty);
+        let read = Statement::new(
+            span,
+            RawStatement::Assign(discr_var.clone(), Rvalue::Discriminant(scrutinee, adt_id)),
+        );
+        let rvalue = Rvalue::BinaryOp(
+            BinOp::Eq,
+            Operand::Move(discr_var),
+            Operand::Const(discr.to_constant()),
+        );
+        (read, rvalue)
+    }
+
+    /// Recognize `b := is_some(move x)` and friends.
+    fn lower_call(&mut self, st: &mut Statement) -> Option<Statement> {
+        let RawStatement::Call(Call {
+            func:
+                FnOperand::Regular(FnPtr {
+                    func: FunIdOrTraitMethodRef::Fun(FunId::Regular(fun_id)),
+                    ..
+                }),
+            args,
+            dest,
+        }) = &st.content
+        else {
+            return None;
+        };
+        let variant_name = *self.checks.get(fun_id)?;
+        let [Operand::Move(arg) | Operand::Copy(arg)] = args.as_slice() else {
+            return None;
+        };
+        let TyKind::Ref(_, enum_ty, _) = arg.ty().kind() else {
+            return None;
+        };
+        let (adt_id, variants) = self.get_enum(enum_ty)?;
+        let discr = variants
+            .iter()
+            .find(|v| v.name == variant_name)?
+            .discriminant;
+
+        let scrutinee = arg.clone().project(ProjectionElem::Deref, enum_ty.clone());
+        let dest = dest.clone();
+        let (read, rvalue) = self.compare_discriminant(st.span, scrutinee, adt_id, discr);
+        st.content = RawStatement::Assign(dest, rvalue);
+        Some(read)
+    }
+
+    /// Recognize `match x { Some => { b := true }, None => { b := false } }` and friends.
+    fn lower_match(&mut self, st: &mut Statement) -> Option<Statement> {
+        let RawStatement::Switch(Switch::Match(scrutinee, targets, otherwise)) = &st.content else {
+            return None;
+        };
+        let (adt_id, variants) = self.get_enum(scrutinee.ty())?;
+
+        /// If the block is a single assignment of a boolean constant, return the assigned place
+        /// and the boolean.
+        fn as_bool_assign(block: &Block) -> Option<(&Place, bool)> {
+            let [Statement {
+                content: RawStatement::Assign(dest, Rvalue::Use(Operand::Const(cst))),
+                ..
+            }] = block.statements.as_slice()
+            else {
+                return None;
+            };
+            let RawConstantExpr::Literal(Literal::Bool(b)) = cst.value else {
+                return None;
+            };
+            Some((dest, b))
+        }
+
+        let mut dest = None;
+        let mut true_variants = HashSet::new();
+        let mut covered_variants = HashSet::new();
+        for (variant_ids, block) in targets {
+            let (assigned, b) = as_bool_assign(block)?;
+            if *dest.get_or_insert(assigned) != assigned {
+                return None;
+            }
+            covered_variants.extend(variant_ids.iter().copied());
+            if b {
+                true_variants.extend(variant_ids.iter().copied());
+            }
+        }
+        if let Some(block) = otherwise {
+            let (assigned, b) = as_bool_assign(block)?;
+            if *dest.get_or_insert(assigned) != assigned {
+                return None;
+            }
+            if b {
+                true_variants.extend(
+                    variants
+                        .all_indices()
+                        .filter(|id| !covered_variants.contains(id)),
+                );
+            }
+        }
+        // We only handle the case where exactly one variant yields `true`.
+        let [true_variant] = true_variants.into_iter().collect::<Vec<_>>()[..] else {
+            return None;
+        };
+        let discr = variants[true_variant].discriminant;
+
+        let dest = dest?.clone();
+        let scrutinee = scrutinee.clone();
+        let (read, rvalue) = self.compare_discriminant(st.span, scrutinee, adt_id, discr);
+        st.content = RawStatement::Assign(dest, rvalue);
+        Some(read)
+    }
+}
+
+/// Find the items that match one of the patterns, along with the index of the pattern they match.
+fn find_items<'a>(
+    krate: &'a TranslatedCrate,
+    patterns: impl Iterator<Item = &'static str>,
+) -> impl Iterator<Item = (AnyTransId, usize)> + 'a {
+    let patterns: Vec<NamePattern> = patterns.map(|s| NamePattern::parse(s).unwrap()).collect();
+    krate.item_names.iter().filter_map(move |(id, name)| {
+        let i = patterns.iter().position(|p| p.matches(krate, name))?;
+        Some((*id, i))
+    })
+}
+
+pub struct Transform;
+impl LlbcPass for Transform {
+    fn transform_ctx(&self, ctx: &mut TransformCtx) {
+        if !ctx.options.lower_discriminant_checks {
+            return;
+        }
+
+        let checks: HashMap<FunDeclId, &'static str> =
+            find_items(&ctx.translated, VARIANT_CHECKS.iter().map(|(pat, _)| *pat))
+                .filter_map(|(id, i)| Some((*id.as_fun()?, VARIANT_CHECKS[i].1)))
+                .collect();
+        let enums: HashSet<TypeDeclId> = find_items(&ctx.translated, ENUMS.iter().copied())
+            .filter_map(|(id, _)| id.as_type().copied())
+            .collect();
+
+        ctx.for_each_fun_decl(|ctx, decl| {
+            if let Ok(body) = &mut decl.body {
+                let body = body.as_structured_mut().unwrap();
+                let mut visitor = Visitor {
+                    ctx,
+                    checks: &checks,
+                    enums: &enums,
+                    locals: &mut body.locals,
+                };
+                body.body.transform(|st| {
+                    visitor
+                        .lower_call(st)
+                        .or_else(|| visitor.lower_match(st))
+                        .into_iter()
+                        .collect()
+                });
+            }
+        });
+    }
+}
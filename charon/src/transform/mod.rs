@@ -1,10 +1,13 @@
 pub mod check_generics;
+pub mod compute_callees;
 pub mod ctx;
+pub mod dedup_constants;
 pub mod duplicate_defaulted_methods;
 pub mod duplicate_return;
 pub mod expand_associated_types;
 pub mod filter_invisible_trait_impls;
 pub mod filter_unreachable_blocks;
+pub mod fold_size_of;
 pub mod graphs;
 pub mod hide_marker_traits;
 pub mod index_intermediate_assigns;
@@ -12,30 +15,48 @@ pub mod index_to_function_calls;
 pub mod inline_local_panic_functions;
 pub mod insert_assign_return_unit;
 pub mod lift_associated_item_clauses;
+pub mod lower_discriminant_checks;
 pub mod merge_goto_chains;
+pub mod monomorphize;
+pub mod normalize_switch_targets;
 pub mod ops_to_function_calls;
 pub mod prettify_cfg;
 pub mod reconstruct_asserts;
+pub mod reconstruct_black_box;
 pub mod reconstruct_boxes;
+pub mod reconstruct_for_loops;
+pub mod reconstruct_let_chains;
+pub mod reconstruct_let_else;
+pub mod reconstruct_rc;
 pub mod recover_body_comments;
 pub mod remove_arithmetic_overflow_checks;
+pub mod remove_copy_drops;
+pub mod remove_dead_set_discriminants;
 pub mod remove_drop_never;
 pub mod remove_dynamic_checks;
 pub mod remove_nops;
+pub mod remove_phantom_data_locals;
 pub mod remove_read_discriminant;
+pub mod remove_trivial_asserts;
+pub mod remove_uninhabited_arms;
 pub mod remove_unit_locals;
 pub mod remove_unused_locals;
 pub mod remove_unused_methods;
 pub mod reorder_decls;
 pub mod simplify_constants;
+pub mod sink_common_statements;
 pub mod skip_trait_refs_when_known;
+pub mod substitute_single_use;
 pub mod ullbc_to_llbc;
 pub mod unbind_item_vars;
 pub mod update_block_indices;
 pub mod update_closure_signatures;
 
-pub use ctx::TransformCtx;
+pub use ctx::{CrateStats, TransformCtx};
 use ctx::{LlbcPass, TransformPass, UllbcPass};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
 use Pass::*;
 
 /// Item and type cleanup passes.
@@ -54,12 +75,20 @@ pub static INITIAL_CLEANUP_PASSES: &[Pass] = &[
     NonBody(&filter_invisible_trait_impls::Transform),
     // Add missing methods to trait impls by duplicating the default method.
     NonBody(&duplicate_defaulted_methods::Transform),
+    // # Micro-pass: replace the `Deref` and `Clone` method calls on `Rc`/`Arc` with built-in
+    // operations. Must happen before `skip_trait_refs_when_known`.
+    UnstructuredBody(&reconstruct_rc::Transform),
+    // # Micro-pass: replace the calls to `core::hint::black_box` with a dedicated rvalue.
+    UnstructuredBody(&reconstruct_black_box::Transform),
     // # Micro-pass: whenever we call a trait method on a known type, refer to the method `FunDecl`
     // directly instead of going via a `TraitRef`. This is done before `reorder_decls` to remove
     // some sources of mutual recursion.
     UnstructuredBody(&skip_trait_refs_when_known::Transform),
     // Change trait associated types to be type parameters instead. See the module for details.
     NonBody(&expand_associated_types::Transform),
+    // # Micro-pass: hoist the constants which appear several times into shared globals, if the
+    // option is set. Must happen before `simplify_constants`.
+    NonBody(&dedup_constants::Transform),
 ];
 
 /// Body cleanup passes on the ullbc.
@@ -84,6 +113,9 @@ pub static ULLBC_PASSES: &[Pass] = &[
     // # Micro-pass: desugar the constants to other values/operands as much
     // as possible.
     UnstructuredBody(&simplify_constants::Transform),
+    // # Micro-pass: replace the calls to `size_of`/`align_of` with constants when the layout of
+    // the type is known.
+    UnstructuredBody(&fold_size_of::Transform),
     // # Micro-pass: the first local variable of closures is the
     // closure itself. This is not consistent with the closure signature,
     // which ignores this first variable. This micro-pass updates this.
@@ -100,6 +132,13 @@ pub static ULLBC_PASSES: &[Pass] = &[
     UnstructuredBody(&update_block_indices::Transform),
     // # Micro-pass: reconstruct the asserts
     UnstructuredBody(&reconstruct_asserts::Transform),
+    // # Micro-pass: remove the assertions on a constant which always hold, and turn the
+    // assumptions which never hold into undefined behavior. Must happen after
+    // `reconstruct_asserts` and before `filter_unreachable_blocks`.
+    UnstructuredBody(&remove_trivial_asserts::Transform),
+    // # Micro-pass: make the switch arms of uninhabited enum variants jump to an
+    // `undefined_behavior` block. Must happen before `filter_unreachable_blocks`.
+    UnstructuredBody(&remove_uninhabited_arms::Transform),
     // # Micro-pass: duplicate the return blocks
     UnstructuredBody(&duplicate_return::Transform),
     // # Micro-pass: filter the "dangling" blocks. Those might have been introduced by,
@@ -124,9 +163,21 @@ pub static ULLBC_PASSES: &[Pass] = &[
     UnstructuredBody(&insert_assign_return_unit::Transform),
     // # Micro-pass: remove locals of type `()` which show up a lot.
     UnstructuredBody(&remove_unit_locals::Transform),
+    // # Micro-pass: remove the locals of type `PhantomData` which are never used.
+    UnstructuredBody(&remove_phantom_data_locals::Transform),
+    // # Micro-pass: remove the `SetDiscriminant`s which are overwritten before being read.
+    UnstructuredBody(&remove_dead_set_discriminants::Transform),
     // # Micro-pass: remove the drops of locals whose type is `Never` (`!`). This
     // is in preparation of the next transformation.
     UnstructuredBody(&remove_drop_never::Transform),
+    // # Micro-pass: remove the drops of values whose type is `Copy`, if the option is set.
+    UnstructuredBody(&remove_copy_drops::Transform),
+    // # Micro-pass: move the statements which end all the branches of a switch to the block where
+    // the branches join, if the option is set.
+    UnstructuredBody(&sink_common_statements::Transform),
+    // # Micro-pass: sort the targets of the integer switches by value and remove the duplicate
+    // values, to get a deterministic output.
+    UnstructuredBody(&normalize_switch_targets::Transform),
 ];
 
 /// Body cleanup passes after control flow reconstruction.
@@ -135,12 +186,31 @@ pub static LLBC_PASSES: &[Pass] = &[
     NonBody(&ullbc_to_llbc::Transform),
     // # Micro-pass: Remove the discriminant reads (merge them with the switches)
     StructuredBody(&remove_read_discriminant::Transform),
+    // # Micro-pass: replace `is_some`/`is_ok`-like checks with a comparison on the discriminant.
+    // Must happen after `remove_read_discriminant`.
+    StructuredBody(&lower_discriminant_checks::Transform),
+    // # Micro-pass: reconstruct the `if let` chains, if the option is set. Must happen after
+    // `remove_read_discriminant`, and before `reconstruct_let_else` which would otherwise take the
+    // chains whose `else` block diverges.
+    StructuredBody(&reconstruct_let_chains::Transform),
+    // # Micro-pass: reconstruct the `let`-else statements. Must happen after
+    // `remove_read_discriminant`.
+    StructuredBody(&reconstruct_let_else::Transform),
+    // # Micro-pass: reconstruct the `for` loops, if the option is set. Must happen after
+    // `remove_read_discriminant`.
+    StructuredBody(&reconstruct_for_loops::Transform),
+    // # Micro-pass: substitute the temporaries which are assigned once and used once, if the
+    // option is set.
+    StructuredBody(&substitute_single_use::Transform),
     // Cleanup the cfg.
     StructuredBody(&prettify_cfg::Transform),
 ];
 
 /// Cleanup passes useful for both llbc and ullbc.
 pub static SHARED_FINALIZING_PASSES: &[Pass] = &[
+    // # Micro-pass: if the option is set, instantiate the generic functions and types at the
+    // concrete arguments they are used with. This must happen before we compute the callees.
+    NonBody(&monomorphize::Transform),
     // # Micro-pass: remove the locals which are never used.
     NonBody(&remove_unused_locals::Transform),
     // # Micro-pass: remove the useless `StatementKind::Nop`s.
@@ -149,6 +219,9 @@ pub static SHARED_FINALIZING_PASSES: &[Pass] = &[
     // statements. This must be last after all the statement-affecting passes to avoid losing
     // comments.
     NonBody(&recover_body_comments::Transform),
+    // # Micro-pass: record the functions called by each function body. This must happen after
+    // all the passes that modify calls.
+    NonBody(&compute_callees::Transform),
     // # Reorder the graph of dependencies and compute the strictly connex components to:
     // - compute the order in which to extract the definitions
     // - find the recursive definitions
@@ -190,6 +263,53 @@ impl Pass {
     }
 }
 
+/// Run the passes in order, stopping after the first pass that emits an error. If
+/// `--time-passes` is set, the time spent in each pass is recorded in the returned timings.
+pub fn run_passes(ctx: &mut TransformCtx, passes: impl IntoIterator<Item = Pass>) -> PassTimings {
+    let mut timings = PassTimings::default();
+    for pass in passes {
+        trace!("# Starting pass {}", pass.name());
+        if ctx.options.time_passes {
+            let start = Instant::now();
+            pass.run(ctx);
+            timings.record(pass.name(), start.elapsed());
+        } else {
+            pass.run(ctx);
+        }
+        if ctx.errors.borrow().has_errors() {
+            break;
+        }
+    }
+    timings
+}
+
+/// The wall-clock time spent in each pass, indexed by pass name. A pass that runs several times
+/// gets the sum of its running times.
+#[derive(Debug, Default)]
+pub struct PassTimings {
+    pub timings: HashMap<String, Duration>,
+}
+
+impl PassTimings {
+    fn record(&mut self, name: &str, duration: Duration) {
+        *self.timings.entry(name.to_owned()).or_default() += duration;
+    }
+}
+
+impl fmt::Display for PassTimings {
+    /// Print the timings, slowest pass first.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut timings: Vec<_> = self.timings.iter().collect();
+        timings.sort_by(|(_, d0), (_, d1)| d1.cmp(d0));
+        let total: Duration = timings.iter().map(|(_, d)| **d).sum();
+        writeln!(f, "# Time spent in each pass (total: {total:.2?}):")?;
+        for (name, duration) in timings {
+            writeln!(f, "{duration:>12.2?}  {name}")?;
+        }
+        Ok(())
+    }
+}
+
 pub struct PrintCtxPass {
     pub message: String,
     /// Whether we're printing to stdout or only logging.
@@ -213,3 +333,43 @@ impl TransformPass for PrintCtxPass {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::TranslatedCrate;
+    use crate::errors::ErrorCtx;
+    use crate::options::{CliOpts, ErrorFormat, TranslateOptions};
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+
+    #[test]
+    fn time_passes() {
+        let cli_opts = CliOpts {
+            time_passes: true,
+            ..CliOpts::default()
+        };
+        let mut errors = ErrorCtx::new(false, false, ErrorFormat::Human);
+        let options = TranslateOptions::new(&mut errors, &cli_opts);
+        let mut ctx = TransformCtx {
+            options,
+            translated: TranslatedCrate::default(),
+            errors: RefCell::new(errors),
+        };
+        let passes: Vec<Pass> = [
+            INITIAL_CLEANUP_PASSES,
+            ULLBC_PASSES,
+            LLBC_PASSES,
+            SHARED_FINALIZING_PASSES,
+            FINAL_CLEANUP_PASSES,
+        ]
+        .concat();
+        let timings = run_passes(&mut ctx, passes.iter().copied());
+
+        let pass_names: HashSet<&str> = passes.iter().map(|pass| pass.name()).collect();
+        assert_eq!(timings.timings.len(), pass_names.len());
+        for name in pass_names {
+            assert!(timings.timings.contains_key(name), "no timing for {name}");
+        }
+    }
+}
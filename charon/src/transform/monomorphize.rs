@@ -0,0 +1,316 @@
+//! # Micro-pass: if the `--monomorphize` option is set, instantiate the generic functions and
+//! types at the concrete arguments they are used with. Starting from the items which don't have
+//! type, const generic or trait parameters, we replace each use of a generic item at concrete
+//! arguments with a use of a copy of that item specialized to these arguments, and recursively
+//! process the copies. The instances are deduplicated: the same item used at the same arguments
+//! refers to a single copy.
+//!
+//! The instance of `foo` at `u32` is named `foo::<u32>`, using [PathElem::Monomorphized].
+//! Polymorphic recursion (e.g. `fn f<T>() { f::<Option<T>>() }`) would create infinitely many
+//! instances: we stop creating instances past [MAX_INSTANCE_DEPTH] nested instantiations, report
+//! an error and leave the remaining uses generic.
+//!
+//! Limitations:
+//! - the regions are erased in the instances;
+//! - the trait method calls (`FunIdOrTraitMethodRef::Trait`) are not resolved, hence not
+//!   instantiated;
+//! - trait declarations, trait impls and their methods are left untouched (rewriting only some of
+//!   them would make the generics of an impl and of its methods inconsistent);
+//! - opaque functions and types and generic globals are not instantiated; the non-generic globals
+//!   are processed like the non-generic functions;
+//! - the generic items are kept, since they may still be used in the places above.
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+
+use derive_generic_visitor::*;
+
+use crate::ast::*;
+use crate::errors::{register_error, ErrorCtx};
+use crate::formatter::IntoFormatter;
+use crate::pretty::FmtWithCtx;
+use crate::transform::TransformCtx;
+
+use super::ctx::TransformPass;
+
+#[derive(Default)]
+struct Instances {
+    funs: HashMap<(FunDeclId, GenericArgs), FunDeclId>,
+    types: HashMap<(TypeDeclId, GenericArgs), TypeDeclId>,
+    /// The items whose content remains to be visited, with their instantiation depth.
+    queue: VecDeque<(AnyTransId, usize)>,
+}
+
+/// The maximal number of nested instantiations: an instance created while visiting an instance
+/// of depth `n` has depth `n + 1`, and the items we start from have depth 0.
+const MAX_INSTANCE_DEPTH: usize = 20;
+
+#[derive(Visitor)]
+struct MonomorphizeVisitor<'a> {
+    translated: &'a mut TranslatedCrate,
+    errors: &'a RefCell<ErrorCtx>,
+    instances: &'a mut Instances,
+    /// The depth of the item being visited.
+    depth: usize,
+    /// The span of the item being visited.
+    span: Span,
+}
+
+/// Normalize the arguments to use them as an instantiation key. Returns `None` if they don't
+/// instantiate anything but regions or if they are not fully concrete.
+fn instance_args(args: &GenericArgs) -> Option<GenericArgs> {
+    if args.types.is_empty() && args.const_generics.is_empty() && args.trait_refs.is_empty() {
+        return None;
+    }
+    let mut args = args.clone();
+    args.dyn_visit_mut(|r: &mut Region| *r = Region::Erased);
+    let mut is_concrete = true;
+    args.dyn_visit(|ty: &Ty| {
+        if let TyKind::TypeVar(_) | TyKind::TraitType(..) = ty.kind() {
+            is_concrete = false
+        }
+    });
+    args.dyn_visit(|cg: &ConstGeneric| {
+        if let ConstGeneric::Var(_) = cg {
+            is_concrete = false
+        }
+    });
+    args.dyn_visit(|kind: &TraitRefKind| {
+        if !matches!(
+            kind,
+            TraitRefKind::TraitImpl(..)
+                | TraitRefKind::BuiltinOrAuto { .. }
+                | TraitRefKind::Dyn(..)
+        ) {
+            is_concrete = false
+        }
+    });
+    is_concrete.then_some(args)
+}
+
+/// The name of an instance is the name of the generic item followed by the arguments.
+fn instance_name(name: &Name, args: &GenericArgs) -> Name {
+    let mut name = name.clone();
+    name.name
+        .push(PathElem::Monomorphized(Box::new(args.clone())));
+    name
+}
+
+impl MonomorphizeVisitor<'_> {
+    /// Check that we may create an instance from the current item, and report an error otherwise.
+    fn check_depth(&self, name: &Name) -> bool {
+        if self.depth < MAX_INSTANCE_DEPTH {
+            return true;
+        }
+        let name = name.fmt_with_ctx(&self.translated.into_fmt());
+        register_error!(
+            self.errors.borrow_mut(),
+            crate(&*self.translated),
+            self.span,
+            "Reached the maximal depth of {MAX_INSTANCE_DEPTH} nested instantiations while \
+            instantiating `{name}`; this use is left generic"
+        );
+        false
+    }
+
+    /// Get the instance of this function at these arguments, creating it if needed.
+    fn fun_instance(&mut self, id: FunDeclId, args: &GenericArgs) -> Option<FunDeclId> {
+        let args = instance_args(args)?;
+        let key = (id, args);
+        if let Some(new_id) = self.instances.funs.get(&key) {
+            return Some(*new_id);
+        }
+        let decl = self.translated.fun_decls.get(id)?.clone();
+        if decl.body.is_err() || !matches!(decl.kind, ItemKind::Regular) {
+            return None;
+        }
+        if !self.check_depth(&decl.item_meta.name) {
+            return None;
+        }
+        let (_, args) = &key;
+        let new_id = self.translated.fun_decls.reserve_slot();
+        let name = instance_name(&decl.item_meta.name, args);
+        let mut new_decl = decl.substitute(args);
+        new_decl.def_id = new_id;
+        new_decl.signature.generics = GenericParams::empty();
+        new_decl.item_meta.name = name;
+        self.translated.all_ids.insert(new_id.into());
+        self.translated
+            .item_names
+            .insert(new_id.into(), new_decl.item_meta.name.clone());
+        self.translated.fun_decls.set_slot(new_id, new_decl);
+        self.instances.funs.insert(key, new_id);
+        self.instances
+            .queue
+            .push_back((new_id.into(), self.depth + 1));
+        Some(new_id)
+    }
+
+    /// Get the instance of this type at these arguments, creating it if needed.
+    fn type_instance(&mut self, id: TypeDeclId, args: &GenericArgs) -> Option<TypeDeclId> {
+        let args = instance_args(args)?;
+        let key = (id, args);
+        if let Some(new_id) = self.instances.types.get(&key) {
+            return Some(*new_id);
+        }
+        let decl = self.translated.type_decls.get(id)?.clone();
+        if let TypeDeclKind::Opaque = decl.kind {
+            return None;
+        }
+        if !self.check_depth(&decl.item_meta.name) {
+            return None;
+        }
+        let (_, args) = &key;
+        let new_id = self.translated.type_decls.reserve_slot();
+        let name = instance_name(&decl.item_meta.name, args);
+        let mut new_decl = decl.substitute(args);
+        new_decl.def_id = new_id;
+        new_decl.generics = GenericParams::empty();
+        new_decl.item_meta.name = name;
+        self.translated.all_ids.insert(new_id.into());
+        self.translated
+            .item_names
+            .insert(new_id.into(), new_decl.item_meta.name.clone());
+        self.translated.type_decls.set_slot(new_id, new_decl);
+        self.instances.types.insert(key, new_id);
+        self.instances
+            .queue
+            .push_back((new_id.into(), self.depth + 1));
+        Some(new_id)
+    }
+
+    /// Visit the item and make it refer to the instances. The item is taken out of the crate
+    /// while we visit it so that we can add new instances to the crate at the same time.
+    fn process_item(&mut self, id: AnyTransId, depth: usize) {
+        self.depth = depth;
+        match id {
+            AnyTransId::Fun(id) => {
+                if let Some(mut decl) = self.translated.fun_decls.remove(id) {
+                    self.span = decl.item_meta.span;
+                    decl.drive_mut(self);
+                    update_adt_ids(&mut decl);
+                    self.translated.fun_decls.set_slot(id, decl);
+                }
+            }
+            AnyTransId::Type(id) => {
+                if let Some(mut decl) = self.translated.type_decls.remove(id) {
+                    self.span = decl.item_meta.span;
+                    decl.drive_mut(self);
+                    self.translated.type_decls.set_slot(id, decl);
+                }
+            }
+            AnyTransId::Global(id) => {
+                if let Some(mut decl) = self.translated.global_decls.remove(id) {
+                    self.span = decl.item_meta.span;
+                    decl.drive_mut(self);
+                    self.translated.global_decls.set_slot(id, decl);
+                }
+            }
+            AnyTransId::TraitDecl(_) | AnyTransId::TraitImpl(_) => {}
+        }
+    }
+}
+
+impl VisitAstMut for MonomorphizeVisitor<'_> {
+    fn visit_item_meta(&mut self, _: &mut ItemMeta) -> ControlFlow<Self::Break> {
+        // Don't look inside: the name of an instance mentions the generic arguments it was
+        // created with, and must stay equal to the one recorded in `item_names`.
+        Continue(())
+    }
+
+    fn exit_ty(&mut self, ty: &mut Ty) {
+        if let TyKind::Adt(TypeId::Adt(id), args) = ty.kind()
+            && let Some(new_id) = self.type_instance(*id, args)
+        {
+            *ty = TyKind::Adt(
+                TypeId::Adt(new_id),
+                GenericArgs::empty(GenericsSource::item(new_id)),
+            )
+            .into_ty();
+        }
+    }
+
+    fn exit_aggregate_kind(&mut self, kind: &mut AggregateKind) {
+        if let AggregateKind::Adt(TypeId::Adt(id), _, _, args) = kind
+            && let Some(new_id) = self.type_instance(*id, args)
+        {
+            *id = new_id;
+            *args = GenericArgs::empty(GenericsSource::item(new_id));
+        }
+    }
+
+    fn exit_fn_ptr(&mut self, fn_ptr: &mut FnPtr) {
+        if let FunIdOrTraitMethodRef::Fun(FunId::Regular(id)) = &mut fn_ptr.func
+            && let Some(new_id) = self.fun_instance(*id, &fn_ptr.generics)
+        {
+            *id = new_id;
+            fn_ptr.generics = GenericArgs::empty(GenericsSource::item(new_id));
+        }
+    }
+}
+
+/// The field projections and discriminant reads mention the id of the ADT they operate on: make
+/// them agree with the (possibly updated) type of the place.
+fn update_adt_ids(decl: &mut FunDecl) {
+    decl.dyn_visit_mut(|place: &mut Place| {
+        if let PlaceKind::Projection(base, ProjectionElem::Field(FieldProjKind::Adt(id, _), _)) =
+            &mut place.kind
+            && let TyKind::Adt(TypeId::Adt(new_id), _) = base.ty().kind()
+        {
+            *id = *new_id;
+        }
+    });
+    decl.dyn_visit_mut(|rvalue: &mut Rvalue| {
+        if let Rvalue::Discriminant(place, id) = rvalue
+            && let TyKind::Adt(TypeId::Adt(new_id), _) = place.ty().kind()
+        {
+            *id = *new_id;
+        }
+    });
+}
+
+pub struct Transform;
+impl TransformPass for Transform {
+    fn transform_ctx(&self, ctx: &mut TransformCtx) {
+        if !ctx.options.monomorphize {
+            return;
+        }
+        let is_root = |generics: &GenericParams| {
+            generics.types.is_empty()
+                && generics.const_generics.is_empty()
+                && generics.trait_clauses.is_empty()
+        };
+        let translated = &ctx.translated;
+        let roots = (translated.fun_decls.iter())
+            .filter(|decl| {
+                matches!(decl.kind, ItemKind::Regular) && is_root(&decl.signature.generics)
+            })
+            .map(|decl| AnyTransId::from(decl.def_id))
+            .chain(
+                (translated.type_decls.iter())
+                    .filter(|decl| is_root(&decl.generics))
+                    .map(|decl| AnyTransId::from(decl.def_id)),
+            )
+            .chain(
+                (translated.global_decls.iter())
+                    .filter(|decl| is_root(&decl.generics))
+                    .map(|decl| AnyTransId::from(decl.def_id)),
+            )
+            .map(|id| (id, 0))
+            .collect();
+
+        let mut instances = Instances {
+            queue: roots,
+            ..Default::default()
+        };
+        let mut visitor = MonomorphizeVisitor {
+            translated: &mut ctx.translated,
+            errors: &ctx.errors,
+            instances: &mut instances,
+            depth: 0,
+            span: Span::dummy(),
+        };
+        while let Some((id, depth)) = visitor.instances.queue.pop_front() {
+            visitor.process_item(id, depth);
+        }
+    }
+}
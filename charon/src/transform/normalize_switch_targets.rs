@@ -0,0 +1,25 @@
+//! # Micro-pass: make the targets of the integer switches deterministic. We sort the `(value,
+//! block)` pairs by value, and remove duplicate values: only the first occurrence of a value can
+//! ever be taken, so the other ones are dead. Several values targeting the same block are left
+//! as-is; they will be grouped together when reconstructing the control-flow.
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcPass;
+
+pub struct Transform;
+impl UllbcPass for Transform {
+    fn transform_body(&self, _ctx: &mut TransformCtx, b: &mut ExprBody) {
+        for block in b.body.iter_mut() {
+            if let RawTerminator::Switch {
+                targets: SwitchTargets::SwitchInt(_, targets, _),
+                ..
+            } = &mut block.terminator.content
+            {
+                // The sort is stable, so the first occurrence of each value stays first.
+                targets.sort_by_key(|(value, _)| *value);
+                targets.dedup_by_key(|(value, _)| *value);
+            }
+        }
+    }
+}
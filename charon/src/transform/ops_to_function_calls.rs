@@ -18,13 +18,11 @@ fn transform_st(s: &mut Statement) {
                 RefKind::Shared => BuiltinFunId::ArrayToSliceShared,
             };
             let func = FunIdOrTraitMethodRef::mk_builtin(id);
-            let generics = GenericArgs::new(
-                vec![Region::Erased].into(),
-                vec![ty.clone()].into(),
-                vec![cg.clone()].into(),
-                vec![].into(),
-                GenericsSource::Builtin,
-            );
+            let generics = GenericArgsBuilder::new(GenericsSource::Builtin)
+                .region(Region::Erased)
+                .ty(ty.clone())
+                .const_generic(cg.clone())
+                .build();
             let func = FnOperand::Regular(FnPtr { func, generics });
             s.content = RawStatement::Call(Call {
                 func,
@@ -38,13 +36,11 @@ fn transform_st(s: &mut Statement) {
             // the statement. In practice, this shouldn't have much impact.
             let id = BuiltinFunId::ArrayRepeat;
             let func = FunIdOrTraitMethodRef::mk_builtin(id);
-            let generics = GenericArgs::new(
-                vec![Region::Erased].into(),
-                vec![ty.clone()].into(),
-                vec![cg.clone()].into(),
-                vec![].into(),
-                GenericsSource::Builtin,
-            );
+            let generics = GenericArgsBuilder::new(GenericsSource::Builtin)
+                .region(Region::Erased)
+                .ty(ty.clone())
+                .const_generic(cg.clone())
+                .build();
             let func = FnOperand::Regular(FnPtr { func, generics });
             s.content = RawStatement::Call(Call {
                 func,
@@ -1,43 +1,116 @@
 //! In the MIR AST, it seems `assert` are introduced to check preconditions
 //! (for the binops for example). The `assert!` introduced by the user
 //! introduce `if ... then { panic!(...) } else { ...}`.
-//! This pass introduces `assert` instead in order to make the code shorter.
+//! This pass introduces `assert` instead in order to make the code shorter. The user asserts
+//! are marked with [AssertKind::UserAssert] and keep their panic message when we could recover
+//! it.
+//! The switches on a constant condition are replaced with a jump to the branch they take.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use crate::transform::TransformCtx;
 use crate::ullbc_ast::*;
 
 use super::ctx::UllbcPass;
 
+/// `core::fmt::{core::fmt::Arguments}::new_const`; the comparison skips the `impl` path element.
+static ARGUMENTS_NEW_CONST: &[&str] = &["core", "fmt", "new_const"];
+
+/// If the block does nothing but panic, return the kind of assertion that a conditional jump to
+/// it corresponds to. We allow the statements that build a constant panic message, like:
+/// ```text
+/// @6 := [const ("bad"); 1 : usize]
+/// @5 := &@6
+/// @4 := &*@5
+/// @3 := core::fmt::Arguments::new_const<'_, 1 : usize>(move @4)
+/// drop @4
+/// panic(core::panicking::panic_fmt)
+/// ```
+fn panic_block_kind(ctx: &TransformCtx, block: &BlockData) -> Option<AssertKind> {
+    let RawTerminator::Abort(abort_kind) = &block.terminator.content else {
+        return None;
+    };
+    // The locals written to in this block: we may drop them without losing side-effects.
+    let assigned: HashSet<VarId> = block
+        .statements
+        .iter()
+        .filter_map(|st| match &st.content {
+            RawStatement::Assign(dest, _) | RawStatement::Call(Call { dest, .. }) => {
+                dest.as_local()
+            }
+            _ => None,
+        })
+        .collect();
+    let builds_message = |st: &Statement| match &st.content {
+        RawStatement::StorageDead(_) | RawStatement::Nop => true,
+        RawStatement::Assign(dest, _) => dest.is_local(),
+        RawStatement::Drop(place) => place.as_local().is_some_and(|v| assigned.contains(&v)),
+        RawStatement::Call(Call {
+            func:
+                FnOperand::Regular(FnPtr {
+                    func: FunIdOrTraitMethodRef::Fun(FunId::Regular(fun_id)),
+                    ..
+                }),
+            dest,
+            ..
+        }) => {
+            dest.is_local()
+                && ctx
+                    .translated
+                    .item_names
+                    .get(&AnyTransId::Fun(*fun_id))
+                    .is_some_and(|name| name.equals_ref_name(ARGUMENTS_NEW_CONST))
+        }
+        _ => false,
+    };
+    match abort_kind {
+        AbortKind::Panic { message, .. }
+            if block.statements.is_empty()
+                || (message.is_some() && block.statements.iter().all(builds_message)) =>
+        {
+            Some(AssertKind::UserAssert {
+                message: message.clone(),
+            })
+        }
+        AbortKind::UndefinedBehavior if block.statements.is_empty() => Some(AssertKind::Assume),
+        _ => None,
+    }
+}
+
 pub struct Transform;
 impl UllbcPass for Transform {
-    fn transform_body(&self, _ctx: &mut TransformCtx, b: &mut ExprBody) {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
         // Start by computing the set of blocks which are actually panics.
         // Remark: doing this in two steps because reading the blocks at random
         // while doing in-place updates is not natural to do in Rust.
-        let panics: HashSet<BlockId> = b
+        let panics: HashMap<BlockId, AssertKind> = b
             .body
             .iter_indexed()
-            .filter_map(|(bid, block)| {
-                if block.statements.is_empty() && block.terminator.content.is_abort() {
-                    Some(bid)
-                } else {
-                    None
-                }
-            })
+            .filter_map(|(bid, block)| Some((bid, panic_block_kind(ctx, block)?)))
             .collect();
 
         for block in b.body.iter_mut() {
             match &block.terminator.content {
                 RawTerminator::Switch {
-                    discr: _,
+                    discr,
                     targets: SwitchTargets::If(bid0, bid1),
                 } => {
-                    let (nbid, expected) = if panics.contains(bid0) {
-                        (*bid1, false)
-                    } else if panics.contains(bid1) {
-                        (*bid0, true)
+                    // A switch on a constant always takes the same branch: we jump to it directly,
+                    // which keeps the original panic block if the branch panics.
+                    if !ctx.options.no_cleanup
+                        && let Operand::Const(ConstantExpr {
+                            value: RawConstantExpr::Literal(Literal::Bool(value)),
+                            ..
+                        }) = discr
+                    {
+                        let target = if *value { *bid0 } else { *bid1 };
+                        block.terminator.content = RawTerminator::Goto { target };
+                        continue;
+                    }
+                    let (nbid, expected, kind) = if let Some(kind) = panics.get(bid0) {
+                        (*bid1, false, kind.clone())
+                    } else if let Some(kind) = panics.get(bid1) {
+                        (*bid0, true, kind.clone())
                     } else {
                         continue;
                     };
@@ -52,6 +125,7 @@ impl UllbcPass for Transform {
                         RawStatement::Assert(Assert {
                             cond: discr.clone(),
                             expected,
+                            kind,
                         }),
                     ));
                 }
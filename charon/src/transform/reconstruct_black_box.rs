@@ -0,0 +1,52 @@
+//! # Micro-pass: replace the calls to `core::hint::black_box` with [Rvalue::BlackBox], so that
+//! consumers can see the optimization barrier without having to recognize the function. We
+//! replace:
+//! ```text
+//! @2 := core::hint::black_box<u32>(move @3)
+//! ```
+//! with:
+//! ```text
+//! @2 := black_box(move @3)
+//! ```
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcPass;
+
+static BLACK_BOX: &[&str] = &["core", "hint", "black_box"];
+
+fn transform_call(ctx: &TransformCtx, call: &Call) -> Option<RawStatement> {
+    let FnOperand::Regular(FnPtr {
+        func: FunIdOrTraitMethodRef::Fun(FunId::Regular(fun_id)),
+        ..
+    }) = &call.func
+    else {
+        return None;
+    };
+    let [arg] = call.args.as_slice() else {
+        return None;
+    };
+    let name = ctx.translated.item_names.get(&AnyTransId::Fun(*fun_id))?;
+    if !name.equals_ref_name(BLACK_BOX) {
+        return None;
+    }
+    let rvalue = Rvalue::BlackBox(arg.clone());
+    Some(RawStatement::Assign(call.dest.clone(), rvalue))
+}
+
+pub struct Transform;
+impl UllbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        if ctx.options.no_cleanup {
+            return;
+        }
+
+        b.visit_statements(|st| {
+            if let RawStatement::Call(call) = &st.content
+                && let Some(new_content) = transform_call(ctx, call)
+            {
+                st.content = new_content;
+            }
+        });
+    }
+}
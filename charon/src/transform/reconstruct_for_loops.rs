@@ -0,0 +1,289 @@
+//! # Micro-pass: reconstruct the `for` loops. `for x in iter { ... }` desugars to:
+//! ```text
+//! it := IntoIterator::into_iter(move iter)
+//! loop {
+//!     @1 := &mut it
+//!     @2 := &two-phase-mut *(@1)
+//!     @3 := Iterator::next(move @2)
+//!     match @3 {
+//!         None => {
+//!             break 0
+//!         },
+//!         Some => {
+//!             x := copy ((@3 as Some).0)
+//!             ... // the body
+//!         },
+//!     }
+//! }
+//! ```
+//! We turn the loop into a [RawStatement::ForLoop]:
+//! ```text
+//! it := IntoIterator::into_iter(move iter)
+//! for x in it {
+//!     ... // the body
+//! }
+//! ```
+//! This must happen after [super::remove_read_discriminant], which introduces the matches.
+use std::collections::{HashMap, HashSet};
+
+use crate::llbc_ast::*;
+use crate::transform::TransformCtx;
+
+use super::ctx::LlbcPass;
+
+static ITERATOR_TRAIT: &[&str] = &["core", "iter", "traits", "iterator", "Iterator"];
+static OPTION_TYPE: &[&str] = &["core", "option", "Option"];
+
+/// Count the occurrences of the locals, apart from the drops.
+fn count_uses(x: &impl BodyVisitable) -> HashMap<VarId, usize> {
+    let mut uses: HashMap<VarId, usize> = HashMap::new();
+    x.dyn_visit_in_body(|var: &VarId| {
+        *uses.entry(*var).or_default() += 1;
+    });
+    x.dyn_visit_in_body(|st: &Statement| {
+        if let RawStatement::Drop(place) = &st.content
+            && let Some(var) = place.as_local()
+        {
+            *uses.get_mut(&var).unwrap() -= 1;
+        }
+    });
+    uses
+}
+
+struct Reconstructor<'a> {
+    ctx: &'a TransformCtx,
+    locals: &'a mut Locals,
+    /// The occurrences of the locals in the whole body, apart from the drops.
+    uses: &'a HashMap<VarId, usize>,
+    /// The locals of the loop headers we removed.
+    removed: HashSet<VarId>,
+}
+
+impl Reconstructor<'_> {
+    fn is_iterator(&self, trait_id: TraitDeclId) -> bool {
+        self.ctx
+            .translated
+            .item_name(trait_id)
+            .is_some_and(|name| name.equals_ref_name(ITERATOR_TRAIT))
+    }
+
+    /// Whether the function is `Iterator::next` or an implementation of it.
+    fn is_iterator_next(&self, func: &FnOperand) -> bool {
+        let FnOperand::Regular(fn_ptr) = func else {
+            return false;
+        };
+        match &fn_ptr.func {
+            FunIdOrTraitMethodRef::Trait(trait_ref, method_name, _) => {
+                method_name.0 == "next"
+                    && self.is_iterator(trait_ref.trait_decl_ref.skip_binder.trait_id)
+            }
+            FunIdOrTraitMethodRef::Fun(FunId::Regular(fun_id)) => {
+                match self
+                    .ctx
+                    .translated
+                    .fun_decls
+                    .get(*fun_id)
+                    .map(|decl| &decl.kind)
+                {
+                    Some(ItemKind::TraitImpl {
+                        trait_ref,
+                        item_name,
+                        ..
+                    }) => item_name.0 == "next" && self.is_iterator(trait_ref.trait_id),
+                    _ => false,
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// If the statement is the loop of a desugared `for` loop, return the corresponding
+    /// [RawStatement::ForLoop].
+    fn reconstruct(&mut self, st: &Statement) -> Option<RawStatement> {
+        let RawStatement::Loop(block) = &st.content else {
+            return None;
+        };
+        let statements = block
+            .statements
+            .iter()
+            .filter(|st| !st.content.is_nop())
+            .collect::<Vec<_>>();
+        let (last, header) = statements.split_last()?;
+        let RawStatement::Switch(Switch::Match(scrutinee, targets, otherwise)) = &last.content
+        else {
+            return None;
+        };
+        let option_var = scrutinee.as_local()?;
+
+        // The header borrows the iterator, calls `next` on it, and drops the temporaries.
+        let mut header_locals = vec![option_var];
+        let mut borrows: HashMap<VarId, &Place> = HashMap::new();
+        let mut next_arg = None;
+        for st in header {
+            match &st.content {
+                RawStatement::Assign(
+                    dest,
+                    Rvalue::Ref(place, BorrowKind::Mut | BorrowKind::TwoPhaseMut),
+                ) => {
+                    let var = dest.as_local()?;
+                    borrows.insert(var, place);
+                    header_locals.push(var);
+                }
+                RawStatement::Call(Call { func, args, dest })
+                    if dest.as_local() == Some(option_var) && self.is_iterator_next(func) =>
+                {
+                    let [Operand::Move(arg)] = args.as_slice() else {
+                        return None;
+                    };
+                    next_arg = Some(arg.as_local()?);
+                }
+                RawStatement::Drop(place) | RawStatement::FakeRead(place)
+                    if place.as_local().is_some() => {}
+                _ => return None,
+            }
+        }
+        // Follow the chain of reborrows to find the iterator.
+        let mut var = next_arg?;
+        let iter = loop {
+            let place = *borrows.get(&var)?;
+            match place.as_projection() {
+                Some((sub, ProjectionElem::Deref))
+                    if sub.as_local().is_some_and(|v| borrows.contains_key(&v)) =>
+                {
+                    var = sub.as_local().unwrap()
+                }
+                _ => break place.clone(),
+            }
+        };
+        // The header locals must not be used outside of the loop.
+        let loop_uses = count_uses(st);
+        if header_locals
+            .iter()
+            .any(|var| loop_uses.get(var) != self.uses.get(var))
+        {
+            return None;
+        }
+
+        // Find the `None` and `Some` branches.
+        let TyKind::Adt(TypeId::Adt(option_id), option_args) = scrutinee.ty().kind() else {
+            return None;
+        };
+        if !self
+            .ctx
+            .translated
+            .item_name(*option_id)
+            .is_some_and(|name| name.equals_ref_name(OPTION_TYPE))
+        {
+            return None;
+        }
+        let variants = self
+            .ctx
+            .translated
+            .type_decls
+            .get(*option_id)?
+            .kind
+            .as_enum()?;
+        let variant_id = |name: &str| variants.iter_indexed().find(|(_, v)| v.name == name);
+        let (none, _) = variant_id("None")?;
+        let (some, _) = variant_id("Some")?;
+        let branch = |variant: VariantId| {
+            targets
+                .iter()
+                .find(|(ids, _)| ids.contains(&variant))
+                .map(|(_, block)| block)
+                .or(otherwise.as_ref())
+        };
+        let none_block = branch(none)?;
+        let some_block = branch(some)?;
+        if std::ptr::eq(none_block, some_block) {
+            return None;
+        }
+        // The `None` branch exits the loop.
+        let none_statements = none_block
+            .statements
+            .iter()
+            .filter(|st| !st.content.is_nop() && !st.content.is_drop())
+            .collect::<Vec<_>>();
+        let [Statement {
+            content: RawStatement::Break(0),
+            ..
+        }] = none_statements[..]
+        else {
+            return None;
+        };
+
+        // Replace the reads of the `Some` field with reads of a new local.
+        let mut body = some_block.clone();
+        let item_ty = option_args.types.get(TypeVarId::ZERO)?.clone();
+        let field = scrutinee
+            .clone()
+            .project(
+                ProjectionElem::Field(FieldProjKind::Adt(*option_id, Some(some)), FieldId::ZERO),
+                item_ty.clone(),
+            )
+            .kind;
+        let mut item = self.locals.new_var(None, item_ty);
+        body.dyn_visit_in_body_mut(|place: &mut Place| {
+            if place.kind == field {
+                *place = item.clone();
+            }
+        });
+        body.visit_statements(|st: &mut Statement| {
+            if let RawStatement::Drop(place) = &st.content
+                && place
+                    .as_local()
+                    .is_some_and(|var| header_locals.contains(&var))
+            {
+                st.content = RawStatement::Nop;
+            }
+        });
+        let mut uses_header = false;
+        body.dyn_visit_in_body(|var: &VarId| uses_header |= header_locals.contains(var));
+        if uses_header {
+            return None;
+        }
+        // If the body starts by moving the item into a variable, use that variable directly.
+        if let Some(first) = body.statements.first()
+            && let RawStatement::Assign(dest, Rvalue::Use(Operand::Move(p) | Operand::Copy(p))) =
+                &first.content
+            && *p == item
+            && dest.is_local()
+        {
+            item = dest.clone();
+            body.statements.remove(0);
+        }
+
+        self.removed.extend(header_locals);
+        Some(RawStatement::ForLoop(iter, item, body))
+    }
+}
+
+pub struct Transform;
+impl LlbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        if !ctx.options.reconstruct_for_loops {
+            return;
+        }
+        let uses = count_uses(&b.body);
+        let mut reconstructor = Reconstructor {
+            ctx,
+            locals: &mut b.locals,
+            uses: &uses,
+            removed: HashSet::new(),
+        };
+        b.body.visit_statements(|st: &mut Statement| {
+            if let Some(new_content) = reconstructor.reconstruct(st) {
+                st.content = new_content;
+            }
+        });
+        // Remove the drops of the header locals that appear after the loops.
+        let removed = reconstructor.removed;
+        b.body.visit_statements(|st: &mut Statement| {
+            if let RawStatement::Drop(place) = &st.content
+                && place.as_local().is_some_and(|var| removed.contains(&var))
+            {
+                st.content = RawStatement::Nop;
+            }
+        });
+    }
+}
@@ -0,0 +1,152 @@
+//! # Micro-pass: reconstruct the `if let` chains. An `if let Some(x) = a && let Ok(y) = b`
+//! becomes nested matches, where each match binds the fields of the matched variant then does the
+//! next match:
+//! ```text
+//! match a {
+//!     Some => {
+//!         x := copy ((a as Some).0)
+//!         match b {
+//!             Ok => {
+//!                 y := copy ((b as Ok).0)
+//!                 ... // the `then` block
+//!             },
+//!             _ => {
+//!                 ... // the `else` block
+//!             },
+//!         }
+//!     },
+//!     _ => {
+//!         ... // the `else` block
+//!     },
+//! }
+//! ```
+//! We turn it into a [RawStatement::IfLet] which gives the conditions in order:
+//! ```text
+//! if let Some = a {
+//!     x := copy ((a as Some).0)
+//! }
+//! else {
+//!     ... // the `else` block
+//! }
+//! && let Ok = b {
+//!     y := copy ((b as Ok).0)
+//! }
+//! else {
+//!     ... // the `else` block
+//! }
+//! then {
+//!     ... // the `then` block
+//! }
+//! ```
+//! We keep one `else` block per condition, as they may differ, e.g. to drop the variables bound
+//! by the previous conditions.
+//!
+//! This must happen after [super::remove_read_discriminant], which introduces the matches.
+use std::mem;
+
+use crate::llbc_ast::*;
+use crate::transform::TransformCtx;
+
+use super::ctx::LlbcPass;
+use super::reconstruct_let_else::is_binding;
+
+/// If the statement is a match with two branches, one of which is for a single variant and starts
+/// by binding some fields of that variant, return the index and the block of that branch.
+fn if_let_branch(st: &RawStatement) -> Option<(usize, &Block)> {
+    let RawStatement::Switch(Switch::Match(scrutinee, targets, otherwise)) = st else {
+        return None;
+    };
+    if targets.len() + otherwise.is_some() as usize != 2 {
+        return None;
+    }
+    targets
+        .iter()
+        .enumerate()
+        .find(|(_, (variants, block))| {
+            variants.len() == 1
+                && block
+                    .statements
+                    .first()
+                    .is_some_and(|st| is_binding(st, scrutinee, variants[0]))
+        })
+        .map(|(i, (_, block))| (i, block))
+}
+
+/// The number of `if let`s nested in each other's branch, starting from this statement.
+fn chain_length(st: &RawStatement) -> usize {
+    match if_let_branch(st) {
+        Some((_, block)) => {
+            1 + block
+                .statements
+                .last()
+                .map_or(0, |st| chain_length(&st.content))
+        }
+        None => 0,
+    }
+}
+
+/// Split a match for which [if_let_branch] returned `i` into its scrutinee, the variant and block
+/// of branch `i`, and the other block.
+fn split_if_let(st: RawStatement, i: usize) -> (Place, VariantId, Block, Block) {
+    let RawStatement::Switch(Switch::Match(scrutinee, mut targets, otherwise)) = st else {
+        unreachable!()
+    };
+    let (variants, then_block) = targets.remove(i);
+    let else_block = match targets.pop() {
+        Some((_, block)) => block,
+        None => otherwise.unwrap(),
+    };
+    (scrutinee, variants[0], then_block, else_block)
+}
+
+fn reconstruct(st: &mut Statement) {
+    let length = chain_length(&st.content);
+    // A single `if let` is better represented by the match.
+    if length < 2 {
+        return;
+    }
+    let mut conditions = Vec::new();
+    let mut current = mem::replace(&mut st.content, RawStatement::Nop);
+    loop {
+        let (i, _) = if_let_branch(&current).unwrap();
+        let (scrutinee, variant, mut bindings, else_block) = split_if_let(current, i);
+        if conditions.len() + 1 < length {
+            // The next `if let` is the last statement of the branch.
+            current = bindings.statements.pop().unwrap().content;
+            conditions.push(LetCondition {
+                scrutinee,
+                variant,
+                bindings,
+                else_block,
+            });
+        } else {
+            let num_bindings = bindings
+                .statements
+                .iter()
+                .take_while(|st| is_binding(st, &scrutinee, variant))
+                .count();
+            let then_block = Block {
+                span: bindings.span,
+                statements: bindings.statements.split_off(num_bindings),
+            };
+            conditions.push(LetCondition {
+                scrutinee,
+                variant,
+                bindings,
+                else_block,
+            });
+            st.content = RawStatement::IfLet(conditions, then_block);
+            return;
+        }
+    }
+}
+
+pub struct Transform;
+impl LlbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        if !ctx.options.reconstruct_let_chains {
+            return;
+        }
+        b.body.visit_statements(reconstruct);
+    }
+}
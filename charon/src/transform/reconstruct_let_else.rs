@@ -0,0 +1,126 @@
+//! # Micro-pass: reconstruct `let`-else statements. A `let Some(x) = opt else { return };`
+//! becomes a match where one branch diverges, with the rest of the code nested in the other
+//! branch:
+//! ```text
+//! match opt {
+//!     Some => {
+//!         x := copy ((opt as Some).0)
+//!         ... // rest of the code
+//!     },
+//!     _ => {
+//!         return
+//!     },
+//! }
+//! ```
+//! We turn it into a [RawStatement::LetElse] followed by the rest of the code:
+//! ```text
+//! let Some = opt {
+//!     x := copy ((opt as Some).0)
+//! }
+//! else {
+//!     return
+//! }
+//! ... // rest of the code
+//! ```
+//! This must happen after [super::remove_read_discriminant], which introduces the matches.
+use std::mem;
+
+use crate::llbc_ast::*;
+use crate::transform::TransformCtx;
+
+use super::ctx::LlbcPass;
+use super::ullbc_to_llbc::is_terminal;
+
+/// Whether `place` is a projection of a field of `scrutinee` seen as `variant`.
+fn is_in_variant(place: &Place, scrutinee: &Place, variant: VariantId) -> bool {
+    let mut place = place;
+    while let Some((sub, elem)) = place.as_projection() {
+        if sub == scrutinee {
+            return matches!(
+                elem,
+                ProjectionElem::Field(FieldProjKind::Adt(_, Some(v)), _) if *v == variant
+            );
+        }
+        place = sub;
+    }
+    false
+}
+
+/// Whether the statement binds a variable of the pattern, i.e. reads or borrows a field of the
+/// matched variant.
+pub(super) fn is_binding(st: &Statement, scrutinee: &Place, variant: VariantId) -> bool {
+    match &st.content {
+        RawStatement::Assign(
+            _,
+            Rvalue::Use(Operand::Copy(place) | Operand::Move(place)) | Rvalue::Ref(place, _),
+        ) => is_in_variant(place, scrutinee, variant),
+        _ => false,
+    }
+}
+
+/// If the statement is a match with two branches, one for a single variant which starts by binding
+/// some fields of that variant and one which diverges, return the `let`-else statement followed
+/// by the rest of the non-diverging branch.
+fn reconstruct(st: &mut Statement) -> Option<Vec<Statement>> {
+    let RawStatement::Switch(Switch::Match(scrutinee, targets, otherwise)) = &st.content else {
+        return None;
+    };
+    if targets.len() + otherwise.is_some() as usize != 2 {
+        return None;
+    }
+    let other_block = |i: usize| match otherwise {
+        Some(otherwise) => otherwise,
+        None => &targets[1 - i].1,
+    };
+    let i = (0..targets.len()).find(|&i| {
+        let (variants, block) = &targets[i];
+        variants.len() == 1
+            && block
+                .statements
+                .first()
+                .is_some_and(|st| is_binding(st, scrutinee, variants[0]))
+            && is_terminal(other_block(i))
+    })?;
+
+    let RawStatement::Switch(Switch::Match(scrutinee, mut targets, otherwise)) =
+        mem::replace(&mut st.content, RawStatement::Nop)
+    else {
+        unreachable!()
+    };
+    let (variants, mut bindings) = targets.remove(i);
+    let variant = variants[0];
+    let else_block = match targets.pop() {
+        Some((_, block)) => block,
+        None => otherwise.unwrap(),
+    };
+    let num_bindings = bindings
+        .statements
+        .iter()
+        .take_while(|st| is_binding(st, &scrutinee, variant))
+        .count();
+    let rest = bindings.statements.split_off(num_bindings);
+
+    let let_else = Statement {
+        span: st.span,
+        content: RawStatement::LetElse(scrutinee, variant, bindings, else_block),
+        comments_before: mem::take(&mut st.comments_before),
+    };
+    Some([let_else].into_iter().chain(rest).collect())
+}
+
+pub struct Transform;
+impl LlbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        if !ctx.options.reconstruct_let_else {
+            return;
+        }
+        b.body.visit_blocks_bwd(|block: &mut Block| {
+            for mut st in mem::take(&mut block.statements) {
+                match reconstruct(&mut st) {
+                    Some(statements) => block.statements.extend(statements),
+                    None => block.statements.push(st),
+                }
+            }
+        });
+    }
+}
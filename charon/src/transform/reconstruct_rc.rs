@@ -0,0 +1,88 @@
+//! # Micro-pass: treat the `Deref` and `Clone` methods of `Rc` and `Arc` as built-in operations.
+//!
+//! Dereferencing an `Rc` goes through its `Deref` impl. We replace:
+//! ```text
+//! @2 := <Rc<T> as Deref>::deref(move @3) // @3: &Rc<T>
+//! ```
+//! with a deref projection, like we would have for a `Box`:
+//! ```text
+//! @2 := &*(*@3)
+//! ```
+//! Similarly, we replace calls to `<Rc<T> as Clone>::clone` with calls to the built-in
+//! [BuiltinFunId::RcClone] (and likewise for `Arc`).
+//!
+//! This must happen before [super::skip_trait_refs_when_known], which would otherwise replace the
+//! trait method calls with calls to the impl methods.
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcPass;
+
+static DEREF_TRAIT: &[&str] = &["core", "ops", "deref", "Deref"];
+static CLONE_TRAIT: &[&str] = &["core", "clone", "Clone"];
+
+fn transform_call(ctx: &TransformCtx, call: &Call) -> Option<RawStatement> {
+    let FnOperand::Regular(FnPtr {
+        func: FunIdOrTraitMethodRef::Trait(trait_ref, method_name, _),
+        ..
+    }) = &call.func
+    else {
+        return None;
+    };
+    let trait_decl_ref = &trait_ref.trait_decl_ref.skip_binder;
+    let self_ty = trait_decl_ref.generics.types.get(TypeVarId::ZERO)?;
+    let TyKind::Adt(TypeId::Builtin(builtin_ty @ (BuiltinTy::Rc | BuiltinTy::Arc)), generics) =
+        self_ty.kind()
+    else {
+        return None;
+    };
+    let [Operand::Move(arg) | Operand::Copy(arg)] = call.args.as_slice() else {
+        return None;
+    };
+    let trait_name = ctx
+        .translated
+        .item_names
+        .get(&AnyTransId::TraitDecl(trait_decl_ref.trait_id))?;
+
+    if trait_name.equals_ref_name(DEREF_TRAIT) && method_name.0 == "deref" {
+        // `*(*arg)`: the first deref goes through the reference, the second through the `Rc`.
+        let pointee = generics.types[TypeVarId::ZERO].clone();
+        let place = arg
+            .clone()
+            .project(ProjectionElem::Deref, self_ty.clone())
+            .project(ProjectionElem::Deref, pointee);
+        let rvalue = Rvalue::Ref(place, BorrowKind::Shared);
+        Some(RawStatement::Assign(call.dest.clone(), rvalue))
+    } else if trait_name.equals_ref_name(CLONE_TRAIT) && method_name.0 == "clone" {
+        let fun_id = match builtin_ty {
+            BuiltinTy::Rc => BuiltinFunId::RcClone,
+            _ => BuiltinFunId::ArcClone,
+        };
+        let generics = GenericArgsBuilder::new(GenericsSource::Builtin)
+            .ty(generics.types[TypeVarId::ZERO].clone())
+            .build();
+        Some(RawStatement::Call(Call {
+            func: FnOperand::Regular(FnPtr {
+                func: FunIdOrTraitMethodRef::Fun(FunId::Builtin(fun_id)),
+                generics,
+            }),
+            args: call.args.clone(),
+            dest: call.dest.clone(),
+        }))
+    } else {
+        None
+    }
+}
+
+pub struct Transform;
+impl UllbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        b.visit_statements(|st| {
+            if let RawStatement::Call(call) = &st.content
+                && let Some(new_content) = transform_call(ctx, call)
+            {
+                st.content = new_content;
+            }
+        });
+    }
+}
@@ -1,5 +1,6 @@
 //! # Micro-pass: remove the overflow checks for arithmetic operations we couldn't remove in
-//! [`remove_dynamic_checks`]. See comments there for more details.
+//! [`remove_dynamic_checks`]. See comments there for more details. Does nothing if
+//! `--keep-overflow-checks` is set.
 use crate::transform::TransformCtx;
 use crate::ullbc_ast::*;
 
@@ -38,6 +39,7 @@ impl Transform {
                 RawStatement::Assert(Assert {
                     cond: Operand::Move(assert_cond),
                     expected: false,
+                    ..
                 }),
             ..
         }, Statement {
@@ -75,7 +77,10 @@ impl Transform {
 }
 
 impl UllbcPass for Transform {
-    fn transform_body(&self, _ctx: &mut TransformCtx, b: &mut ExprBody) {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        if ctx.options.keep_overflow_checks {
+            return;
+        }
         b.transform_sequences(|_, seq| {
             Transform::update_statements(seq);
             Vec::new()
@@ -0,0 +1,35 @@
+//! # Micro-pass: remove the drops of values whose type is `Copy`. Dropping such a value is a
+//! no-op, yet the bodies contain `StorageDead`s (which become drops in LLBC) for all the locals,
+//! e.g. integers. We replace these with `Nop`s.
+//!
+//! See [crate::ast::Ty::is_copy] for the types we consider `Copy`.
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcPass;
+
+pub struct Transform;
+impl UllbcPass for Transform {
+    fn transform_ctx(&self, ctx: &mut TransformCtx) {
+        if !ctx.options.remove_copy_drops {
+            return;
+        }
+        let copy_adts = ctx.translated.copy_adts();
+        ctx.for_each_fun_decl(|_ctx, decl| {
+            if let Ok(body) = &mut decl.body {
+                let body = body.as_unstructured_mut().unwrap();
+                let locals = body.locals.clone();
+                body.visit_statements(|st: &mut Statement| {
+                    let is_copy_drop = match &st.content {
+                        RawStatement::StorageDead(var_id) => locals[*var_id].ty.is_copy(&copy_adts),
+                        RawStatement::Drop(place) => place.ty().is_copy(&copy_adts),
+                        _ => false,
+                    };
+                    if is_copy_drop {
+                        st.content = RawStatement::Nop;
+                    }
+                });
+            }
+        });
+    }
+}
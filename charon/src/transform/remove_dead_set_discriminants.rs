@@ -0,0 +1,65 @@
+//! # Micro-pass: remove the `SetDiscriminant` statements which are overwritten by another
+//! `SetDiscriminant` on the same place later in the block, without the place being read in
+//! between. E.g.:
+//! ```text
+//! SetDiscriminant(x, 0);
+//! y := const 1;
+//! SetDiscriminant(x, 1);
+//! ```
+//! becomes:
+//! ```text
+//! y := const 1;
+//! SetDiscriminant(x, 1);
+//! ```
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcPass;
+
+/// Whether the statement may read the value of `var_id`. We are conservative: we only accept the
+/// statements which can't panic nor call a function, and which don't mention the local nor
+/// dereference anything (a pointer might alias the local).
+fn may_read(st: &Statement, var_id: VarId) -> bool {
+    use RawStatement::*;
+    match &st.content {
+        Nop => false,
+        Assign(..) | FakeRead(..) | StorageDead(..) | SetDiscriminant(..) | Deinit(..) => {
+            let mut may_read = false;
+            st.content
+                .dyn_visit_in_body(|id: &VarId| may_read |= *id == var_id);
+            st.content
+                .dyn_visit_in_body(|elem: &ProjectionElem| may_read |= elem.is_deref());
+            may_read
+        }
+        _ => true,
+    }
+}
+
+pub struct Transform;
+impl UllbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, body: &mut ExprBody) {
+        if ctx.options.no_cleanup {
+            return;
+        }
+
+        for block in body.body.iter_mut() {
+            for i in 0..block.statements.len() {
+                let RawStatement::SetDiscriminant(place, _) = &block.statements[i].content else {
+                    continue;
+                };
+                let var_id = place.var_id();
+                let sets_place = |st: &Statement| match &st.content {
+                    RawStatement::SetDiscriminant(other_place, _) => other_place == place,
+                    _ => false,
+                };
+                let overwritten = block.statements[i + 1..]
+                    .iter()
+                    .take_while(|st| sets_place(st) || !may_read(st, var_id))
+                    .any(sets_place);
+                if overwritten {
+                    block.statements[i].content = RawStatement::Nop;
+                }
+            }
+        }
+    }
+}
@@ -3,6 +3,8 @@
 //! must lead to a panic in Rust (which is why those checks are always present, even when
 //! compiling for release). In our case, we take this into account in the semantics of our
 //! array/slice manipulation and arithmetic functions, on the verification side.
+//!
+//! The overflow checks are kept if `--keep-overflow-checks` is set.
 
 use crate::ast::*;
 use crate::transform::TransformCtx;
@@ -12,7 +14,8 @@ use super::ctx::UllbcPass;
 
 /// Rustc inserts dybnamic checks during MIR lowering. They all end in an `Assert` statement (and
 /// this is the only use of this statement).
-fn remove_dynamic_checks(_ctx: &mut TransformCtx, statements: &mut [Statement]) {
+fn remove_dynamic_checks(ctx: &mut TransformCtx, statements: &mut [Statement]) {
+    let keep_overflow_checks = ctx.options.keep_overflow_checks;
     // We return the statements we want to keep, which must be a prefix of `block.statements`.
     let statements_to_keep = match statements {
         // Bounds checks for arrays/slices. They look like:
@@ -34,6 +37,7 @@ fn remove_dynamic_checks(_ctx: &mut TransformCtx, statements: &mut [Statement])
                 RawStatement::Assert(Assert {
                     cond: Operand::Move(cond),
                     expected,
+                    ..
                 }),
             ..
         }, rest @ ..]
@@ -54,6 +58,7 @@ fn remove_dynamic_checks(_ctx: &mut TransformCtx, statements: &mut [Statement])
                 RawStatement::Assert(Assert {
                     cond: Operand::Move(cond),
                     expected,
+                    ..
                 }),
             ..
         }, rest @ ..]
@@ -85,13 +90,15 @@ fn remove_dynamic_checks(_ctx: &mut TransformCtx, statements: &mut [Statement])
                 RawStatement::Assert(Assert {
                     cond: Operand::Move(cond),
                     expected,
+                    ..
                 }),
             ..
         }, rest @ ..]
             if and_op1 == is_neg_1
                 && and_op2 == is_min
                 && cond == has_overflow
-                && *expected == false =>
+                && *expected == false
+                && !keep_overflow_checks =>
         {
             rest
         }
@@ -115,10 +122,14 @@ fn remove_dynamic_checks(_ctx: &mut TransformCtx, statements: &mut [Statement])
                 RawStatement::Assert(Assert {
                     cond: Operand::Move(cond),
                     expected,
+                    ..
                 }),
             ..
         }, rest @ ..]
-            if lt_op2 == x && cond == has_overflow && *expected == true =>
+            if lt_op2 == x
+                && cond == has_overflow
+                && *expected == true
+                && !keep_overflow_checks =>
         {
             rest
         }
@@ -137,10 +148,11 @@ fn remove_dynamic_checks(_ctx: &mut TransformCtx, statements: &mut [Statement])
                 RawStatement::Assert(Assert {
                     cond: Operand::Move(cond),
                     expected,
+                    ..
                 }),
             ..
         }, rest @ ..]
-            if cond == has_overflow && *expected == true =>
+            if cond == has_overflow && *expected == true && !keep_overflow_checks =>
         {
             rest
         }
@@ -162,6 +174,7 @@ fn remove_dynamic_checks(_ctx: &mut TransformCtx, statements: &mut [Statement])
                 RawStatement::Assert(Assert {
                     cond: Operand::Move(cond),
                     expected,
+                    ..
                 }),
             ..
         }, ..]
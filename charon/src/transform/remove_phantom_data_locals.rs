@@ -0,0 +1,111 @@
+//! # Micro-pass: remove the locals of type `PhantomData<T>` which are only assigned, fake-read or
+//! dropped. Those appear e.g. when building a value with a `PhantomData` field, which goes
+//! through a temporary. The return value and the arguments are kept, so that the signature
+//! doesn't change.
+use derive_generic_visitor::*;
+use std::collections::HashSet;
+
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcPass;
+
+static PHANTOM_DATA_TYPE: &[&str] = &["core", "marker", "PhantomData"];
+
+fn is_phantom_data(ctx: &TransformCtx, ty: &Ty) -> bool {
+    match ty.kind() {
+        TyKind::Adt(TypeId::Adt(id), _) => ctx
+            .translated
+            .item_name(*id)
+            .is_some_and(|name| name.equals_ref_name(PHANTOM_DATA_TYPE)),
+        _ => false,
+    }
+}
+
+/// Whether evaluating the rvalue has no effect. We don't accept places with projections, as
+/// those might read through a pointer.
+fn is_pure(rvalue: &Rvalue) -> bool {
+    let is_pure_operand = |op: &Operand| match op {
+        Operand::Const(_) => true,
+        Operand::Copy(place) | Operand::Move(place) => place.is_local(),
+    };
+    match rvalue {
+        Rvalue::Use(op) => is_pure_operand(op),
+        Rvalue::Aggregate(_, ops) => ops.iter().all(is_pure_operand),
+        _ => false,
+    }
+}
+
+/// If the statement doesn't use the value of a local, apart from assigning, fake-reading or
+/// dropping it, return that local.
+fn non_use_of_local(st: &Statement) -> Option<VarId> {
+    match &st.content {
+        RawStatement::Assign(place, rvalue) if is_pure(rvalue) => place.as_local(),
+        RawStatement::StorageDead(var_id) => Some(*var_id),
+        RawStatement::FakeRead(place) | RawStatement::Drop(place) | RawStatement::Deinit(place) => {
+            place.as_local()
+        }
+        _ => None,
+    }
+}
+
+/// Find the locals among `candidates` which are never used.
+#[derive(Visitor)]
+struct UnusedLocalsVisitor {
+    unused: HashSet<VarId>,
+}
+impl VisitBody for UnusedLocalsVisitor {
+    fn enter_place(&mut self, x: &Place) {
+        if let Some(var_id) = x.as_local() {
+            self.unused.remove(&var_id);
+        }
+    }
+    fn visit_ullbc_statement(&mut self, x: &ullbc_ast::Statement) -> ControlFlow<Self::Break> {
+        match &x.content {
+            // Don't count the assignment as a use, but count the uses in the assigned value.
+            RawStatement::Assign(_, rvalue) if non_use_of_local(x).is_some() => {
+                self.visit(rvalue)?
+            }
+            _ if non_use_of_local(x).is_some() => {}
+            _ => self.visit_inner(x)?,
+        }
+        Continue(())
+    }
+}
+
+pub struct Transform;
+impl UllbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, body: &mut ExprBody) {
+        if ctx.options.no_cleanup {
+            return;
+        }
+
+        let candidates: HashSet<VarId> = body
+            .locals
+            .non_argument_locals()
+            .filter(|(_, var)| is_phantom_data(ctx, &var.ty))
+            .map(|(id, _)| id)
+            .collect();
+        // Removing a move from a phantom local into another one can make the first one unused,
+        // hence the loop.
+        loop {
+            let unused = (UnusedLocalsVisitor {
+                unused: candidates.clone(),
+            })
+            .visit_by_val_infallible(&*body)
+            .unused;
+            let mut changed = false;
+            body.visit_statements(|st| {
+                if let Some(var_id) = non_use_of_local(st)
+                    && unused.contains(&var_id)
+                {
+                    st.content = RawStatement::Nop;
+                    changed = true;
+                }
+            });
+            if !changed {
+                break;
+            }
+        }
+    }
+}
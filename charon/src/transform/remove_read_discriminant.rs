@@ -158,6 +158,9 @@ impl Transform {
 
 impl LlbcPass for Transform {
     fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        if ctx.options.keep_discriminant_reads {
+            return;
+        }
         b.body.visit_blocks_bwd(|block: &mut Block| {
             Transform::update_block(ctx, block);
         });
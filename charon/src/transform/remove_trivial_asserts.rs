@@ -0,0 +1,51 @@
+//! # Micro-pass: remove the assertions whose condition is a constant equal to the expected value.
+//! Among the ones which always fail, we replace the assumptions ([AssertKind::Assume]) with
+//! undefined behavior, and the statements that become unreachable are then removed by
+//! [`filter_unreachable_blocks`](super::filter_unreachable_blocks). We keep the other failing
+//! assertions, which panic: the user assertions on a constant are already turned into jumps to
+//! their panic block by [`reconstruct_asserts`](super::reconstruct_asserts), and the failing
+//! checks inserted by rustc have no corresponding abort.
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcPass;
+
+pub struct Transform;
+impl UllbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        if ctx.options.no_cleanup {
+            return;
+        }
+
+        for block in b.body.iter_mut() {
+            for i in 0..block.statements.len() {
+                let st = &mut block.statements[i];
+                let RawStatement::Assert(Assert {
+                    cond:
+                        Operand::Const(ConstantExpr {
+                            value: RawConstantExpr::Literal(Literal::Bool(value)),
+                            ..
+                        }),
+                    expected,
+                    kind,
+                }) = &st.content
+                else {
+                    continue;
+                };
+                if value == expected {
+                    st.content = RawStatement::Nop;
+                    continue;
+                }
+                if *kind != AssertKind::Assume {
+                    continue;
+                }
+                // The assumption never holds: the rest of the block is dead.
+                let span = st.span;
+                block.statements.truncate(i);
+                block.terminator =
+                    Terminator::new(span, RawTerminator::Abort(AbortKind::UndefinedBehavior));
+                break;
+            }
+        }
+    }
+}
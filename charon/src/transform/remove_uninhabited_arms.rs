@@ -0,0 +1,71 @@
+//! # Micro-pass: make the arms of a switch on an enum discriminant that correspond to uninhabited
+//! variants jump to an `undefined_behavior` block. Such arms can't be taken, whatever the code
+//! rustc generated for them (e.g. a `panic!()` the user wrote in an `Err(_)` arm of a match on a
+//! `Result<u32, Empty>`). The blocks that become unreachable are then removed by
+//! [`filter_unreachable_blocks`](super::filter_unreachable_blocks).
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcPass;
+
+pub struct Transform;
+impl UllbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        if ctx.options.no_cleanup {
+            return;
+        }
+
+        // The block we redirect the uninhabited arms to. We add it at the end if we need it.
+        let unreachable_block = b.body.next_id();
+        let mut needs_unreachable_block = false;
+
+        for block in b.body.iter_mut() {
+            // We look for a switch on the discriminant read at the end of the block.
+            let Some(Statement {
+                content: RawStatement::Assign(dest, Rvalue::Discriminant(scrutinee, adt_id)),
+                ..
+            }) = block.statements.last()
+            else {
+                continue;
+            };
+            let RawTerminator::Switch {
+                discr: Operand::Copy(discr) | Operand::Move(discr),
+                targets: SwitchTargets::SwitchInt(_, targets, _),
+            } = &mut block.terminator.content
+            else {
+                continue;
+            };
+            if discr != dest {
+                continue;
+            }
+            let TyKind::Adt(_, args) = scrutinee.ty().kind() else {
+                continue;
+            };
+            let Some(TypeDeclKind::Enum(variants)) =
+                ctx.translated.type_decls.get(*adt_id).map(|d| &d.kind)
+            else {
+                continue;
+            };
+            for (value, target) in targets.iter_mut() {
+                let is_uninhabited = variants
+                    .iter()
+                    .find(|variant| variant.discriminant == *value)
+                    .is_some_and(|variant| ctx.translated.variant_is_uninhabited(variant, args));
+                if is_uninhabited {
+                    *target = unreachable_block;
+                    needs_unreachable_block = true;
+                }
+            }
+        }
+
+        if needs_unreachable_block {
+            let _ = b.body.push(BlockData {
+                statements: Vec::new(),
+                terminator: Terminator::new(
+                    b.span,
+                    RawTerminator::Abort(AbortKind::UndefinedBehavior),
+                ),
+            });
+        }
+    }
+}
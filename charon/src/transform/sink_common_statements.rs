@@ -0,0 +1,108 @@
+//! # Micro-pass: sink the statements that end all the branches of a switch into the block where
+//! the branches join. Some desugarings duplicate statements in each branch, e.g.:
+//! ```text
+//! bb0: switch copy b -> bb1, bb2
+//! bb1: x := const 1; y := const 0; goto bb3
+//! bb2: x := const 2; y := const 0; goto bb3
+//! bb3: ...
+//! ```
+//! which we turn into:
+//! ```text
+//! bb0: switch copy b -> bb1, bb2
+//! bb1: x := const 1; goto bb3
+//! bb2: x := const 2; goto bb3
+//! bb3: y := const 0; ...
+//! ```
+//! We only do this when the branches are only reached from the switch and the join block is only
+//! reached from the branches, so that moving the statements doesn't change what is executed.
+use itertools::Itertools;
+
+use crate::ids::Vector;
+use crate::transform::TransformCtx;
+use crate::ullbc_ast::*;
+
+use super::ctx::UllbcPass;
+
+/// Whether the two statements are the same. We only compare the simple statements: the
+/// assignments of operands and borrows, and the drops.
+fn same_statement(st1: &Statement, st2: &Statement) -> bool {
+    use RawStatement::*;
+    match (&st1.content, &st2.content) {
+        (Assign(p1, Rvalue::Use(op1)), Assign(p2, Rvalue::Use(op2))) => p1 == p2 && op1 == op2,
+        (Assign(p1, Rvalue::Ref(q1, k1)), Assign(p2, Rvalue::Ref(q2, k2))) => {
+            p1 == p2 && q1 == q2 && k1 == k2
+        }
+        (StorageDead(v1), StorageDead(v2)) => v1 == v2,
+        (Drop(p1), Drop(p2)) | (Deinit(p1), Deinit(p2)) => p1 == p2,
+        _ => false,
+    }
+}
+
+pub struct Transform;
+impl UllbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, body: &mut ExprBody) {
+        if !ctx.options.sink_common_statements {
+            return;
+        }
+
+        // Compute the predecessors of each block.
+        let mut predecessors: Vector<BlockId, Vec<BlockId>> = body.body.map_ref(|_| Vec::new());
+        for (block_id, block) in body.body.iter_indexed() {
+            for target in block.targets().into_iter().unique() {
+                predecessors[target].push(block_id);
+            }
+        }
+
+        for switch_id in body.body.all_indices().collect_vec() {
+            let RawTerminator::Switch { targets, .. } = &body.body[switch_id].terminator.content
+            else {
+                continue;
+            };
+            let arms = targets.get_targets().into_iter().unique().collect_vec();
+            if arms.len() < 2 || arms.contains(&switch_id) {
+                continue;
+            }
+            // All the arms are only reached from the switch, and jump to the same block.
+            let join_ids = arms
+                .iter()
+                .map(|&arm| match body.body[arm].terminator.content {
+                    RawTerminator::Goto { target } if predecessors[arm] == [switch_id] => {
+                        Some(target)
+                    }
+                    _ => None,
+                })
+                .collect::<Option<Vec<_>>>();
+            let Some(join_id) = join_ids.and_then(|ids| ids.into_iter().all_equal_value().ok())
+            else {
+                continue;
+            };
+            // The join block is only reached from the arms.
+            if join_id == switch_id
+                || arms.contains(&join_id)
+                || predecessors[join_id].len() != arms.len()
+                || !predecessors[join_id].iter().all(|id| arms.contains(id))
+            {
+                continue;
+            }
+
+            // Move the common trailing statements to the start of the join block.
+            loop {
+                let last = |arm: BlockId| body.body[arm].statements.last();
+                let Some(first_last) = last(arms[0]) else {
+                    break;
+                };
+                if !arms[1..]
+                    .iter()
+                    .all(|&arm| last(arm).is_some_and(|st| same_statement(first_last, st)))
+                {
+                    break;
+                }
+                let st = body.body[arms[0]].statements.pop().unwrap();
+                for &arm in &arms[1..] {
+                    body.body[arm].statements.pop();
+                }
+                body.body[join_id].statements.insert(0, st);
+            }
+        }
+    }
+}
@@ -0,0 +1,126 @@
+//! # Micro-pass: substitute the temporaries which are assigned once and used once. The bodies we
+//! get from MIR are full of those, e.g. `let t = a + b; t` gives:
+//! ```text
+//! t := copy a + copy b
+//! @0 := move t
+//! drop t
+//! return
+//! ```
+//! which we turn into:
+//! ```text
+//! @0 := copy a + copy b
+//! return
+//! ```
+//! When the temporary is assigned an operand, we also substitute it into the operands of the
+//! following statement, e.g. the arguments of a call.
+//!
+//! To stay clear of side effects and aliasing, we only substitute into the statement which
+//! immediately follows the assignment (ignoring `Nop`s): nothing can happen in between, so we
+//! never move an rvalue across a call, a write or a mutable borrow.
+use std::collections::{HashMap, HashSet};
+use std::mem;
+
+use crate::llbc_ast::*;
+use crate::transform::TransformCtx;
+
+use super::ctx::LlbcPass;
+
+/// Whether the operand is a read of `var` (with no projections).
+fn is_read_of(op: &Operand, var: VarId) -> bool {
+    match op {
+        Operand::Move(place) | Operand::Copy(place) => place.as_local() == Some(var),
+        Operand::Const(_) => false,
+    }
+}
+
+/// Find the operand which reads `var` among the direct operands of the statement.
+fn find_read(st: &mut Statement, var: VarId) -> Option<&mut Operand> {
+    let mut ops: Vec<&mut Operand> = match &mut st.content {
+        RawStatement::Assign(_, rvalue) => match rvalue {
+            Rvalue::Use(op)
+            | Rvalue::UnaryOp(_, op)
+            | Rvalue::BlackBox(op)
+            | Rvalue::Repeat(op, ..) => vec![op],
+            Rvalue::BinaryOp(_, op1, op2) => vec![op1, op2],
+            Rvalue::Aggregate(_, ops) => ops.iter_mut().collect(),
+            _ => vec![],
+        },
+        RawStatement::Call(call) => call.args.iter_mut().collect(),
+        _ => vec![],
+    };
+    let i = ops.iter().position(|op| is_read_of(op, var))?;
+    Some(ops.swap_remove(i))
+}
+
+/// If `seq` starts with the assignment of one of the candidate variables, try to substitute it
+/// into the next statement. Returns the substituted variable on success.
+fn substitute(seq: &mut [Statement], candidates: &HashSet<VarId>) -> Option<VarId> {
+    let (first, rest) = seq.split_first_mut()?;
+    let RawStatement::Assign(dest, rvalue) = &mut first.content else {
+        return None;
+    };
+    let var = dest.as_local().filter(|var| candidates.contains(var))?;
+    let next = rest.iter_mut().find(|st| !st.content.is_nop())?;
+
+    if let RawStatement::Assign(_, next_rvalue) = &mut next.content
+        && let Rvalue::Use(op) = next_rvalue
+        && is_read_of(op, var)
+    {
+        // `t := rvalue; x := move t` becomes `x := rvalue`.
+        mem::swap(next_rvalue, rvalue);
+    } else if let Rvalue::Use(op) = rvalue
+        && let Some(read) = find_read(next, var)
+    {
+        // `t := op; x := f(move t)` becomes `x := f(op)`.
+        mem::swap(read, op);
+    } else {
+        return None;
+    }
+    first.content = RawStatement::Nop;
+    Some(var)
+}
+
+pub struct Transform;
+impl LlbcPass for Transform {
+    fn transform_body(&self, ctx: &mut TransformCtx, b: &mut ExprBody) {
+        if !ctx.options.substitute_single_use {
+            return;
+        }
+
+        // Count the occurrences of the locals, apart from the drops.
+        let mut occurrences: HashMap<VarId, usize> = HashMap::new();
+        b.body.dyn_visit_in_body(|var: &VarId| {
+            *occurrences.entry(*var).or_default() += 1;
+        });
+        b.body.dyn_visit_in_body(|st: &Statement| {
+            if let RawStatement::Drop(place) = &st.content
+                && let Some(var) = place.as_local()
+            {
+                *occurrences.get_mut(&var).unwrap() -= 1;
+            }
+        });
+        // The candidates are the locals which are neither the return value nor an argument, and
+        // which occur exactly twice: once when assigned, once when used.
+        let candidates: HashSet<VarId> = occurrences
+            .into_iter()
+            .filter(|(var, count)| var.index() > b.locals.arg_count && *count == 2)
+            .map(|(var, _)| var)
+            .collect();
+
+        let mut substituted = HashSet::new();
+        b.body.transform_sequences(|seq| {
+            substituted.extend(substitute(seq, &candidates));
+            vec![]
+        });
+        // The substituted locals are not used anymore: remove their drops.
+        b.body.visit_statements(|st: &mut Statement| {
+            if let RawStatement::Drop(place) = &st.content
+                && place
+                    .as_local()
+                    .is_some_and(|var| substituted.contains(&var))
+            {
+                st.content = RawStatement::Nop;
+            }
+        });
+    }
+}
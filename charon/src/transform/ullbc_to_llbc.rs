@@ -1603,7 +1603,7 @@ fn translate_terminator(
 /// - a panic or return
 /// - a break which goes to a loop outside the expression
 /// - a continue statement
-fn is_terminal(block: &tgt::Block) -> bool {
+pub fn is_terminal(block: &tgt::Block) -> bool {
     is_terminal_explore_block(0, block)
 }
 
@@ -1624,6 +1624,20 @@ fn is_terminal_explore(num_loops: usize, st: &tgt::Statement) -> bool {
             .iter_targets()
             .all(|tgt_st| is_terminal_explore_block(num_loops, tgt_st)),
         tgt::RawStatement::Loop(loop_st) => is_terminal_explore_block(num_loops + 1, loop_st),
+        // The `else` block diverges, but the bindings don't.
+        tgt::RawStatement::LetElse(_, _, bindings, _) => {
+            is_terminal_explore_block(num_loops, bindings)
+        }
+        // The loop exits when the iterator is exhausted.
+        tgt::RawStatement::ForLoop(..) => false,
+        // Either one of the conditions fails and we run its `else` block, or we run the `then`
+        // block.
+        tgt::RawStatement::IfLet(conditions, then_block) => {
+            conditions
+                .iter()
+                .all(|cond| is_terminal_explore_block(num_loops, &cond.else_block))
+                && is_terminal_explore_block(num_loops, then_block)
+        }
     }
 }
 fn is_terminal_explore_block(num_loops: usize, block: &tgt::Block) -> bool {
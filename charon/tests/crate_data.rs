@@ -6,44 +6,17 @@ use std::error::Error;
 use charon_lib::llbc_ast::*;
 
 mod util;
+use util::{fun_by_name, llbc_body, repr_name, trait_name, type_by_name};
 
 fn translate(code: impl std::fmt::Display) -> anyhow::Result<TranslatedCrate> {
     util::translate_rust_text(code)
 }
 
-/// `Name` is a complex datastructure; to inspect it we serialize it a little bit.
-fn repr_name(crate_data: &TranslatedCrate, n: &Name) -> String {
-    n.name
-        .iter()
-        .map(|path_elem| match path_elem {
-            PathElem::Ident(i, _) => i.clone(),
-            PathElem::Impl(elem, _) => match elem {
-                ImplElem::Trait(impl_id) => match crate_data.trait_impls.get(*impl_id) {
-                    None => format!("<trait impl#{impl_id}>"),
-                    Some(timpl) => {
-                        let trait_name = trait_name(crate_data, timpl.impl_trait.trait_id);
-                        format!("<impl {trait_name} for ??>")
-                    }
-                },
-                ImplElem::Ty(..) => "<inherent impl>".to_string(),
-            },
-        })
-        .join("::")
-}
-
 fn repr_span(span: Span) -> String {
     let raw_span = span.span;
     format!("{}-{}", raw_span.beg, raw_span.end)
 }
 
-fn trait_name(crate_data: &TranslatedCrate, trait_id: TraitDeclId) -> &str {
-    let tr = &crate_data.trait_decls[trait_id];
-    let PathElem::Ident(trait_name, _) = tr.item_meta.name.name.last().unwrap() else {
-        panic!()
-    };
-    trait_name
-}
-
 /// A general item, with information shared by all items.
 struct Item<'c> {
     name_str: String,
@@ -293,7 +266,11 @@ fn attributes() -> anyhow::Result<()> {
         struct Struct;
 
         #[non_exhaustive]
-        enum Enum {}
+        enum Enum {
+            #[non_exhaustive]
+            A {},
+            B,
+        }
 
         #[clippy::foo]
         trait Trait {}
@@ -310,6 +287,20 @@ fn attributes() -> anyhow::Result<()> {
         #[inline(never)]
         /// This is a doc comment.
         fn main() {}
+
+        #[no_mangle]
+        fn unmangled() {}
+
+        #[export_name = "foo"]
+        fn exported() {}
+
+        #[must_use]
+        fn must_use() -> u32 {
+            0
+        }
+
+        #[must_use]
+        struct MustUse;
         "#,
     )?;
     assert_eq!(
@@ -320,6 +311,11 @@ fn attributes() -> anyhow::Result<()> {
         unknown_attrs(&crate_data.type_decls[1].item_meta),
         vec!["non_exhaustive"]
     );
+    assert!(!crate_data.type_decls[0].item_meta.non_exhaustive);
+    assert!(crate_data.type_decls[1].item_meta.non_exhaustive);
+    let variants = crate_data.type_decls[1].kind.as_enum().unwrap();
+    assert!(variants[VariantId::new(0)].non_exhaustive);
+    assert!(!variants[VariantId::new(1)].non_exhaustive);
     assert_eq!(
         unknown_attrs(&crate_data.trait_decls[0].item_meta),
         vec!["clippy::foo"]
@@ -354,6 +350,28 @@ fn attributes() -> anyhow::Result<()> {
             .unwrap(),
         &Attribute::DocComment(" This is a doc comment.".to_owned())
     );
+    let fun_meta = |name: &str| &fun_by_name(&crate_data, name).item_meta;
+    let main = fun_meta("test_crate::main");
+    assert!(!main.no_mangle);
+    assert_eq!(main.export_name, None);
+    let unmangled = fun_meta("test_crate::unmangled");
+    assert!(unmangled.no_mangle);
+    assert_eq!(unmangled.export_name, None);
+    let exported = fun_meta("test_crate::exported");
+    assert!(!exported.no_mangle);
+    assert_eq!(exported.export_name.as_deref(), Some("foo"));
+    assert!(!exported.must_use);
+    assert!(fun_meta("test_crate::must_use").must_use);
+    assert!(
+        !type_by_name(&crate_data, "test_crate::Struct")
+            .item_meta
+            .must_use
+    );
+    assert!(
+        type_by_name(&crate_data, "test_crate::MustUse")
+            .item_meta
+            .must_use
+    );
     Ok(())
 }
 
@@ -708,3 +726,3342 @@ fn known_trait_method_call() -> Result<(), Box<dyn Error>> {
     };
     Ok(())
 }
+
+#[test]
+fn const_generic_defaults() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        struct S<const B: bool, const N: usize = 4>([u8; N]);
+        "#,
+    )?;
+    let cgs = crate_data.type_decls[0]
+        .generics
+        .const_generics
+        .iter()
+        .collect_vec();
+    assert_eq!(cgs[0].name, "B");
+    assert_eq!(cgs[0].default, None);
+    assert_eq!(cgs[1].name, "N");
+    assert_eq!(
+        cgs[1].default,
+        Some(ConstGeneric::Value(Literal::Scalar(ScalarValue::Usize(4))))
+    );
+    Ok(())
+}
+
+#[test]
+fn lower_discriminant_checks() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        fn is_some(x: Option<u32>) -> bool {
+            x.is_some()
+        }
+        fn is_err(x: Result<u32, ()>) -> bool {
+            matches!(x, Err(_))
+        }
+        "#,
+        &["--lower-discriminant-checks"],
+    )?;
+    for name in ["test_crate::is_some", "test_crate::is_err"] {
+        let function = fun_by_name(&crate_data, name);
+        let body = &llbc_body(function).body;
+        let statements = body.statements.iter().map(|st| &st.content).collect_vec();
+        assert!(!statements.iter().any(|st| st.is_call() || st.is_switch()));
+        assert!(statements
+            .iter()
+            .any(|st| matches!(st, RawStatement::Assign(_, Rvalue::Discriminant(..)))));
+        assert!(statements.iter().any(|st| matches!(
+            st,
+            RawStatement::Assign(_, Rvalue::BinaryOp(BinOp::Eq, _, Operand::Const(_)))
+        )));
+    }
+    Ok(())
+}
+
+#[test]
+fn panic_message() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn explicit() {
+            panic!("x")
+        }
+        fn unreachable() {
+            unreachable!()
+        }
+        fn formatted(n: u32) {
+            panic!("{n}")
+        }
+        "#,
+    )?;
+    let panic_of = |name: &str| {
+        let function = fun_by_name(&crate_data, name);
+        let body = &llbc_body(function).body;
+        body.statements
+            .iter()
+            .find_map(|st| match &st.content {
+                RawStatement::Abort(AbortKind::Panic { kind, message, .. }) => {
+                    Some((*kind, message.clone()))
+                }
+                _ => None,
+            })
+            .unwrap()
+    };
+    assert_eq!(
+        panic_of("test_crate::explicit"),
+        (PanicKind::Explicit, Some("x".to_owned()))
+    );
+    assert_eq!(
+        panic_of("test_crate::unreachable"),
+        (
+            PanicKind::Explicit,
+            Some("internal error: entered unreachable code".to_owned())
+        )
+    );
+    assert_eq!(
+        panic_of("test_crate::formatted"),
+        (PanicKind::Explicit, None)
+    );
+    Ok(())
+}
+
+#[test]
+fn unwrap_panic_kind() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        fn unwrap(res: Result<u32, u32>) -> u32 {
+            res.unwrap()
+        }
+        "#,
+        &["--extract-opaque-bodies"],
+    )?;
+    // The panic happens at the call to `unwrap_failed` in the body of `Result::unwrap`.
+    let function = fun_by_name(&crate_data, "core::result::<inherent impl>::unwrap");
+    let mut panics = vec![];
+    llbc_body(function)
+        .body
+        .dyn_visit_in_body(|st: &Statement| {
+            if let RawStatement::Abort(AbortKind::Panic { kind, message, .. }) = &st.content {
+                panics.push((*kind, message.clone()));
+            }
+        });
+    assert_eq!(
+        panics,
+        vec![(
+            PanicKind::Unwrap,
+            Some("called `Result::unwrap()` on an `Err` value".to_owned())
+        )]
+    );
+    Ok(())
+}
+
+#[test]
+fn rc_builtin() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn deref(rc: std::rc::Rc<u32>) -> u32 {
+            *rc
+        }
+        "#,
+    )?;
+    let function = &crate_data.fun_decls[0];
+    assert_eq!(
+        repr_name(&crate_data, &function.item_meta.name),
+        "test_crate::deref"
+    );
+    assert!(matches!(
+        function.signature.inputs[0].kind(),
+        TyKind::Adt(TypeId::Builtin(BuiltinTy::Rc), _)
+    ));
+    let body = &llbc_body(function).body;
+    // The call to `Deref::deref` became a deref projection on the `Rc`.
+    assert!(!body
+        .statements
+        .iter()
+        .any(|st| matches!(st.content, RawStatement::Call(_))));
+    assert!(body.statements.iter().any(|st| match &st.content {
+        RawStatement::Assign(_, Rvalue::Ref(place, _)) => match place.as_projection() {
+            Some((sub, ProjectionElem::Deref)) => matches!(
+                sub.ty().kind(),
+                TyKind::Adt(TypeId::Builtin(BuiltinTy::Rc), _)
+            ),
+            _ => false,
+        },
+        _ => false,
+    }));
+    Ok(())
+}
+
+#[test]
+fn let_else() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        fn let_else(opt: Option<u32>) -> u32 {
+            let Some(x) = opt else {
+                return 0;
+            };
+            x
+        }
+        "#,
+        &["--reconstruct-let-else"],
+    )?;
+    let function = &crate_data.fun_decls[0];
+    let body = llbc_body(function);
+    let statements = body
+        .body
+        .statements
+        .iter()
+        .map(|st| &st.content)
+        .collect_vec();
+    assert!(!statements.iter().any(|st| st.is_switch()));
+    let (scrutinee, variant, bindings, else_block) = statements
+        .iter()
+        .find_map(|st| match st {
+            RawStatement::LetElse(scrutinee, variant, bindings, else_block) => {
+                Some((scrutinee, variant, bindings, else_block))
+            }
+            _ => None,
+        })
+        .unwrap();
+    // The scrutinee is the argument, and we check for the `Some` variant.
+    assert_eq!(scrutinee.as_local(), Some(VarId::new(1)));
+    assert_eq!(*variant, VariantId::new(1));
+    // The bindings assign `x`.
+    let binding_names = bindings
+        .statements
+        .iter()
+        .filter_map(|st| match &st.content {
+            RawStatement::Assign(dest, _) => body.locals[dest.var_id()].name.as_deref(),
+            _ => None,
+        })
+        .collect_vec();
+    assert_eq!(binding_names, ["x"]);
+    // The `else` block returns.
+    assert!(else_block
+        .statements
+        .iter()
+        .any(|st| matches!(st.content, RawStatement::Return)));
+    Ok(())
+}
+
+#[test]
+fn field_by_name() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        struct Struct {
+            field0: u32,
+            field1: bool,
+        }
+        enum Enum {
+            A { x: u32 },
+            B { x: u32, y: bool },
+        }
+        "#,
+    )?;
+    let struct_decl = type_by_name(&crate_data, "test_crate::Struct");
+    let enum_decl = type_by_name(&crate_data, "test_crate::Enum");
+
+    let (field_id, field) = struct_decl.field_by_name(None, "field1").unwrap();
+    assert_eq!(field_id, FieldId::new(1));
+    assert!(matches!(field.ty.kind(), TyKind::Literal(LiteralTy::Bool)));
+    assert!(struct_decl.field_by_name(None, "field2").is_none());
+    assert!(struct_decl
+        .field_by_name(Some(VariantId::ZERO), "field1")
+        .is_none());
+
+    let (field_id, field) = enum_decl
+        .field_by_name(Some(VariantId::new(1)), "y")
+        .unwrap();
+    assert_eq!(field_id, FieldId::new(1));
+    assert_eq!(field.name.as_deref(), Some("y"));
+    assert!(enum_decl
+        .field_by_name(Some(VariantId::ZERO), "y")
+        .is_none());
+    assert!(enum_decl.field_by_name(None, "x").is_none());
+    Ok(())
+}
+
+#[test]
+fn sorted_switch_targets() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn switch(x: u32) -> u32 {
+            match x {
+                3 => 10,
+                1 => 11,
+                2 => 12,
+                _ => 13,
+            }
+        }
+        "#,
+    )?;
+    let function = &crate_data.fun_decls[0];
+    let body = llbc_body(function);
+    let targets = body
+        .body
+        .statements
+        .iter()
+        .find_map(|st| match &st.content {
+            RawStatement::Switch(Switch::SwitchInt(_, _, targets, _)) => Some(targets),
+            _ => None,
+        })
+        .unwrap();
+    let values = targets
+        .iter()
+        .flat_map(|(values, _)| values.iter().copied())
+        .collect_vec();
+    assert_eq!(values, [1, 2, 3].map(ScalarValue::U32),);
+    Ok(())
+}
+
+#[test]
+fn reference_self_impl() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        trait Foo {
+            fn foo(&self) -> u32;
+        }
+        impl Foo for &u32 {
+            fn foo(&self) -> u32 {
+                **self
+            }
+        }
+        fn call_foo(x: &u32) -> u32 {
+            x.foo()
+        }
+        "#,
+    )?;
+    // The impl is registered, with `&u32` as its self type.
+    let [trait_impl] = &crate_data.trait_impls.iter().collect_vec()[..] else {
+        panic!()
+    };
+    let self_ty = &trait_impl.impl_trait.generics.types[TypeVarId::ZERO];
+    let TyKind::Ref(_, pointee, RefKind::Shared) = self_ty.kind() else {
+        panic!()
+    };
+    assert!(matches!(
+        pointee.kind(),
+        TyKind::Literal(LiteralTy::Integer(IntegerTy::U32))
+    ));
+
+    // The call resolves to the method of that impl.
+    let function = fun_by_name(&crate_data, "test_crate::call_foo");
+    let body = llbc_body(function);
+    let call = body
+        .body
+        .statements
+        .iter()
+        .find_map(|st| match &st.content {
+            RawStatement::Call(call) => Some(call),
+            _ => None,
+        })
+        .unwrap();
+    let FnOperand::Regular(FnPtr {
+        func: FunIdOrTraitMethodRef::Fun(FunId::Regular(id)),
+        ..
+    }) = &call.func
+    else {
+        panic!()
+    };
+    let ItemKind::TraitImpl { impl_ref, .. } = &crate_data.fun_decls[*id].kind else {
+        panic!()
+    };
+    assert_eq!(impl_ref.impl_id, trait_impl.def_id);
+    Ok(())
+}
+
+#[test]
+fn items_with_attr() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        #![feature(register_tool)]
+        #![register_tool(charon)]
+        #[clippy::foo]
+        struct Struct;
+
+        #[clippy::foo(arg)]
+        fn tagged() {}
+
+        #[clippy::bar]
+        fn untagged() {}
+
+        #[charon::opaque]
+        fn opaque() {}
+        "#,
+    )?;
+    let names = |attr: &str| {
+        crate_data
+            .items_with_attr(attr)
+            .into_iter()
+            .map(|id| repr_name(&crate_data, crate_data.item_name(id).unwrap()))
+            .sorted()
+            .collect_vec()
+    };
+    assert_eq!(
+        names("clippy::foo"),
+        vec!["test_crate::Struct", "test_crate::tagged"]
+    );
+    assert_eq!(names("clippy::bar"), vec!["test_crate::untagged"]);
+    assert_eq!(names("charon::opaque"), vec!["test_crate::opaque"]);
+    assert!(names("clippy").is_empty());
+    Ok(())
+}
+
+#[test]
+fn deterministic_serialization() -> anyhow::Result<()> {
+    let code = r#"
+        trait Trait {
+            fn method(&self);
+        }
+        struct Struct;
+        impl Trait for Struct {
+            fn method(&self) {}
+        }
+        fn foo() {}
+        fn bar() {}
+        static BAZ: u32 = 0;
+        "#;
+    let serialize = || -> anyhow::Result<String> {
+        let mut crate_data = translate(code)?;
+        // The paths of the temporary files differ between runs.
+        crate_data.files = Default::default();
+        crate_data.options = Default::default();
+        Ok(serde_json::to_string(&crate_data)?)
+    };
+    assert_eq!(serialize()?, serialize()?);
+    Ok(())
+}
+
+#[test]
+fn const_generic_expr() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        #![feature(generic_const_exprs)]
+        #![allow(incomplete_features)]
+        fn f<const N: usize>() -> [u8; N + 1]
+        where
+            [(); N + 1]:,
+        {
+            todo!()
+        }
+        "#,
+    )?;
+    let function = &crate_data.fun_decls[0];
+    let TyKind::Adt(TypeId::Builtin(BuiltinTy::Array), generics) = function.signature.output.kind()
+    else {
+        panic!()
+    };
+    let ConstGeneric::App(ConstGenericOp::BinOp(BinOp::Add), args) = &generics.const_generics[0]
+    else {
+        panic!("{:?}", generics.const_generics[0])
+    };
+    let [lhs, rhs] = args.as_slice() else {
+        panic!("{args:?}")
+    };
+    assert!(matches!(lhs, ConstGeneric::Var(_)));
+    assert_eq!(
+        *rhs,
+        ConstGeneric::Value(Literal::Scalar(ScalarValue::Usize(1)))
+    );
+
+    let crate_data = translate(
+        r#"
+        #![feature(generic_const_exprs)]
+        #![allow(incomplete_features)]
+        fn f<const N: usize, const M: usize>() -> [u8; N * M]
+        where
+            [(); N * M]:,
+        {
+            todo!()
+        }
+        "#,
+    )?;
+    let function = &crate_data.fun_decls[0];
+    let TyKind::Adt(TypeId::Builtin(BuiltinTy::Array), generics) = function.signature.output.kind()
+    else {
+        panic!()
+    };
+    let ConstGeneric::App(ConstGenericOp::BinOp(BinOp::Mul), args) = &generics.const_generics[0]
+    else {
+        panic!("{:?}", generics.const_generics[0])
+    };
+    assert!(matches!(
+        args.as_slice(),
+        [ConstGeneric::Var(_), ConstGeneric::Var(_)]
+    ));
+    Ok(())
+}
+
+#[test]
+fn remove_copy_drops() -> anyhow::Result<()> {
+    let code = r#"
+        fn f(x: u32) -> u32 {
+            let y = x + 1;
+            y
+        }
+        "#;
+    let count_drops = |crate_data: &TranslatedCrate| {
+        let body = crate_data.fun_decls[0].body.as_ref().unwrap();
+        body.as_structured()
+            .unwrap()
+            .body
+            .statements
+            .iter()
+            .filter(|st| st.content.is_drop())
+            .count()
+    };
+    // By default, the `StorageDead` of `y` becomes a drop.
+    let crate_data = translate(code)?;
+    assert_ne!(count_drops(&crate_data), 0);
+    let crate_data = util::translate_rust_text_with_args(code, &["--remove-copy-drops"])?;
+    assert_eq!(count_drops(&crate_data), 0);
+    Ok(())
+}
+
+#[test]
+fn dyn_method_call() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        trait Trait {
+            fn method(&self) -> u32;
+        }
+        fn call_method(x: &dyn Trait) -> u32 {
+            x.method()
+        }
+        "#,
+    )?;
+    let function = fun_by_name(&crate_data, "test_crate::call_method");
+    let body = llbc_body(function);
+    let call = body
+        .body
+        .statements
+        .iter()
+        .find_map(|st| match &st.content {
+            RawStatement::Call(call) => Some(call),
+            _ => None,
+        })
+        .unwrap();
+    let FnOperand::Dynamic(FnPtr {
+        func: FunIdOrTraitMethodRef::Trait(trait_ref, method_name, _),
+        ..
+    }) = &call.func
+    else {
+        panic!()
+    };
+    assert_eq!(method_name.0, "method");
+    assert!(matches!(trait_ref.kind, TraitRefKind::Dyn(_)));
+    assert_eq!(
+        trait_name(&crate_data, trait_ref.trait_decl_ref.skip_binder.trait_id),
+        "Trait"
+    );
+    // The receiver is the first argument.
+    assert_eq!(call.args.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn only_option() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        struct Used;
+        struct Unused;
+        fn f() -> Used {
+            g()
+        }
+        fn g() -> Used {
+            Used
+        }
+        fn h() -> Unused {
+            Unused
+        }
+        "#,
+        &["--only", "test_crate::f"],
+    )?;
+    let names = crate_data
+        .all_items()
+        .map(|item| repr_name(&crate_data, &item.item_meta().name))
+        .sorted()
+        .collect_vec();
+    assert_eq!(
+        names,
+        vec!["test_crate::Used", "test_crate::f", "test_crate::g"]
+    );
+    Ok(())
+}
+
+#[test]
+fn cfg_option() -> anyhow::Result<()> {
+    let code = r#"
+        #[cfg(feature = "x")]
+        fn gated() {}
+        fn always() {}
+        "#;
+    let fun_names = |crate_data: &TranslatedCrate| {
+        crate_data
+            .fun_decls
+            .iter()
+            .map(|f| repr_name(crate_data, &f.item_meta.name))
+            .sorted()
+            .collect_vec()
+    };
+    let crate_data = translate(code)?;
+    assert_eq!(fun_names(&crate_data), vec!["test_crate::always"]);
+    let crate_data = util::translate_rust_text_with_args(code, &["--cfg", "feature=x"])?;
+    assert_eq!(
+        fun_names(&crate_data),
+        vec!["test_crate::always", "test_crate::gated"]
+    );
+    Ok(())
+}
+
+#[test]
+fn global_kinds() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        const C: u32 = 0;
+        static S: u32 = 0;
+        static mut M: u32 = 0;
+        thread_local! {
+            static T: u32 = 0;
+        }
+        "#,
+    )?;
+    let kinds: HashMap<String, GlobalKind> = crate_data
+        .global_decls
+        .iter()
+        .map(|g| (repr_name(&crate_data, &g.item_meta.name), g.global_kind))
+        .collect();
+    assert_eq!(kinds["test_crate::C"], GlobalKind::NamedConst);
+    assert_eq!(kinds["test_crate::S"], GlobalKind::Static);
+    assert_eq!(kinds["test_crate::M"], GlobalKind::StaticMut);
+    assert_eq!(kinds["test_crate::T"], GlobalKind::ThreadLocal);
+    Ok(())
+}
+
+#[test]
+fn substitute_single_use() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        fn add(a: u32, b: u32) -> u32 {
+            let t = a + b;
+            return t;
+        }
+        "#,
+        &["--substitute-single-use"],
+    )?;
+    let function = &crate_data.fun_decls[0];
+    let body = llbc_body(function);
+    let assignments = body
+        .body
+        .statements
+        .iter()
+        .filter_map(|st| match &st.content {
+            RawStatement::Assign(dest, rvalue) => Some((dest, rvalue)),
+            _ => None,
+        })
+        .collect_vec();
+    // Only `@0 := copy a + copy b` remains.
+    let [(dest, Rvalue::BinaryOp(BinOp::Add, Operand::Copy(a), Operand::Copy(b)))] =
+        assignments[..]
+    else {
+        panic!("unexpected assignments: {assignments:?}")
+    };
+    assert_eq!(dest.as_local(), Some(VarId::new(0)));
+    assert_eq!(a.as_local(), Some(VarId::new(1)));
+    assert_eq!(b.as_local(), Some(VarId::new(2)));
+    // The temporaries have been removed.
+    assert_eq!(body.locals.vars.elem_count(), 3);
+    Ok(())
+}
+
+#[test]
+fn for_loops() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        fn sum(n: u32) -> u32 {
+            let mut s = 0;
+            for i in 0..n {
+                s += i;
+            }
+            s
+        }
+        "#,
+        &["--reconstruct-for-loops"],
+    )?;
+    let function = &crate_data.fun_decls[0];
+    let body = llbc_body(function);
+    let statements = body
+        .body
+        .statements
+        .iter()
+        .map(|st| &st.content)
+        .collect_vec();
+    assert!(!statements.iter().any(|st| st.is_loop()));
+    let (iter, item, loop_body) = statements
+        .iter()
+        .find_map(|st| match st {
+            RawStatement::ForLoop(iter, item, loop_body) => Some((iter, item, loop_body)),
+            _ => None,
+        })
+        .unwrap();
+    let name = |place: &Place| body.locals[place.var_id()].name.as_deref();
+    assert_eq!(name(iter), Some("iter"));
+    assert_eq!(name(item), Some("i"));
+    // The body doesn't call `next` anymore.
+    assert!(!loop_body
+        .statements
+        .iter()
+        .any(|st| st.content.is_call() || st.content.is_switch()));
+    Ok(())
+}
+
+#[test]
+fn resolve_assoc_type() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        trait Trait {
+            type Item;
+        }
+        impl<T> Trait for Vec<T> {
+            type Item = T;
+        }
+        fn f(_x: <Vec<u32> as Trait>::Item) {}
+        fn g<T: Trait>(_x: T::Item) {}
+        "#,
+    )?;
+    let input_ty = |name: &str| {
+        let fun = fun_by_name(&crate_data, name);
+        fun.signature.inputs[0].clone()
+    };
+
+    let ty = input_ty("test_crate::f");
+    let TyKind::TraitType(tr, name) = ty.kind() else {
+        panic!("{ty:?}")
+    };
+    assert!(matches!(tr.kind, TraitRefKind::TraitImpl(..)));
+    assert_eq!(
+        crate_data.resolve_assoc_type(tr, name),
+        Some(TyKind::Literal(LiteralTy::Integer(IntegerTy::U32)).into_ty())
+    );
+
+    let ty = input_ty("test_crate::g");
+    let TyKind::TraitType(tr, name) = ty.kind() else {
+        panic!("{ty:?}")
+    };
+    assert_eq!(crate_data.resolve_assoc_type(tr, name), None);
+    Ok(())
+}
+
+#[test]
+fn aggregate_field_order() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        struct S {
+            a: u8,
+            b: u16,
+            c: u32,
+        }
+        enum E {
+            V { x: u8, y: u16 },
+        }
+        fn id<T>(x: T) -> T {
+            x
+        }
+        fn mk_s(a: u8, b: u16, c: u32) -> S {
+            S { c: id(c), a: id(a), b }
+        }
+        fn mk_e(x: u8, y: u16) -> E {
+            E::V { y, x }
+        }
+        "#,
+    )?;
+    for (name, expected) in [
+        (
+            "test_crate::mk_s",
+            vec![IntegerTy::U8, IntegerTy::U16, IntegerTy::U32],
+        ),
+        ("test_crate::mk_e", vec![IntegerTy::U8, IntegerTy::U16]),
+    ] {
+        let function = fun_by_name(&crate_data, name);
+        let body = llbc_body(function);
+        let operands = body
+            .body
+            .statements
+            .iter()
+            .find_map(|st| match &st.content {
+                RawStatement::Assign(_, Rvalue::Aggregate(AggregateKind::Adt(..), ops)) => {
+                    Some(ops)
+                }
+                _ => None,
+            })
+            .unwrap();
+        // The operands are in the order of the field declarations.
+        let operand_tys = operands
+            .iter()
+            .map(|op| op.as_place().unwrap().ty().clone())
+            .collect_vec();
+        let expected = expected
+            .into_iter()
+            .map(|ity| TyKind::Literal(LiteralTy::Integer(ity)).into_ty())
+            .collect_vec();
+        assert_eq!(operand_tys, expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn align_and_cold() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        #[repr(align(16))]
+        struct S(u8);
+        struct T(u8);
+
+        #[cold]
+        fn f() {}
+        fn g() {}
+        "#,
+    )?;
+    assert_eq!(type_by_name(&crate_data, "test_crate::S").align, Some(16));
+    assert_eq!(type_by_name(&crate_data, "test_crate::T").align, None);
+    assert!(fun_by_name(&crate_data, "test_crate::f").item_meta.cold);
+    assert!(!fun_by_name(&crate_data, "test_crate::g").item_meta.cold);
+    Ok(())
+}
+
+#[test]
+fn visit_body_statements() -> anyhow::Result<()> {
+    let mut crate_data = translate(
+        r#"
+        fn f(x: u32) -> u32 {
+            x
+        }
+        "#,
+    )?;
+    let body = crate_data.fun_decls[0].body.as_mut().unwrap();
+    let body = body.as_structured_mut().unwrap();
+    // `@0 := copy x; return`
+    let mut count = 0;
+    body.visit_statements(|_| count += 1);
+    assert_eq!(count, 2);
+
+    body.visit_statements_mut(|st| st.comments_before.push("visited".to_owned()));
+    let mut visited = 0;
+    body.visit_statements(|st| visited += st.comments_before.len());
+    assert_eq!(visited, 2);
+    Ok(())
+}
+
+#[test]
+fn overloaded_deref() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        use std::ops::Deref;
+        struct MyPtr(u32);
+        impl Deref for MyPtr {
+            type Target = u32;
+            fn deref(&self) -> &u32 {
+                &self.0
+            }
+        }
+        fn deref_my_ptr(p: MyPtr) -> u32 {
+            *p
+        }
+        fn deref_box(b: Box<u32>) -> u32 {
+            *b
+        }
+        "#,
+    )?;
+    let body = |name: &str| llbc_body(fun_by_name(&crate_data, name));
+    let calls = |body: &ExprBody| {
+        let mut calls = vec![];
+        body.visit_statements(|st| {
+            if let RawStatement::Call(call) = &st.content {
+                calls.push(call.clone());
+            }
+        });
+        calls
+    };
+
+    // A user `Deref` impl is a call to its `deref` method.
+    let [call] = &calls(body("test_crate::deref_my_ptr"))[..] else {
+        panic!()
+    };
+    let FnOperand::Regular(FnPtr {
+        func: FunIdOrTraitMethodRef::Fun(FunId::Regular(id)),
+        ..
+    }) = &call.func
+    else {
+        panic!("{:?}", call.func)
+    };
+    let ItemKind::TraitImpl { item_name, .. } = &crate_data.fun_decls[*id].kind else {
+        panic!()
+    };
+    assert_eq!(item_name.0, "deref");
+
+    // Dereferencing a box is a projection.
+    let body = body("test_crate::deref_box");
+    assert!(calls(body).is_empty());
+    let mut box_derefs = 0;
+    body.body.dyn_visit_in_body(|place: &Place| {
+        if let Some((sub, ProjectionElem::Deref)) = place.as_projection() {
+            if sub.ty().is_box() {
+                box_derefs += 1;
+            }
+        }
+    });
+    assert!(box_derefs > 0);
+    Ok(())
+}
+
+#[test]
+fn ullbc_output() -> anyhow::Result<()> {
+    use charon_lib::ullbc_ast::{RawTerminator, SwitchTargets};
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        fn f(b: bool) -> u32 {
+            if b {
+                1
+            } else {
+                0
+            }
+        }
+        "#,
+        &["--ullbc"],
+    )?;
+    // The body is exported as a control-flow graph.
+    let body = crate_data.fun_decls[0].body.as_ref().unwrap();
+    assert!(body.as_structured().is_none());
+    let body = body.as_unstructured().unwrap();
+    assert!(body.body.iter().any(|block| matches!(
+        block.terminator.content,
+        RawTerminator::Switch {
+            targets: SwitchTargets::If(..),
+            ..
+        }
+    )));
+    Ok(())
+}
+
+#[test]
+fn closure_captures_by_ref() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn f(x: u32) -> u32 {
+            let c = || x;
+            c()
+        }
+        "#,
+    )?;
+    let function = fun_by_name(&crate_data, "test_crate::f");
+    let body = llbc_body(function);
+    let assignments = body
+        .body
+        .statements
+        .iter()
+        .filter_map(|st| match &st.content {
+            RawStatement::Assign(dest, rvalue) => Some((dest, rvalue)),
+            _ => None,
+        })
+        .collect_vec();
+    // The closure state is built from an explicit borrow of `x`.
+    let state = assignments
+        .iter()
+        .find_map(|(_, rvalue)| match rvalue {
+            Rvalue::Aggregate(AggregateKind::Closure(..), ops) => Some(ops),
+            _ => None,
+        })
+        .unwrap();
+    let [Operand::Move(upvar)] = &state[..] else {
+        panic!("{state:?}")
+    };
+    let borrow = assignments
+        .iter()
+        .find_map(|(dest, rvalue)| match rvalue {
+            Rvalue::Ref(place, BorrowKind::Shared) if *dest == upvar => Some(place),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(borrow.as_local(), Some(VarId::new(1)));
+    Ok(())
+}
+
+#[test]
+fn dominators() -> anyhow::Result<()> {
+    use charon_lib::ullbc_ast::{RawTerminator, SwitchTargets, START_BLOCK_ID};
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        fn f(b: bool) -> u32 {
+            let x = if b { 1 } else { 2 };
+            x
+        }
+        "#,
+        &["--ullbc"],
+    )?;
+    let body = crate_data.fun_decls[0].body.as_ref().unwrap();
+    let body = body.as_unstructured().unwrap();
+    let dominators = body.dominators();
+    assert_eq!(dominators[START_BLOCK_ID], None);
+
+    // The diamond: the two branches of the `if` jump to the same merge block.
+    let (switch_id, then_id, else_id) = body
+        .body
+        .iter_indexed()
+        .find_map(|(id, block)| match block.terminator.content {
+            RawTerminator::Switch {
+                targets: SwitchTargets::If(then_id, else_id),
+                ..
+            } => Some((id, then_id, else_id)),
+            _ => None,
+        })
+        .unwrap();
+    let goto_target = |id| match body.body[id].terminator.content {
+        RawTerminator::Goto { target } => target,
+        _ => panic!(),
+    };
+    let merge_id = goto_target(then_id);
+    assert_eq!(goto_target(else_id), merge_id);
+    assert_eq!(dominators[then_id], Some(switch_id));
+    assert_eq!(dominators[else_id], Some(switch_id));
+    assert_eq!(dominators[merge_id], Some(switch_id));
+    Ok(())
+}
+
+#[test]
+fn derived_default() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        #[derive(Default)]
+        struct S {
+            x: u32,
+        }
+        impl S {
+            fn new() -> Self {
+                S { x: 0 }
+            }
+        }
+        "#,
+    )?;
+    let type_id = crate_data.type_decls[0].def_id;
+    assert!(!crate_data.type_decls[0].item_meta.is_derived);
+    let [timpl] = &crate_data.trait_impls.iter().collect_vec()[..] else {
+        panic!()
+    };
+    assert!(timpl.item_meta.is_derived);
+
+    let fun = |name: &str| {
+        crate_data
+            .fun_decls
+            .iter()
+            .filter(|f| !matches!(f.kind, ItemKind::TraitDecl { .. }))
+            .find(|f| repr_name(&crate_data, &f.item_meta.name).ends_with(name))
+            .unwrap()
+    };
+    assert!(!fun("::new").item_meta.is_derived);
+    // The derived `default` method is translated, and builds the struct.
+    let default = fun("::default");
+    assert!(matches!(default.kind, ItemKind::TraitImpl { .. }));
+    assert!(default.item_meta.is_derived);
+    let body = llbc_body(default);
+    let mut builds_s = false;
+    body.visit_statements(|st| {
+        if let RawStatement::Assign(_, Rvalue::Aggregate(AggregateKind::Adt(id, ..), ops)) =
+            &st.content
+        {
+            builds_s |= *id == TypeId::Adt(type_id) && ops.len() == 1;
+        }
+    });
+    assert!(builds_s);
+    Ok(())
+}
+
+#[test]
+fn json_errors() -> anyhow::Result<()> {
+    let stderr = util::translate_rust_text_stderr(
+        r#"
+        #![feature(trait_alias)]
+        trait Alias = Clone;
+        "#,
+        &["--error-format=json", "--error-on-warnings"],
+    )?;
+    let diagnostics: Vec<serde_json::Value> = stderr
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .map(serde_json::from_str)
+        .try_collect()?;
+    let error = diagnostics
+        .iter()
+        .find(|diag| diag["message"] == "Trait aliases are not supported")
+        .unwrap_or_else(|| panic!("{stderr}"));
+    assert_eq!(error["level"], "error");
+    let span = &error["spans"][0];
+    assert!(span["file"].as_str().unwrap().ends_with("test_crate.rs"));
+    assert_eq!(span["beg"]["line"], 3);
+    Ok(())
+}
+
+#[test]
+fn range_construction() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn f(n: u32) -> core::ops::Range<u32> {
+            let r = 0..n;
+            r
+        }
+        "#,
+    )?;
+    let body = crate_data.fun_decls[0].body.as_ref().unwrap();
+    let body = body.as_structured().unwrap();
+    // `0..n` is a `Range { start: 0, end: n }` aggregate.
+    let mut ranges = vec![];
+    body.visit_statements(|st| {
+        if let RawStatement::Assign(
+            _,
+            Rvalue::Aggregate(AggregateKind::Adt(TypeId::Adt(id), ..), ops),
+        ) = &st.content
+        {
+            ranges.push((*id, ops.clone()));
+        }
+    });
+    let [(range_id, ops)] = &ranges[..] else {
+        panic!("{ranges:?}")
+    };
+    assert_eq!(
+        repr_name(&crate_data, crate_data.item_name(*range_id).unwrap()),
+        "core::ops::range::Range"
+    );
+    let [Operand::Const(start), Operand::Copy(end) | Operand::Move(end)] = &ops[..] else {
+        panic!("{ops:?}")
+    };
+    assert_eq!(
+        start.value,
+        RawConstantExpr::Literal(Literal::Scalar(ScalarValue::U32(0)))
+    );
+    assert_eq!(end.as_local(), Some(VarId::new(1)));
+    Ok(())
+}
+
+#[test]
+fn crate_stats() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        struct A;
+        enum B {
+            X,
+            Y,
+        }
+        fn f() {}
+        fn g(x: u32) -> u32 {
+            x
+        }
+        "#,
+    )?;
+    let stats = charon_lib::transform::CrateStats::new(&crate_data);
+    assert_eq!(stats.functions, 2);
+    assert_eq!(stats.types, 2);
+    assert_eq!(stats.globals, 0);
+    assert_eq!(stats.trait_decls, 0);
+    assert_eq!(stats.trait_impls, 0);
+    assert_eq!(stats.opaque_bodies, 0);
+    assert!(stats.statements > 0);
+    // The bodies are structured.
+    assert_eq!(stats.max_cfg_size, 0);
+    Ok(())
+}
+
+#[test]
+fn bool_and_char_const_generics() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        struct S<const C: char>;
+        fn f<const B: bool>() -> bool {
+            B
+        }
+        fn g() -> bool {
+            f::<true>()
+        }
+        "#,
+    )?;
+    let cg_ty = |generics: &GenericParams| generics.const_generics[ConstGenericVarId::ZERO].ty;
+    assert_eq!(cg_ty(&crate_data.type_decls[0].generics), LiteralTy::Char);
+
+    let f = fun_by_name(&crate_data, "test_crate::f");
+    assert_eq!(cg_ty(&f.signature.generics), LiteralTy::Bool);
+    // The body of `f` returns the const generic.
+    let body = llbc_body(f);
+    let mut reads_var = false;
+    body.visit_statements(|st| {
+        if let RawStatement::Assign(_, Rvalue::Use(Operand::Const(c))) = &st.content {
+            reads_var |= c.value.is_var();
+        }
+    });
+    assert!(reads_var);
+
+    // `g` instantiates it with `true`.
+    let body = llbc_body(fun_by_name(&crate_data, "test_crate::g"));
+    let mut calls = vec![];
+    body.visit_statements(|st| {
+        if let RawStatement::Call(call) = &st.content {
+            calls.push(call.clone());
+        }
+    });
+    let [Call {
+        func: FnOperand::Regular(fn_ptr),
+        ..
+    }] = &calls[..]
+    else {
+        panic!("{calls:?}")
+    };
+    assert_eq!(
+        fn_ptr.generics.const_generics[ConstGenericVarId::ZERO],
+        ConstGeneric::Value(Literal::Bool(true))
+    );
+    Ok(())
+}
+
+#[test]
+fn sink_common_statements() -> anyhow::Result<()> {
+    use charon_lib::ullbc_ast::{RawStatement, RawTerminator, SwitchTargets};
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        fn f(b: bool, mut y: u32) -> u32 {
+            let x;
+            if b {
+                x = 1;
+                y = 0;
+            } else {
+                x = 2;
+                y = 0;
+            }
+            x + y
+        }
+        "#,
+        &["--ullbc", "--sink-common-statements"],
+    )?;
+    let body = crate_data.fun_decls[0].body.as_ref().unwrap();
+    let body = body.as_unstructured().unwrap();
+    let (then_id, else_id) = body
+        .body
+        .iter()
+        .find_map(|block| match block.terminator.content {
+            RawTerminator::Switch {
+                targets: SwitchTargets::If(then_id, else_id),
+                ..
+            } => Some((then_id, else_id)),
+            _ => None,
+        })
+        .unwrap();
+    let goto_target = |id| match body.body[id].terminator.content {
+        RawTerminator::Goto { target } => target,
+        _ => panic!(),
+    };
+    let merge_id = goto_target(then_id);
+    assert_eq!(goto_target(else_id), merge_id);
+    // The assignment of `y` was moved to the merge block, the assignments of `x` stayed in the
+    // branches.
+    let assigned = |st: &charon_lib::ullbc_ast::Statement| match &st.content {
+        RawStatement::Assign(place, _) => place.as_local(),
+        _ => None,
+    };
+    let y = VarId::new(2);
+    for id in [then_id, else_id] {
+        let statements = &body.body[id].statements;
+        assert!(statements.iter().all(|st| assigned(st) != Some(y)));
+        assert!(statements.iter().any(|st| assigned(st).is_some()));
+    }
+    assert_eq!(assigned(&body.body[merge_id].statements[0]), Some(y));
+    Ok(())
+}
+
+#[test]
+fn drop_impl() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        struct S(u32);
+        impl Drop for S {
+            fn drop(&mut self) {}
+        }
+        struct T(u32);
+        "#,
+    )?;
+    let type_decl = |name: &str| type_by_name(&crate_data, name);
+    assert_eq!(type_decl("test_crate::T").drop_impl, None);
+    let impl_id = type_decl("test_crate::S").drop_impl.unwrap();
+    let trait_impl = &crate_data.trait_impls[impl_id];
+    assert_eq!(
+        trait_name(&crate_data, trait_impl.impl_trait.trait_id),
+        "Drop"
+    );
+    let (method_name, method) = &trait_impl.methods[0];
+    assert_eq!(method_name.0, "drop");
+    let method = &crate_data.fun_decls[method.skip_binder.id];
+    assert_eq!(
+        repr_name(&crate_data, &method.item_meta.name),
+        "test_crate::<impl Drop for ??>::drop"
+    );
+    Ok(())
+}
+
+#[test]
+fn simd_repr() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        #![feature(repr_simd)]
+        #[repr(simd)]
+        struct F32x4([f32; 4]);
+        #[repr(simd)]
+        struct Simd<T, const N: usize>([T; N]);
+        struct NotSimd([f32; 4]);
+        "#,
+    )?;
+    let f32_ty = TyKind::Literal(LiteralTy::Float(FloatTy::F32)).into_ty();
+    let simd = crate_data.type_decls[0].simd.as_ref().unwrap();
+    assert_eq!(simd.elem_ty, f32_ty);
+    assert_eq!(
+        simd.lanes,
+        ConstGeneric::Value(Literal::Scalar(ScalarValue::Usize(4)))
+    );
+    let simd = crate_data.type_decls[1].simd.as_ref().unwrap();
+    assert!(matches!(simd.elem_ty.kind(), TyKind::TypeVar(_)));
+    assert!(matches!(simd.lanes, ConstGeneric::Var(_)));
+    assert!(crate_data.type_decls[2].simd.is_none());
+    Ok(())
+}
+
+#[test]
+fn dedup_constants() -> anyhow::Result<()> {
+    // With optimized MIR, the borrowed arrays are promoted to constants.
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        fn f() -> &'static [u32; 8] {
+            &[1, 2, 3, 4, 5, 6, 7, 8]
+        }
+        fn g() -> &'static [u32; 8] {
+            &[1, 2, 3, 4, 5, 6, 7, 8]
+        }
+        "#,
+        &["--mir_optimized", "--dedup-constants"],
+    )?;
+    // The shared constant is the only global.
+    let [global] = crate_data.global_decls.iter().collect_vec()[..] else {
+        panic!()
+    };
+    // Its name can't collide with a user item.
+    assert_eq!(
+        global.item_meta.name.name.last(),
+        Some(&PathElem::Ident(
+            "<shared_const>".to_string(),
+            Disambiguator::new(0)
+        ))
+    );
+    // Both functions read it.
+    for name in ["test_crate::f", "test_crate::g"] {
+        let function = fun_by_name(&crate_data, name);
+        let body = llbc_body(function);
+        let mut reads_global = false;
+        body.body.dyn_visit_in_body(|rvalue: &Rvalue| {
+            if let Rvalue::Global(global_ref) = rvalue {
+                reads_global |= global_ref.id == global.def_id;
+            }
+        });
+        assert!(reads_global, "{name} doesn't read the shared constant");
+    }
+    Ok(())
+}
+
+#[test]
+fn name_prefix_and_suffix() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        struct Counter(u32);
+        impl Iterator for Counter {
+            type Item = u32;
+            fn next(&mut self) -> Option<u32> {
+                None
+            }
+        }
+        "#,
+    )?;
+    let next = crate_data
+        .fun_decls
+        .iter()
+        .find(|decl| decl.item_meta.is_local)
+        .unwrap();
+    // `test_crate::{impl Iterator for Counter}::next`: the impl element is skipped.
+    let name = &next.item_meta.name;
+    assert!(name.ends_with(&["next"]));
+    assert!(name.ends_with(&["test_crate", "next"]));
+    assert!(!name.ends_with(&["Iterator", "next"]));
+    assert!(name.starts_with(&["test_crate"]));
+    assert!(name.starts_with(&["test_crate", "next"]));
+    assert!(!name.starts_with(&["core"]));
+    assert!(name.ends_with(&[]));
+    Ok(())
+}
+
+#[test]
+fn static_referencing_static() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        static A: u32 = 1;
+        static B: u32 = A + 1;
+        "#,
+    )?;
+    let global = |name: &str| {
+        crate_data
+            .global_decls
+            .iter()
+            .find(|decl| repr_name(&crate_data, &decl.item_meta.name) == name)
+            .unwrap()
+    };
+    let a = global("test_crate::A");
+    let b = global("test_crate::B");
+
+    // The initializer of `B` reads `A`.
+    let init = crate_data.fun_decls[b.init].body.as_ref().unwrap();
+    let mut mentions_a = false;
+    init.as_structured()
+        .unwrap()
+        .body
+        .dyn_visit_in_body(|global_ref: &GlobalDeclRef| mentions_a |= global_ref.id == a.def_id);
+    assert!(mentions_a);
+
+    // `A` is declared before `B`.
+    let decl_groups = crate_data.ordered_decls.as_ref().unwrap();
+    let position = |id: GlobalDeclId| {
+        decl_groups
+            .iter()
+            .position(|group| group.get_ids().contains(&AnyTransId::Global(id)))
+            .unwrap()
+    };
+    assert!(position(a.def_id) < position(b.def_id));
+    Ok(())
+}
+
+#[test]
+fn remove_phantom_data_locals() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        use std::marker::PhantomData;
+        fn g<T>(marker: PhantomData<T>) -> PhantomData<T> {
+            let _unused = PhantomData::<u32>;
+            marker
+        }
+        "#,
+    )?;
+    let is_phantom_data = |ty: &Ty| match ty.kind() {
+        TyKind::Adt(TypeId::Adt(id), _) => {
+            repr_name(&crate_data, crate_data.item_name(*id).unwrap())
+                == "core::marker::PhantomData"
+        }
+        _ => false,
+    };
+    // The argument and the return value are kept, but `_unused` is removed.
+    let body = crate_data.fun_decls[0].body.as_ref().unwrap();
+    let locals = &body.as_structured().unwrap().locals;
+    assert_eq!(locals.vars.elem_count(), 2);
+    assert!(locals.vars.iter().all(|var| is_phantom_data(&var.ty)));
+    Ok(())
+}
+
+#[test]
+fn foreign_items() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        extern "C" {
+            fn foo(x: i32) -> i32;
+            static BAR: u32;
+        }
+        fn baz() -> i32 {
+            unsafe { foo(BAR as i32) }
+        }
+        "#,
+    )?;
+    let foo = fun_by_name(&crate_data, "test_crate::foo");
+    assert!(foo.body.is_err());
+    assert!(foo.item_meta.is_foreign);
+    assert_eq!(foo.item_meta.foreign_abi.as_deref(), Some("C"));
+    assert_eq!(foo.signature.inputs.len(), 1);
+
+    let bar = crate_data
+        .global_decls
+        .iter()
+        .find(|g| repr_name(&crate_data, &g.item_meta.name) == "test_crate::BAR")
+        .unwrap();
+    assert!(bar.item_meta.is_foreign);
+    assert_eq!(bar.item_meta.foreign_abi, None);
+
+    let baz = fun_by_name(&crate_data, "test_crate::baz");
+    assert!(!baz.item_meta.is_foreign);
+    Ok(())
+}
+
+#[test]
+fn name_tuple_fields() -> anyhow::Result<()> {
+    let code = r#"
+        struct Pair(u32, bool);
+        enum E { A(u8, u16), B { x: u32 } }
+        "#;
+    let field_names = |crate_data: &TranslatedCrate| {
+        crate_data
+            .type_decls
+            .iter()
+            .map(|decl| match &decl.kind {
+                TypeDeclKind::Struct(fields) => {
+                    vec![fields.iter().map(|f| f.name.clone()).collect_vec()]
+                }
+                TypeDeclKind::Enum(variants) => variants
+                    .iter()
+                    .map(|v| v.fields.iter().map(|f| f.name.clone()).collect_vec())
+                    .collect_vec(),
+                _ => unreachable!(),
+            })
+            .collect_vec()
+    };
+    let name = |s: &str| Some(s.to_owned());
+
+    // By default, tuple fields are unnamed.
+    let crate_data = translate(code)?;
+    assert_eq!(
+        field_names(&crate_data),
+        vec![
+            vec![vec![None, None]],
+            vec![vec![None, None], vec![name("x")]]
+        ]
+    );
+
+    let crate_data = util::translate_rust_text_with_args(code, &["--name-tuple-fields"])?;
+    assert_eq!(
+        field_names(&crate_data),
+        vec![
+            vec![vec![name("0"), name("1")]],
+            vec![vec![name("0"), name("1")], vec![name("x")]]
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn remove_dead_set_discriminants() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        #![feature(custom_mir, core_intrinsics)]
+        use std::intrinsics::mir::*;
+
+        #[custom_mir(dialect = "runtime", phase = "optimized")]
+        fn f(x: Option<u32>) -> Option<u32> {
+            mir! {
+                {
+                    RET = x;
+                    SetDiscriminant(RET, 0);
+                    SetDiscriminant(RET, 1);
+                    Return()
+                }
+            }
+        }
+        "#,
+    )?;
+    let body = crate_data.fun_decls[0].body.as_ref().unwrap();
+    let mut set_discriminants = vec![];
+    body.as_structured()
+        .unwrap()
+        .body
+        .dyn_visit_in_body(|st: &Statement| {
+            if let RawStatement::SetDiscriminant(_, variant_id) = &st.content {
+                set_discriminants.push(*variant_id);
+            }
+        });
+    // Only the last write remains.
+    assert_eq!(set_discriminants, vec![VariantId::new(1)]);
+    Ok(())
+}
+
+#[test]
+fn primitive_methods() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn f(x: u32) -> u32 {
+            x.count_ones()
+        }
+        "#,
+    )?;
+    let body = crate_data.fun_decls[0].body.as_ref().unwrap();
+    let mut calls = vec![];
+    body.as_structured()
+        .unwrap()
+        .body
+        .dyn_visit_in_body(|fn_ptr: &FnPtr| calls.push(fn_ptr.clone()));
+    let [fn_ptr] = calls.as_slice() else {
+        panic!("expected a single call, found {calls:?}")
+    };
+    assert_eq!(
+        fn_ptr.func,
+        FunIdOrTraitMethodRef::Fun(FunId::Builtin(BuiltinFunId::PrimitiveMethod(
+            PrimitiveMethod::CountOnes
+        )))
+    );
+    assert_eq!(
+        fn_ptr.generics.types.iter().collect_vec(),
+        vec![&TyKind::Literal(LiteralTy::Integer(IntegerTy::U32)).into_ty()]
+    );
+    // We don't translate the method of the standard library.
+    assert_eq!(crate_data.fun_decls.iter().count(), 1);
+    Ok(())
+}
+
+#[test]
+fn version_metadata() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_to_crate_data("fn f() {}", &[])?;
+    assert_eq!(crate_data.charon_version, env!("CARGO_PKG_VERSION"));
+    assert!(!crate_data.charon_commit.is_empty());
+    assert!(!crate_data.rustc_version.is_empty());
+    Ok(())
+}
+
+#[test]
+fn let_chains() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        #![feature(let_chains)]
+        fn let_chain(opt: Option<u32>, res: Result<u32, ()>) -> u32 {
+            if let Some(x) = opt && let Ok(y) = res {
+                x + y
+            } else {
+                0
+            }
+        }
+        "#,
+        &["--reconstruct-let-chains"],
+    )?;
+    let function = &crate_data.fun_decls[0];
+    let body = llbc_body(function);
+    let mut chains = vec![];
+    body.body.dyn_visit_in_body(|st: &Statement| {
+        if let RawStatement::IfLet(conditions, then_block) = &st.content {
+            chains.push((conditions.clone(), then_block.clone()));
+        }
+    });
+    let [(conditions, then_block)] = chains.as_slice() else {
+        panic!("expected a single `if let` chain")
+    };
+    let binding_names = |block: &Block| {
+        block
+            .statements
+            .iter()
+            .filter_map(|st| match &st.content {
+                RawStatement::Assign(dest, _) => body.locals[dest.var_id()].name.as_deref(),
+                _ => None,
+            })
+            .collect_vec()
+    };
+    // We match `opt` against `Some`, then `res` against `Ok`.
+    let [first, second] = conditions.as_slice() else {
+        panic!("expected two conditions")
+    };
+    assert_eq!(first.scrutinee.as_local(), Some(VarId::new(1)));
+    assert_eq!(first.variant, VariantId::new(1));
+    assert!(binding_names(&first.bindings).contains(&"x"));
+    assert_eq!(second.scrutinee.as_local(), Some(VarId::new(2)));
+    assert_eq!(second.variant, VariantId::new(0));
+    assert!(binding_names(&second.bindings).contains(&"y"));
+    // The `then` block doesn't match anymore.
+    let mut has_switch = false;
+    then_block.dyn_visit_in_body(|sw: &Switch| has_switch |= sw.is_match());
+    assert!(!has_switch);
+    Ok(())
+}
+
+#[test]
+fn copy_and_zst_types() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        struct Empty;
+        struct Wrapper(Empty, [u8; 0]);
+        #[derive(Clone, Copy)]
+        struct Point<T>(T, T);
+        fn f(_: u32, _: (), _: String, _: Wrapper, _: [String; 0], _: Point<u8>, _: Point<String>) {}
+        "#,
+    )?;
+    let copy_adts = crate_data.copy_adts();
+    let f = fun_by_name(&crate_data, "test_crate::f");
+    let [u32_ty, unit_ty, string_ty, wrapper_ty, empty_array_ty, copy_point_ty, string_point_ty] =
+        f.signature.inputs.as_slice()
+    else {
+        panic!()
+    };
+    assert!(u32_ty.is_copy(&copy_adts));
+    assert!(!crate_data.ty_is_zst(u32_ty));
+    assert!(unit_ty.is_copy(&copy_adts));
+    assert!(crate_data.ty_is_zst(unit_ty));
+    assert!(!string_ty.is_copy(&copy_adts));
+    assert!(!crate_data.ty_is_zst(string_ty));
+    assert!(!wrapper_ty.is_copy(&copy_adts));
+    assert!(crate_data.ty_is_zst(wrapper_ty));
+    assert!(!empty_array_ty.is_copy(&copy_adts));
+    assert!(crate_data.ty_is_zst(empty_array_ty));
+    assert!(copy_point_ty.is_copy(&copy_adts));
+    assert!(!string_point_ty.is_copy(&copy_adts));
+    Ok(())
+}
+
+#[test]
+fn deeply_nested_code() -> anyhow::Result<()> {
+    const DEPTH: usize = 200;
+    let tuple_ty = format!("{}u8{}", "(".repeat(DEPTH), ",)".repeat(DEPTH));
+    let mut expr = "0".to_string();
+    for i in 0..DEPTH {
+        expr = format!("if x == {i} {{ {i} }} else {{ {expr} }}");
+    }
+    let crate_data = translate(format!(
+        r#"
+        fn nested_ty(x: {tuple_ty}) -> {tuple_ty} {{ x }}
+        fn nested_expr(x: u32) -> u32 {{ {expr} }}
+        "#
+    ))?;
+    assert_eq!(crate_data.fun_decls.len(), 2);
+    assert!(crate_data.fun_decls.iter().all(|f| f.body.is_ok()));
+    Ok(())
+}
+
+#[test]
+fn function_callees() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn foo() {}
+        fn bar() {}
+        fn baz() {
+            foo();
+            bar();
+            foo();
+        }
+        "#,
+    )?;
+    let fun_id = |name: &str| fun_by_name(&crate_data, name).def_id;
+    let baz = &crate_data.fun_decls[fun_id("test_crate::baz")];
+    assert_eq!(
+        baz.calls,
+        [fun_id("test_crate::foo"), fun_id("test_crate::bar")]
+            .into_iter()
+            .collect()
+    );
+    assert!(crate_data.fun_decls[fun_id("test_crate::foo")]
+        .calls
+        .is_empty());
+    Ok(())
+}
+
+#[test]
+fn opaque_module() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        #![feature(register_tool)]
+        #![register_tool(charon)]
+        #[charon::opaque]
+        mod helpers {
+            pub fn foo() -> u32 { 0 }
+            pub mod inner {
+                pub fn bar() -> u32 { 1 }
+            }
+        }
+        fn main() -> u32 {
+            helpers::foo() + helpers::inner::bar()
+        }
+        "#,
+    )?;
+    let body_is_opaque = |name: &str| fun_by_name(&crate_data, name).body.is_err();
+    assert!(body_is_opaque("test_crate::helpers::foo"));
+    assert!(body_is_opaque("test_crate::helpers::inner::bar"));
+    assert!(!body_is_opaque("test_crate::main"));
+    Ok(())
+}
+
+#[test]
+fn opaque_module_attribute_errors_reported_once() -> anyhow::Result<()> {
+    let stderr = util::translate_rust_text_stderr(
+        r#"
+        #![feature(register_tool)]
+        #![register_tool(charon)]
+        #[charon::opaque]
+        #[charon::rename("")]
+        mod helpers {
+            pub fn foo() -> u32 { 0 }
+            pub fn bar() -> u32 { 1 }
+        }
+        fn main() -> u32 {
+            helpers::foo() + helpers::bar()
+        }
+        "#,
+        &[],
+    )?;
+    let errors = stderr.matches("Error parsing attribute").count();
+    assert_eq!(errors, 1, "{stderr}");
+    Ok(())
+}
+
+#[test]
+fn keep_discriminant_reads() -> anyhow::Result<()> {
+    let code = r#"
+        enum E { A, B(u32), C }
+        fn f(e: E) -> u32 {
+            match e {
+                E::A => 0,
+                E::B(x) => x,
+                E::C => 2,
+            }
+        }
+    "#;
+    let discriminant_reads = |crate_data: &TranslatedCrate| {
+        let mut reads = vec![];
+        for f in crate_data.fun_decls.iter() {
+            f.body.dyn_visit_in_body(|rvalue: &Rvalue| {
+                if let Rvalue::Discriminant(_, adt_id) = rvalue {
+                    reads.push(*adt_id)
+                }
+            });
+        }
+        reads
+    };
+    let crate_data = translate(code)?;
+    assert!(discriminant_reads(&crate_data).is_empty());
+    let crate_data = util::translate_rust_text_with_args(code, &["--keep-discriminant-reads"])?;
+    let enum_id = type_by_name(&crate_data, "test_crate::E").def_id;
+    assert_eq!(discriminant_reads(&crate_data), vec![enum_id]);
+    Ok(())
+}
+
+#[test]
+fn nested_closures() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn adder<T: Copy + std::ops::Add<Output = T>>(x: T, y: T) -> T {
+            let f = |a: T| move |b: T| a + b;
+            f(x)(y)
+        }
+        "#,
+    )?;
+    let find = |name: &str| fun_by_name(&crate_data, name);
+    let adder = find("test_crate::adder");
+    let outer = find("test_crate::adder::closure");
+    let inner = find("test_crate::adder::closure::closure");
+    assert!(!adder.signature.is_closure);
+    // Both closures get their type parameters and trait clauses from `adder`.
+    for closure in [outer, inner] {
+        assert!(closure.signature.is_closure);
+        assert_eq!(
+            closure.signature.generics.types,
+            adder.signature.generics.types
+        );
+        assert_eq!(
+            closure.signature.generics.trait_clauses.elem_count(),
+            adder.signature.generics.trait_clauses.elem_count()
+        );
+    }
+    // The outer closure builds the inner one by passing its own type parameters.
+    let mut closure_generics = vec![];
+    outer.body.dyn_visit_in_body(|aggregate: &AggregateKind| {
+        if let AggregateKind::Closure(fun_id, generics) = aggregate {
+            closure_generics.push((*fun_id, generics.clone()));
+        }
+    });
+    let [(fun_id, generics)] = closure_generics.as_slice() else {
+        panic!("{closure_generics:?}")
+    };
+    assert_eq!(*fun_id, inner.def_id);
+    assert_eq!(
+        generics.types.elem_count(),
+        outer.signature.generics.types.elem_count()
+    );
+    Ok(())
+}
+
+#[test]
+fn const_trait_bounds() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        #![allow(incomplete_features)]
+        #![feature(const_trait_impl, effects)]
+        #[const_trait]
+        trait Double {
+            fn double(self) -> Self;
+        }
+        impl const Double for u32 {
+            fn double(self) -> u32 {
+                self * 2
+            }
+        }
+        const fn quadruple<T: ~const Double>(x: T) -> T {
+            x.double().double()
+        }
+        fn plain<T: Double>(x: T) -> T {
+            x.double()
+        }
+        "#,
+    )?;
+    let clauses = |name: &str| {
+        let f = fun_by_name(&crate_data, name);
+        f.signature
+            .generics
+            .trait_clauses
+            .iter()
+            .map(|clause| {
+                let trait_id = clause.trait_.skip_binder.trait_id;
+                let trait_name = repr_name(
+                    &crate_data,
+                    &crate_data.trait_decls[trait_id].item_meta.name,
+                );
+                (trait_name, clause.is_const)
+            })
+            .filter(|(trait_name, _)| trait_name == "test_crate::Double")
+            .collect_vec()
+    };
+    assert_eq!(
+        clauses("test_crate::quadruple"),
+        [("test_crate::Double".to_owned(), true)]
+    );
+    assert_eq!(
+        clauses("test_crate::plain"),
+        [("test_crate::Double".to_owned(), false)]
+    );
+    Ok(())
+}
+
+#[test]
+fn impls_for_trait() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        trait Foo {}
+        impl Foo for u32 {}
+        impl<T> Foo for Option<T> {}
+        trait Bar {}
+        impl<T> Bar for T {}
+        "#,
+    )?;
+    let trait_id = |name: &str| {
+        crate_data
+            .trait_decls
+            .iter()
+            .find(|t| repr_name(&crate_data, &t.item_meta.name) == name)
+            .unwrap()
+            .def_id
+    };
+    let foo_impls = crate_data.impls_for_trait(trait_id("test_crate::Foo"));
+    assert_eq!(foo_impls.len(), 2);
+    let [bar_impl] = crate_data.impls_for_trait(trait_id("test_crate::Bar"))[..] else {
+        panic!()
+    };
+    let foo_impls = foo_impls
+        .iter()
+        .map(|id| &crate_data.trait_impls[*id])
+        .collect_vec();
+    let [impl_u32, impl_option] = foo_impls[..] else {
+        panic!()
+    };
+    assert!(!impl_u32.may_overlap(impl_option));
+    assert!(impl_option.may_overlap(impl_option));
+    assert!(!impl_u32.may_overlap(&crate_data.trait_impls[bar_impl]));
+    Ok(())
+}
+
+#[test]
+fn impls_may_overlap_fn_ptrs() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        #![allow(incomplete_features)]
+        #![feature(specialization)]
+        trait Foo {}
+        impl<T> Foo for fn(T) {}
+        impl Foo for fn(u32) {}
+        impl Foo for fn(u32, u32) {}
+        "#,
+    )?;
+    // Find the impls by their self type.
+    let impl_for = |arity: usize, generic: bool| {
+        crate_data
+            .trait_impls
+            .iter()
+            .find(|timpl| {
+                let TyKind::Arrow(sig) = timpl.impl_trait.generics.types[0].kind() else {
+                    panic!()
+                };
+                sig.skip_binder.0.len() == arity && timpl.generics.types.is_empty() != generic
+            })
+            .unwrap()
+    };
+    let (generic, unary, binary) = (impl_for(1, true), impl_for(1, false), impl_for(2, false));
+    assert!(generic.may_overlap(unary));
+    assert!(unary.may_overlap(generic));
+    assert!(!generic.may_overlap(binary));
+    assert!(!unary.may_overlap(binary));
+    Ok(())
+}
+
+#[test]
+fn matches_with_guard() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        fn is_positive(x: Option<i32>) -> bool {
+            matches!(x, Some(n) if n > 0)
+        }
+        "#,
+        &["--lower-discriminant-checks"],
+    )?;
+    let function = fun_by_name(&crate_data, "test_crate::is_positive");
+    let body = llbc_body(function);
+    // The guard is still evaluated...
+    let mut has_guard = false;
+    body.body.dyn_visit_in_body(|rvalue: &Rvalue| {
+        has_guard |= matches!(rvalue, Rvalue::BinaryOp(BinOp::Gt, ..));
+    });
+    assert!(has_guard);
+    // ...after checking the variant, so the match must be kept.
+    let mut has_match = false;
+    body.body
+        .dyn_visit_in_body(|switch: &Switch| has_match |= switch.is_match());
+    assert!(has_match);
+    Ok(())
+}
+
+#[test]
+fn print_llbc() -> anyhow::Result<()> {
+    let stdout = util::translate_rust_text_stdout(
+        r#"
+        struct S;
+        trait Tr {
+            fn method(&self);
+        }
+        impl Tr for S {
+            fn method(&self) {}
+        }
+        fn f(s: S) {
+            s.method()
+        }
+        "#,
+        &["--print-llbc"],
+    )?;
+    assert!(stdout.contains("# Final LLBC before serialization"));
+    assert!(stdout.contains("struct test_crate::S"));
+    assert!(stdout.contains("trait test_crate::Tr"));
+    assert!(stdout.contains("impl test_crate::{impl test_crate::Tr for test_crate::S}"));
+    assert!(stdout.contains("fn test_crate::f(@1: test_crate::S)"));
+    Ok(())
+}
+
+#[test]
+fn generic_adt_constant() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        const X: Option<u32> = Some(1);
+        "#,
+    )?;
+    let [global] = crate_data.global_decls.iter().collect_vec()[..] else {
+        panic!()
+    };
+    let init = &crate_data.fun_decls[global.init];
+    let body = llbc_body(init);
+    let mut aggregates = vec![];
+    body.body.dyn_visit_in_body(|rvalue: &Rvalue| {
+        if let Rvalue::Aggregate(AggregateKind::Adt(TypeId::Adt(id), Some(variant), None, _), ops) =
+            rvalue
+        {
+            aggregates.push((*id, *variant, ops.clone()));
+        }
+    });
+    let [(adt_id, variant_id, ops)] = &aggregates[..] else {
+        panic!("{aggregates:?}")
+    };
+    let option = &crate_data.type_decls[*adt_id];
+    assert_eq!(
+        repr_name(&crate_data, &option.item_meta.name),
+        "core::option::Option"
+    );
+    assert_eq!(option.kind.as_enum().unwrap()[*variant_id].name, "Some");
+    let [Operand::Const(cst)] = &ops[..] else {
+        panic!("{ops:?}")
+    };
+    assert_eq!(
+        cst.value,
+        RawConstantExpr::Literal(Literal::Scalar(ScalarValue::U32(1)))
+    );
+    Ok(())
+}
+
+#[test]
+fn closure_names_are_stable() -> anyhow::Result<()> {
+    let code = r#"
+        fn f(x: u32) -> u32 {
+            let second_in_call_order = |y: u32| y + 1;
+            let first_in_call_order = |y: u32| y * 2;
+            second_in_call_order(first_in_call_order(x))
+        }
+    "#;
+    let closure_names = |crate_data: &TranslatedCrate| {
+        crate_data
+            .fun_decls
+            .iter()
+            .filter(|f| f.signature.is_closure)
+            .map(|f| (f.item_meta.name.clone(), f.item_meta.span.span.beg.line))
+            .sorted_by_key(|(_, line)| *line)
+            .collect_vec()
+    };
+    let first = closure_names(&translate(code)?);
+    let second = closure_names(&translate(code)?);
+    assert_eq!(first, second);
+    // The closures are numbered in source order.
+    let disambiguators = first
+        .iter()
+        .map(|(name, _)| match name.name.last() {
+            Some(PathElem::Ident(ident, disambiguator)) if ident == "closure" => *disambiguator,
+            _ => panic!("{name:?}"),
+        })
+        .collect_vec();
+    assert_eq!(
+        disambiguators,
+        [Disambiguator::new(0), Disambiguator::new(1)]
+    );
+    Ok(())
+}
+
+#[test]
+fn black_box() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn f(x: u32) -> u32 {
+            core::hint::black_box(x)
+        }
+        "#,
+    )?;
+    let f = fun_by_name(&crate_data, "test_crate::f");
+    let body = &llbc_body(f).body;
+    let mut black_box_args = vec![];
+    body.dyn_visit_in_body(|rvalue: &Rvalue| {
+        if let Rvalue::BlackBox(op) = rvalue {
+            black_box_args.push(op.clone());
+        }
+    });
+    let [Operand::Move(arg) | Operand::Copy(arg)] = black_box_args.as_slice() else {
+        panic!("expected a single `black_box`, found {black_box_args:?}")
+    };
+    assert_eq!(
+        arg.ty().kind(),
+        &TyKind::Literal(LiteralTy::Integer(IntegerTy::U32))
+    );
+    let mut has_call = false;
+    body.dyn_visit_in_body(|_: &Call| has_call = true);
+    assert!(!has_call);
+    Ok(())
+}
+
+#[test]
+fn name_span() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn foo(x: u32) -> u32 {
+            x + 1
+        }
+        "#,
+    )?;
+    let item_meta = &fun_by_name(&crate_data, "test_crate::foo").item_meta;
+    let span = item_meta.span.span;
+    let name_span = item_meta.name_span.span;
+    let pos = |loc: Loc| (loc.line, loc.col);
+    // The name span is strictly included in the span of the whole item.
+    assert!(pos(span.beg) < pos(name_span.beg));
+    assert!(pos(name_span.end) < pos(span.end));
+    // It covers exactly the identifier `foo`.
+    assert_eq!(name_span.beg.line, name_span.end.line);
+    assert_eq!(name_span.end.col - name_span.beg.col, "foo".len());
+    Ok(())
+}
+
+#[test]
+fn method_where_clause_on_assoc_type() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        trait Container {
+            type Item;
+            fn f(&self)
+            where
+                Self::Item: Clone;
+        }
+        "#,
+    )?;
+    let method = fun_by_name(&crate_data, "test_crate::Container::f");
+    let clone_clauses = method
+        .signature
+        .generics
+        .trait_clauses
+        .iter()
+        .filter(|clause| {
+            let trait_id = clause.trait_.skip_binder.trait_id;
+            let trait_name = &crate_data.trait_decls[trait_id].item_meta.name;
+            repr_name(&crate_data, trait_name) == "core::clone::Clone"
+        })
+        .collect_vec();
+    let [clause] = clone_clauses.as_slice() else {
+        panic!("expected a single `Clone` clause, found {clone_clauses:?}")
+    };
+    // The clause is about `Self::Item`.
+    let self_ty = &clause.trait_.skip_binder.generics.types[TypeVarId::ZERO];
+    let TyKind::TraitType(_, name) = self_ty.kind() else {
+        panic!("{self_ty:?}")
+    };
+    assert_eq!(name.0, "Item");
+    Ok(())
+}
+
+#[test]
+fn body_local_classification() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn f(x: u32, y: bool) -> u32 {
+            let z = if y { x } else { x + 1 };
+            z
+        }
+        "#,
+    )?;
+    let body = llbc_body(fun_by_name(&crate_data, "test_crate::f"));
+    assert_eq!(body.return_local(), VarId::new(0));
+    assert!(!body.is_arg(body.return_local()));
+    let args = body.arg_locals().collect_vec();
+    assert_eq!(args, [VarId::new(1), VarId::new(2)]);
+    let arg_names = args
+        .iter()
+        .map(|&v| body.locals[v].name.as_deref())
+        .collect_vec();
+    assert_eq!(arg_names, [Some("x"), Some("y")]);
+    assert!(args.iter().all(|&v| body.is_arg(v)));
+    // The remaining locals are temporaries.
+    let temporaries = body.locals.non_argument_locals().collect_vec();
+    assert!(!temporaries.is_empty());
+    assert!(temporaries.iter().all(|(v, _)| !body.is_arg(*v)));
+    Ok(())
+}
+
+#[test]
+fn user_assert_message() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn f(x: u32) {
+            assert!(x > 0, "bad");
+        }
+        fn g(x: u32) {
+            assert!(x > 0);
+        }
+        fn new_const(x: &mut u32) -> u32 {
+            *x += 1;
+            *x
+        }
+        fn h(b: bool, x: &mut u32) {
+            if b {
+                let _ = new_const(x);
+                panic!("bad");
+            }
+        }
+        "#,
+    )?;
+    let assert_kinds = |name: &str| {
+        let function = fun_by_name(&crate_data, name);
+        let body = &llbc_body(function).body;
+        let mut kinds = vec![];
+        body.dyn_visit_in_body(|assert: &Assert| kinds.push(assert.kind.clone()));
+        kinds
+    };
+    assert_eq!(
+        assert_kinds("test_crate::f"),
+        [AssertKind::UserAssert {
+            message: Some("bad".to_owned())
+        }]
+    );
+    assert_eq!(
+        assert_kinds("test_crate::g"),
+        [AssertKind::UserAssert {
+            message: Some("assertion failed: x > 0".to_owned())
+        }]
+    );
+    // A user function named `new_const` doesn't build the panic message: its call is kept.
+    assert_eq!(assert_kinds("test_crate::h"), []);
+    let new_const_id = fun_by_name(&crate_data, "test_crate::new_const").def_id;
+    let mut calls_new_const = false;
+    llbc_body(fun_by_name(&crate_data, "test_crate::h"))
+        .body
+        .dyn_visit_in_body(|fn_ptr: &FnPtr| {
+            calls_new_const |= matches!(
+                fn_ptr.func,
+                FunIdOrTraitMethodRef::Fun(FunId::Regular(id)) if id == new_const_id
+            );
+        });
+    assert!(calls_new_const);
+    Ok(())
+}
+
+#[test]
+fn binding_with_subpattern() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn f() -> (u32, u32, (u32, u32)) {
+            let p @ (a, b) = (1, 2);
+            (a, b, p)
+        }
+        "#,
+    )?;
+    let body = llbc_body(fun_by_name(&crate_data, "test_crate::f"));
+    let mut assigned = vec![];
+    body.body.dyn_visit_in_body(|st: &Statement| {
+        if let RawStatement::Assign(dest, _) = &st.content {
+            if let Some(name) = dest.as_local().and_then(|v| body.locals[v].name.clone()) {
+                assigned.push(name);
+            }
+        }
+    });
+    for name in ["p", "a", "b"] {
+        assert!(assigned.iter().any(|n| n == name), "`{name}` is not bound");
+    }
+    Ok(())
+}
+
+#[test]
+fn preset_raw() -> anyhow::Result<()> {
+    use charon_lib::ullbc_ast::RawStatement;
+    let code = r#"
+        fn g(x: u32) -> u32 {
+            x
+        }
+        fn f(x: u32) -> u32 {
+            let _ = x;
+            let y = g(x);
+            g(core::hint::black_box(y))
+        }
+    "#;
+    let translate_f = |args: &[&str]| -> anyhow::Result<_> {
+        let crate_data = util::translate_rust_text_with_args(code, args)?;
+        let f = fun_by_name(&crate_data, "test_crate::f");
+        Ok(f.body.as_ref().unwrap().as_unstructured().unwrap().clone())
+    };
+    let default_body = translate_f(&["--ullbc"])?;
+    let raw_body = translate_f(&["--ullbc", "--preset", "raw"])?;
+    // The goto chains created by the calls are not merged.
+    assert!(raw_body.body.elem_count() > default_body.body.elem_count());
+    // The fake read of `let _ = x` is kept.
+    assert!(raw_body
+        .body
+        .iter()
+        .flat_map(|block| &block.statements)
+        .any(|st| matches!(st.content, RawStatement::FakeRead(_))));
+    // The cleanup passes don't run: `black_box` stays a regular call.
+    let has_black_box = |body: &charon_lib::ullbc_ast::ExprBody| {
+        body.body
+            .iter()
+            .flat_map(|block| &block.statements)
+            .any(|st| matches!(st.content, RawStatement::Assign(_, Rvalue::BlackBox(_))))
+    };
+    assert!(has_black_box(&default_body));
+    assert!(!has_black_box(&raw_body));
+
+    // The exported options are the ones the preset expands to.
+    let crate_data = util::translate_rust_text_with_args(code, &["--preset", "raw"])?;
+    assert!(crate_data.options.no_merge_goto_chains);
+    assert!(crate_data.options.keep_discriminant_reads);
+    assert!(crate_data.options.no_cleanup);
+    Ok(())
+}
+
+#[test]
+fn preset_keeps_explicit_options() {
+    use charon_lib::options::CliOpts;
+    use clap::Parser;
+    let mut options = CliOpts::parse_from([
+        "charon",
+        "--preset",
+        "raw",
+        "--reconstruct-let-else",
+        "--hide-marker-traits",
+    ]);
+    options.apply_preset();
+    assert!(options.no_cleanup);
+    assert!(options.reconstruct_let_else);
+    assert!(options.hide_marker_traits);
+}
+
+#[test]
+fn variant_ctor_as_fn() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn f(xs: &[u32]) -> Vec<Option<&u32>> {
+            xs.iter().map(Some).collect()
+        }
+        "#,
+    )?;
+    let f = fun_by_name(&crate_data, "test_crate::f");
+    let body = &llbc_body(f).body;
+    // `Some` is passed to `map` as a function value.
+    let mut fn_values = vec![];
+    body.dyn_visit_in_body(|op: &Operand| {
+        if let Operand::Const(ConstantExpr {
+            value: RawConstantExpr::FnPtr(fn_ptr),
+            ..
+        }) = op
+        {
+            fn_values.push(fn_ptr.clone());
+        }
+    });
+    let [fn_ptr] = fn_values.as_slice() else {
+        panic!("expected a single function value, found {fn_values:?}")
+    };
+    let FunIdOrTraitMethodRef::Fun(FunId::Regular(ctor_id)) = fn_ptr.func else {
+        panic!("{fn_ptr:?}")
+    };
+    let ctor = &crate_data.fun_decls[ctor_id];
+    assert_eq!(
+        repr_name(&crate_data, &ctor.item_meta.name),
+        "core::option::Option::Some"
+    );
+    // The constructor has a body even though `Option` is foreign, which builds the variant.
+    let ctor_body = llbc_body(ctor);
+    let mut variants = vec![];
+    ctor_body.body.dyn_visit_in_body(|kind: &AggregateKind| {
+        if let AggregateKind::Adt(TypeId::Adt(type_id), variant_id, ..) = kind {
+            variants.push((*type_id, *variant_id));
+        }
+    });
+    let [(type_id, Some(variant_id))] = variants.as_slice() else {
+        panic!("{variants:?}")
+    };
+    let variants = crate_data.type_decls[*type_id].kind.as_enum().unwrap();
+    assert_eq!(variants[*variant_id].name, "Some");
+    Ok(())
+}
+
+#[test]
+fn generic_global() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        struct Wrapper<T>(T);
+        impl<T> Wrapper<T> {
+            const NONE: Option<T> = None;
+        }
+        fn f() -> Option<u32> {
+            Wrapper::<u32>::NONE
+        }
+        "#,
+    )?;
+    let global = crate_data
+        .global_decls
+        .iter()
+        .find(|decl| repr_name(&crate_data, &decl.item_meta.name).ends_with("::NONE"))
+        .unwrap();
+    // The global has the type parameter of the impl.
+    assert_eq!(global.generics.types.elem_count(), 1);
+    let f = fun_by_name(&crate_data, "test_crate::f");
+    let mut global_refs = vec![];
+    llbc_body(f)
+        .body
+        .dyn_visit_in_body(|global_ref: &GlobalDeclRef| global_refs.push(global_ref.clone()));
+    let [global_ref] = global_refs.as_slice() else {
+        panic!("expected a single global reference, found {global_refs:?}")
+    };
+    assert_eq!(global_ref.id, global.def_id);
+    // The reference instantiates the type parameter.
+    assert_eq!(
+        global_ref.generics.types.iter().collect_vec(),
+        vec![&TyKind::Literal(LiteralTy::Integer(IntegerTy::U32)).into_ty()]
+    );
+    Ok(())
+}
+
+#[test]
+fn keep_overflow_checks() -> anyhow::Result<()> {
+    let code = r#"
+        fn f(x: u32, y: u32) -> u32 {
+            x + y
+        }
+    "#;
+    let overflow_asserts = |args: &[&str]| -> anyhow::Result<usize> {
+        let crate_data = util::translate_rust_text_with_args(code, args)?;
+        let body = llbc_body(fun_by_name(&crate_data, "test_crate::f"));
+        let mut count = 0;
+        body.body.dyn_visit_in_body(|assert: &Assert| {
+            if assert.kind == AssertKind::Overflow {
+                count += 1;
+            }
+        });
+        Ok(count)
+    };
+    let overflow_checks = "--rustc-flag=-Coverflow-checks=on";
+    assert_eq!(overflow_asserts(&[overflow_checks])?, 0);
+    assert_eq!(
+        overflow_asserts(&[overflow_checks, "--keep-overflow-checks"])?,
+        1
+    );
+    Ok(())
+}
+
+#[test]
+fn inherent_method_self() -> anyhow::Result<()> {
+    let code = r#"
+        struct Foo {
+            x: u32,
+        }
+        impl Foo {
+            fn bar(&self) -> Self {
+                Self { x: self.x }
+            }
+        }
+    "#;
+    let crate_data = translate(code)?;
+    let foo_id = crate_data.type_decls[0].def_id;
+    let is_foo = |ty: &Ty| match ty.kind() {
+        TyKind::Adt(TypeId::Adt(id), args) => *id == foo_id && args.is_empty(),
+        _ => false,
+    };
+    let bar = &crate_data.fun_decls[0];
+    assert_eq!(
+        repr_name(&crate_data, &bar.item_meta.name),
+        "test_crate::<inherent impl>::bar"
+    );
+    // Inherent methods have no trait attached.
+    assert!(matches!(bar.kind, ItemKind::Regular));
+    // The receiver is `&Foo` and `Self` in the signature is `Foo`.
+    let [receiver] = bar.signature.inputs.as_slice() else {
+        panic!()
+    };
+    let TyKind::Ref(_, receiver_ty, RefKind::Shared) = receiver.kind() else {
+        panic!("unexpected receiver type {receiver:?}")
+    };
+    assert!(is_foo(receiver_ty));
+    assert!(is_foo(&bar.signature.output));
+    // `Self { .. }` in the body builds a `Foo`.
+    let mut built_adts = vec![];
+    llbc_body(bar)
+        .body
+        .dyn_visit_in_body(|kind: &AggregateKind| {
+            if let AggregateKind::Adt(id, ..) = kind {
+                built_adts.push(*id);
+            }
+        });
+    assert_eq!(built_adts, vec![TypeId::Adt(foo_id)]);
+    Ok(())
+}
+
+#[test]
+fn external_crates() -> anyhow::Result<()> {
+    let code = r#"
+        fn f() -> Vec<u32> {
+            Vec::new()
+        }
+    "#;
+    let crate_data = util::translate_rust_text_to_crate_data(code, &[])?;
+    assert!(crate_data.external_crates.contains(&"alloc".to_owned()));
+    assert!(!crate_data
+        .external_crates
+        .contains(&crate_data.translated.real_crate_name));
+    // `Vec::new` comes from `alloc` and its body isn't translated.
+    let vec_new = crate_data
+        .translated
+        .fun_decls
+        .iter()
+        .find(|decl| {
+            repr_name(&crate_data.translated, &decl.item_meta.name)
+                == "alloc::vec::<inherent impl>::new"
+        })
+        .unwrap();
+    assert!(crate_data
+        .external_opaque_items
+        .contains(&AnyTransId::Fun(vec_new.def_id)));
+    let f = &crate_data.translated.fun_decls[0];
+    assert!(!crate_data
+        .external_opaque_items
+        .contains(&AnyTransId::Fun(f.def_id)));
+    Ok(())
+}
+
+#[test]
+fn index_vs_index_mut() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        use std::ops::{Index, IndexMut};
+        struct W(Vec<u32>);
+        impl Index<usize> for W {
+            type Output = u32;
+            fn index(&self, i: usize) -> &u32 {
+                &self.0[i]
+            }
+        }
+        impl IndexMut<usize> for W {
+            fn index_mut(&mut self, i: usize) -> &mut u32 {
+                &mut self.0[i]
+            }
+        }
+        fn set(w: &mut W, i: usize) {
+            w[i] = 0;
+        }
+        fn get(w: &W, i: usize) -> u32 {
+            w[i]
+        }
+        "#,
+    )?;
+    // The names of the trait methods called by the given function.
+    let called_methods = |fun_name: &str| -> Vec<String> {
+        let fun = fun_by_name(&crate_data, fun_name);
+        let mut called = vec![];
+        llbc_body(fun).body.dyn_visit_in_body(|fn_ptr: &FnPtr| {
+            if let FunIdOrTraitMethodRef::Trait(_, method_name, _) = &fn_ptr.func {
+                called.push(method_name.0.clone());
+            }
+        });
+        called
+    };
+    // Indexing in a place that is written to goes through `IndexMut`, reading goes through
+    // `Index`.
+    assert_eq!(called_methods("test_crate::set"), vec!["index_mut"]);
+    assert_eq!(called_methods("test_crate::get"), vec!["index"]);
+    Ok(())
+}
+
+#[test]
+fn deterministic_clause_order() -> anyhow::Result<()> {
+    let code = r#"
+        trait Foo<T> {}
+        trait Bar {}
+        fn f<T, U>(_: T, _: U)
+        where
+            T: Clone + Foo<U> + Bar,
+            U: Foo<T> + Copy + Bar,
+        {
+        }
+        struct S<T: Bar + Clone>(T);
+        impl<T: Bar + Clone> Foo<T> for S<T> where T: Foo<u32> {}
+    "#;
+    let crate_data = translate(code)?;
+    // The clauses follow the order of the bounds in the source, after the implicit `Sized` bounds
+    // of the type parameters.
+    let clause_traits = |generics: &GenericParams| {
+        generics
+            .trait_clauses
+            .iter()
+            .map(|clause| trait_name(&crate_data, clause.trait_.skip_binder.trait_id))
+            .collect_vec()
+    };
+    let f = fun_by_name(&crate_data, "test_crate::f");
+    assert_eq!(
+        clause_traits(&f.signature.generics),
+        ["Sized", "Sized", "Clone", "Foo", "Bar", "Foo", "Copy", "Bar"]
+    );
+    let s = type_by_name(&crate_data, "test_crate::S");
+    assert_eq!(clause_traits(&s.generics), ["Sized", "Bar", "Clone"]);
+    let [timpl] = crate_data.trait_impls.iter().collect_vec()[..] else {
+        panic!()
+    };
+    assert_eq!(
+        clause_traits(&timpl.generics),
+        ["Sized", "Bar", "Clone", "Foo"]
+    );
+    Ok(())
+}
+
+#[test]
+fn from_macro() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        macro_rules! mk_struct {
+            ($name:ident) => {
+                struct $name;
+            };
+        }
+        mk_struct!(Foo);
+        struct Bar;
+        "#,
+    )?;
+    let ty = |name: &str| type_by_name(&crate_data, name);
+    let (foo, bar) = (ty("test_crate::Foo"), ty("test_crate::Bar"));
+    // The span points to the macro invocation.
+    let invocation = foo.item_meta.from_macro.unwrap();
+    assert_eq!(invocation.span.beg.line, 7);
+    assert!(bar.item_meta.from_macro.is_none());
+    Ok(())
+}
+
+#[test]
+fn char_switch() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn f(c: char) -> u32 {
+            match c {
+                'a' => 0,
+                'é' => 1,
+                _ => 2,
+            }
+        }
+        "#,
+    )?;
+    let body = crate_data.fun_decls[0].body.as_ref().unwrap();
+    let body = body.as_structured().unwrap();
+    let (int_ty, targets) = body
+        .body
+        .statements
+        .iter()
+        .find_map(|st| match &st.content {
+            RawStatement::Switch(Switch::SwitchInt(_, int_ty, targets, _)) => {
+                Some((*int_ty, targets))
+            }
+            _ => None,
+        })
+        .unwrap();
+    // We switch on the scalar value of the `char`.
+    assert_eq!(int_ty, IntegerTy::U32);
+    let values = targets
+        .iter()
+        .flat_map(|(values, _)| values.iter().copied())
+        .sorted_by_key(|v| v.to_bits())
+        .collect_vec();
+    assert_eq!(values, ['a' as u32, 'é' as u32].map(ScalarValue::U32));
+    Ok(())
+}
+
+#[test]
+fn predicate_origin() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        trait Foo: Clone {
+            type Assoc: Copy;
+        }
+        "#,
+    )?;
+    let foo = &crate_data.trait_decls[0];
+    let origin = |name: &str| {
+        foo.parent_clauses
+            .iter()
+            .find(|clause| trait_name(&crate_data, clause.trait_.skip_binder.trait_id) == name)
+            .unwrap()
+            .origin
+            .clone()
+    };
+    assert_eq!(origin("Clone"), PredicateOrigin::WhereClauseOnTrait);
+    assert_eq!(
+        origin("Copy"),
+        PredicateOrigin::TraitItem(TraitItemName("Assoc".to_owned()))
+    );
+    // The origin is part of the export.
+    let exported = |name: &str| -> anyhow::Result<serde_json::Value> {
+        Ok(serde_json::to_value(origin(name))?)
+    };
+    assert_eq!(exported("Clone")?, serde_json::json!("WhereClauseOnTrait"));
+    assert_eq!(
+        exported("Copy")?,
+        serde_json::json!({ "TraitItem": "Assoc" })
+    );
+    Ok(())
+}
+
+#[test]
+fn pin_is_transparent() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        use std::pin::Pin;
+        fn f(x: Pin<Box<u32>>) -> u32 {
+            *x
+        }
+        "#,
+    )?;
+    let f = fun_by_name(&crate_data, "test_crate::f");
+    let [input] = f.signature.inputs.as_slice() else {
+        panic!()
+    };
+    let TyKind::Adt(TypeId::Adt(pin_id), args) = input.kind() else {
+        panic!("unexpected type {input:?}")
+    };
+    let pin = &crate_data.type_decls[*pin_id];
+    assert_eq!(
+        repr_name(&crate_data, &pin.item_meta.name),
+        "core::pin::Pin"
+    );
+    // The pinned pointer is visible, both in the type arguments and in the declaration.
+    let [pointer] = args.types.iter().collect_vec()[..] else {
+        panic!()
+    };
+    assert!(pointer.is_box());
+    let TypeDeclKind::Struct(fields) = &pin.kind else {
+        panic!("unexpected kind {:?}", pin.kind)
+    };
+    let [field] = fields.iter().collect_vec()[..] else {
+        panic!()
+    };
+    assert!(matches!(field.ty.kind(), TyKind::TypeVar(_)));
+    Ok(())
+}
+
+#[test]
+fn uninhabited_arms() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        enum Empty {}
+        #[allow(unreachable_patterns)]
+        fn f(r: Result<u32, Empty>) -> u32 {
+            match r {
+                Ok(x) => x,
+                Err(_) => panic!(),
+            }
+        }
+        "#,
+    )?;
+    let empty_id = type_by_name(&crate_data, "test_crate::Empty").def_id;
+    assert!(crate_data.ty_is_uninhabited(
+        &TyKind::Adt(
+            TypeId::Adt(empty_id),
+            GenericArgs::empty(GenericsSource::item(empty_id))
+        )
+        .into_ty()
+    ));
+    let f = fun_by_name(&crate_data, "test_crate::f");
+    let mut panics = false;
+    llbc_body(f).body.dyn_visit_in_body(|st: &Statement| {
+        if let RawStatement::Abort(AbortKind::Panic { .. }) = &st.content {
+            panics = true;
+        }
+    });
+    // The `Err` arm can't be taken: its panic is replaced with undefined behavior.
+    assert!(!panics);
+    Ok(())
+}
+
+#[test]
+fn monomorphize() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        struct Wrap<T>(T);
+        fn id<T>(x: T) -> T {
+            x
+        }
+        fn main() {
+            let _ = id(0u32);
+            let _ = id(1u32);
+            let _ = id(Wrap(true));
+        }
+        "#,
+        &["--monomorphize"],
+    )?;
+    let id_u32 = fun_by_name(&crate_data, "test_crate::id::<u32>");
+    assert!(id_u32.signature.generics.is_empty());
+    fun_by_name(&crate_data, "test_crate::id::<test_crate::Wrap<bool>>");
+    type_by_name(&crate_data, "test_crate::Wrap::<bool>");
+    // The names of the instances are the ones recorded in `item_names`.
+    for fun in &crate_data.fun_decls {
+        assert_eq!(
+            crate_data.item_names.get(&AnyTransId::Fun(fun.def_id)),
+            Some(&fun.item_meta.name)
+        );
+    }
+    // The calls refer to the instances: both calls at `u32` share the same one.
+    let main = fun_by_name(&crate_data, "test_crate::main");
+    assert!(main.calls.contains(&id_u32.def_id));
+    assert!(!main
+        .calls
+        .contains(&fun_by_name(&crate_data, "test_crate::id").def_id));
+    assert_eq!(main.calls.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn monomorphize_distinct_names() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        struct Wrap_bool;
+        struct Wrap<T>(T);
+        fn f<T>(_: T) {}
+        fn g(_: u8) {}
+        fn h(_: u16) {}
+        fn main() {
+            f(g as fn(u8));
+            f(h as fn(u16));
+            let _ = Wrap(true);
+        }
+        "#,
+        &["--monomorphize"],
+    )?;
+    let names = crate_data
+        .fun_decls
+        .iter()
+        .map(|f| repr_name(&crate_data, &f.item_meta.name))
+        .collect_vec();
+    assert!(names.iter().any(|n| n == "test_crate::f::<fn(u8)>"));
+    assert!(names.iter().any(|n| n == "test_crate::f::<fn(u16)>"));
+    let type_names = crate_data
+        .type_decls
+        .iter()
+        .map(|t| repr_name(&crate_data, &t.item_meta.name))
+        .collect_vec();
+    assert!(type_names.iter().all_unique());
+    Ok(())
+}
+
+#[test]
+fn monomorphize_polymorphic_recursion() -> anyhow::Result<()> {
+    let stderr = util::translate_rust_text_stderr(
+        r#"
+        fn f<T>(n: u32) {
+            if n > 0 {
+                f::<(T, T)>(n - 1)
+            }
+        }
+        fn main() {
+            f::<u8>(3)
+        }
+        "#,
+        &["--monomorphize"],
+    )?;
+    assert!(stderr.contains("Reached the maximal depth of 20 nested instantiations"));
+    Ok(())
+}
+
+#[test]
+fn signature_hash() -> anyhow::Result<()> {
+    let hash = |code: &str| -> anyhow::Result<u64> {
+        let crate_data = translate(code)?;
+        let f = fun_by_name(&crate_data, "test_crate::f");
+        Ok(f.signature_hash(&crate_data))
+    };
+    let original = hash("fn f<T: Clone>(x: &T, y: u32) -> u32 { y }")?;
+    let body_changed = hash(
+        "fn f<T: Clone>(x: &T, y: u32) -> u32 {
+            let _ = x.clone();
+            y + 1
+        }",
+    )?;
+    let param_changed = hash("fn f<T: Clone>(x: &T, y: u64) -> u32 { 0 }")?;
+    assert_eq!(original, body_changed);
+    assert_ne!(original, param_changed);
+
+    // The hash doesn't depend on the order in which the items are registered.
+    let adt = hash("struct S; fn f(x: S) -> S { x }")?;
+    let adt_other_items = hash(
+        "struct T;
+        fn g(_: T) {}
+        struct S;
+        fn f(x: S) -> S { x }",
+    )?;
+    assert_eq!(adt, adt_other_items);
+
+    // The ABI is part of the signature.
+    let c_abi = hash(r#"extern "C" { fn f(x: u32) -> u32; }"#)?;
+    let rust_abi = hash(r#"extern "Rust" { fn f(x: u32) -> u32; }"#)?;
+    assert_ne!(c_abi, rust_abi);
+    Ok(())
+}
+
+#[test]
+fn custom_try_from_residual() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        #![feature(try_trait_v2)]
+        use std::ops::{ControlFlow, FromResidual, Try};
+        struct Checked(Option<u32>);
+        struct Failed;
+        impl FromResidual<Failed> for Checked {
+            fn from_residual(_: Failed) -> Self {
+                Checked(None)
+            }
+        }
+        impl Try for Checked {
+            type Output = u32;
+            type Residual = Failed;
+            fn from_output(x: u32) -> Self {
+                Checked(Some(x))
+            }
+            fn branch(self) -> ControlFlow<Failed, u32> {
+                match self.0 {
+                    Some(x) => ControlFlow::Continue(x),
+                    None => ControlFlow::Break(Failed),
+                }
+            }
+        }
+        fn incr(c: Checked) -> Checked {
+            let x = c?;
+            Checked(Some(x + 1))
+        }
+        "#,
+    )?;
+    let incr = fun_by_name(&crate_data, "test_crate::incr");
+    let calls_method = |st: &Statement, name: &str| match &st.content {
+        RawStatement::Call(Call {
+            func:
+                FnOperand::Regular(FnPtr {
+                    func: FunIdOrTraitMethodRef::Trait(_, method_name, _),
+                    ..
+                }),
+            ..
+        }) => method_name.0 == name,
+        _ => false,
+    };
+    // The `?` calls `Try::branch`, and the branch that returns early converts the residual with
+    // `FromResidual::from_residual` before returning.
+    let mut calls_branch = false;
+    let mut converts_then_returns = false;
+    llbc_body(incr).body.dyn_visit_in_body(|block: &Block| {
+        let statements = &block.statements;
+        calls_branch |= statements.iter().any(|st| calls_method(st, "branch"));
+        if let Some(i) = statements
+            .iter()
+            .position(|st| calls_method(st, "from_residual"))
+        {
+            converts_then_returns |= statements[i + 1..]
+                .iter()
+                .any(|st| matches!(st.content, RawStatement::Return));
+        }
+    });
+    assert!(calls_branch);
+    assert!(converts_then_returns);
+    Ok(())
+}
+
+#[test]
+fn trivial_asserts() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn never_fails() -> u32 {
+            if false {
+                panic!("unreachable")
+            }
+            0
+        }
+        fn always_fails() -> u32 {
+            if true {
+                panic!("always")
+            }
+            0
+        }
+        "#,
+    )?;
+    // Returns whether the body contains asserts and panics.
+    let asserts_and_panics = |fun_name: &str| {
+        let fun = fun_by_name(&crate_data, fun_name);
+        let mut asserts = false;
+        let mut panics = vec![];
+        llbc_body(fun)
+            .body
+            .dyn_visit_in_body(|st: &Statement| match &st.content {
+                RawStatement::Assert(_) => asserts = true,
+                RawStatement::Abort(AbortKind::Panic { name, message, .. }) => {
+                    panics.push((repr_name(&crate_data, name), message.clone()))
+                }
+                _ => {}
+            });
+        (asserts, panics)
+    };
+    assert_eq!(
+        asserts_and_panics("test_crate::never_fails"),
+        (false, vec![])
+    );
+    // The original panic is kept.
+    assert_eq!(
+        asserts_and_panics("test_crate::always_fails"),
+        (
+            false,
+            vec![(
+                "core::panicking::panic_fmt".to_owned(),
+                Some("always".to_owned())
+            )]
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn boxed_closure_call() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn apply(f: Box<dyn Fn(u32) -> u32>) -> u32 {
+            f(1)
+        }
+        fn apply_mut(mut f: Box<dyn FnMut(u32) -> u32>) -> u32 {
+            f(1)
+        }
+        "#,
+    )?;
+    // Returns the trait and method called dynamically by the function.
+    let dyn_call = |fun_name: &str| {
+        let function = fun_by_name(&crate_data, fun_name);
+        let mut dyn_calls = vec![];
+        llbc_body(function).body.dyn_visit_in_body(|call: &Call| {
+            if let FnOperand::Dynamic(FnPtr {
+                func: FunIdOrTraitMethodRef::Trait(trait_ref, method_name, _),
+                ..
+            }) = &call.func
+            {
+                assert!(matches!(trait_ref.kind, TraitRefKind::Dyn(_)));
+                // The receiver is a reference to the trait object, not to the box.
+                let receiver_ty = call.args[0].as_place().unwrap().ty();
+                let TyKind::Ref(_, pointee, _) = receiver_ty.kind() else {
+                    panic!()
+                };
+                assert!(matches!(pointee.kind(), TyKind::DynTrait(_)));
+                dyn_calls.push((
+                    trait_name(&crate_data, trait_ref.trait_decl_ref.skip_binder.trait_id)
+                        .to_string(),
+                    method_name.0.clone(),
+                ));
+            }
+        });
+        dyn_calls
+    };
+    assert_eq!(
+        dyn_call("test_crate::apply"),
+        vec![("Fn".to_string(), "call".to_string())]
+    );
+    assert_eq!(
+        dyn_call("test_crate::apply_mut"),
+        vec![("FnMut".to_string(), "call_mut".to_string())]
+    );
+    Ok(())
+}
+
+#[test]
+fn fold_size_of() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        fn size_u32() -> usize {
+            std::mem::size_of::<u32>()
+        }
+        fn size_generic<T>() -> usize {
+            std::mem::size_of::<T>()
+        }
+        fn align_bytes() -> usize {
+            std::mem::align_of::<[u8; 3]>()
+        }
+        fn align_u16() -> usize {
+            std::mem::align_of::<u16>()
+        }
+        "#,
+    )?;
+    // Returns whether the function calls a function, and the `usize` constants it uses.
+    let calls_and_constants = |fun_name: &str| {
+        let function = fun_by_name(&crate_data, fun_name);
+        let body = &llbc_body(function).body;
+        let mut calls = false;
+        body.dyn_visit_in_body(|_: &Call| calls = true);
+        let mut constants = vec![];
+        body.dyn_visit_in_body(|rvalue: &Rvalue| {
+            if let Rvalue::Use(Operand::Const(ConstantExpr {
+                value: RawConstantExpr::Literal(Literal::Scalar(ScalarValue::Usize(n))),
+                ..
+            })) = rvalue
+            {
+                constants.push(*n);
+            }
+        });
+        (calls, constants)
+    };
+    assert_eq!(
+        calls_and_constants("test_crate::size_u32"),
+        (false, vec![4])
+    );
+    // The size of a type variable is unknown: we keep the call.
+    assert_eq!(
+        calls_and_constants("test_crate::size_generic"),
+        (true, vec![])
+    );
+    assert_eq!(
+        calls_and_constants("test_crate::align_bytes"),
+        (false, vec![1])
+    );
+    // The alignment of `u16` depends on the target.
+    assert_eq!(calls_and_constants("test_crate::align_u16"), (true, vec![]));
+    Ok(())
+}
+
+#[test]
+fn include_std() -> anyhow::Result<()> {
+    let code = r#"
+        fn len(v: &Vec<u32>) -> usize {
+            v.len()
+        }
+    "#;
+    // Whether we translated the body of `Vec::len`.
+    let vec_len_is_transparent = |crate_data: &TranslatedCrate| {
+        fun_by_name(crate_data, "alloc::vec::<inherent impl>::len")
+            .body
+            .is_ok()
+    };
+    let crate_data = translate(code)?;
+    assert!(!vec_len_is_transparent(&crate_data));
+    let crate_data = util::translate_rust_text_with_args(code, &["--include-std"])?;
+    assert!(vec_len_is_transparent(&crate_data));
+    Ok(())
+}
+
+#[test]
+fn include_std_rc() -> anyhow::Result<()> {
+    let crate_data = util::translate_rust_text_with_args(
+        r#"
+        use std::rc::Rc;
+        fn get(x: &Rc<u32>) -> u32 {
+            **x
+        }
+        "#,
+        &["--include-std"],
+    )?;
+    // The implementation of `Rc` accesses its fields, which we can't translate since `Rc` is a
+    // builtin type: its bodies are skipped.
+    let deref = fun_by_name(&crate_data, "alloc::rc::<impl Deref for ??>::deref");
+    assert!(deref.body.is_err());
+    Ok(())
+}
+
+#[test]
+fn array_impl_with_const_generic_len() -> anyhow::Result<()> {
+    let crate_data = translate(
+        r#"
+        trait Size {
+            fn size(&self) -> usize;
+        }
+        impl<const N: usize> Size for [u8; N] {
+            fn size(&self) -> usize {
+                N
+            }
+        }
+        fn size3(x: [u8; 3]) -> usize {
+            x.size()
+        }
+        "#,
+    )?;
+    // The impl is registered, with the length of the array as a const generic variable.
+    let timpl = crate_data.trait_impls.iter().next().unwrap();
+    let self_ty = &timpl.impl_trait.generics.types[0];
+    let TyKind::Adt(TypeId::Builtin(BuiltinTy::Array), array_args) = self_ty.kind() else {
+        panic!("unexpected self type: {self_ty:?}")
+    };
+    assert!(matches!(array_args.const_generics[0], ConstGeneric::Var(_)));
+
+    // The call resolves to that impl, with the length instantiated.
+    let size3 = fun_by_name(&crate_data, "test_crate::size3");
+    let mut resolved = false;
+    llbc_body(size3).body.dyn_visit_in_body(|fn_ptr: &FnPtr| {
+        if let FunIdOrTraitMethodRef::Trait(trait_ref, method_name, _) = &fn_ptr.func {
+            assert_eq!(method_name.0, "size");
+            let TraitRefKind::TraitImpl(impl_id, impl_args) = &trait_ref.kind else {
+                panic!("unresolved trait ref")
+            };
+            assert_eq!(*impl_id, timpl.def_id);
+            let ConstGeneric::Value(Literal::Scalar(len)) = &impl_args.const_generics[0] else {
+                panic!()
+            };
+            assert_eq!(len.to_bits(), 3);
+            resolved = true;
+        }
+    });
+    assert!(resolved);
+    Ok(())
+}
+
+#[test]
+fn merge_crates() -> anyhow::Result<()> {
+    let defining = translate(
+        r#"
+        pub fn helper(x: u32) -> u32 {
+            x + 1
+        }
+        "#,
+    )?;
+    let using = translate(
+        r#"
+        extern "Rust" {
+            fn helper(x: u32) -> u32;
+        }
+        pub fn main() -> u32 {
+            unsafe { helper(1) }
+        }
+        "#,
+    )?;
+    let merged = TranslatedCrate::merge(vec![using, defining]).unwrap();
+
+    // The declaration and the definition of `helper` are identified.
+    let helpers = merged
+        .fun_decls
+        .iter()
+        .filter(|f| repr_name(&merged, &f.item_meta.name) == "test_crate::helper")
+        .collect_vec();
+    assert_eq!(helpers.len(), 1);
+    let helper = helpers[0];
+    assert!(helper.body.is_ok());
+
+    // The call in `main` refers to the definition.
+    let main = fun_by_name(&merged, "test_crate::main");
+    let mut callees = vec![];
+    llbc_body(main).body.dyn_visit_in_body(|fn_ptr: &FnPtr| {
+        if let FunIdOrTraitMethodRef::Fun(FunId::Regular(id)) = &fn_ptr.func {
+            callees.push(*id);
+        }
+    });
+    assert_eq!(callees, vec![helper.def_id]);
+    assert_eq!(
+        merged.item_name(helper.def_id),
+        Some(&helper.item_meta.name)
+    );
+    Ok(())
+}
+
+#[test]
+fn merge_dependent_crates() -> anyhow::Result<()> {
+    let dependency = r#"
+        pub fn helper(x: u32) -> Vec<u32> {
+            let mut v = Vec::new();
+            v.push(x);
+            v
+        }
+    "#;
+    // Build the metadata of the dependency so that the other crate can use it.
+    let tmp_dir = tempfile::TempDir::new()?;
+    let dependency_path = tmp_dir.path().join("other.rs");
+    std::fs::write(&dependency_path, dependency)?;
+    use assert_cmd::prelude::OutputAssertExt;
+    std::process::Command::new("rustc")
+        .args(["--edition=2021", "--crate-type=rlib", "--emit=metadata"])
+        .arg("--out-dir")
+        .arg(tmp_dir.path())
+        .arg(&dependency_path)
+        .output()?
+        .assert()
+        .try_success()?;
+    let metadata_path = tmp_dir.path().join("libother.rmeta");
+
+    let other =
+        util::translate_rust_text_with_args(dependency, &["--rustc-flag=--crate-name=other"])?;
+    let main = util::translate_rust_text_with_args(
+        r#"
+        pub fn main() -> Vec<u32> {
+            let mut v = other::helper(1);
+            v.push(2);
+            v
+        }
+        "#,
+        &[&format!(
+            "--rustc-flag=--extern=other={}",
+            metadata_path.display()
+        )],
+    )?;
+    let merged = TranslatedCrate::merge(vec![main, other]).unwrap();
+
+    let funs_named = |name: &str| {
+        merged
+            .fun_decls
+            .iter()
+            .filter(|f| repr_name(&merged, &f.item_meta.name) == name)
+            .collect_vec()
+    };
+    // The use of `other::helper` resolves to its definition.
+    let [helper] = funs_named("other::helper")[..] else {
+        panic!()
+    };
+    assert!(helper.body.is_ok());
+    let [main] = funs_named("test_crate::main")[..] else {
+        panic!()
+    };
+    let mut callees = vec![];
+    llbc_body(main).body.dyn_visit_in_body(|fn_ptr: &FnPtr| {
+        if let FunIdOrTraitMethodRef::Fun(FunId::Regular(id)) = &fn_ptr.func {
+            callees.push(*id);
+        }
+    });
+    assert!(callees.contains(&helper.def_id));
+    // The items from the standard library that both crates use are kept once.
+    assert_eq!(funs_named("alloc::vec::<inherent impl>::new").len(), 1);
+    assert_eq!(funs_named("alloc::vec::<inherent impl>::push").len(), 1);
+    Ok(())
+}
@@ -12,8 +12,12 @@ use std::fmt::Display;
 use std::path::Path;
 use std::{fs::File, io::BufReader, process::Command};
 
-use charon_lib::ast::TranslatedCrate;
-use charon_lib::{export::CrateData, logger};
+use charon_lib::ast::*;
+use charon_lib::formatter::IntoFormatter;
+use charon_lib::pretty::FmtWithCtx;
+use charon_lib::{export::CrateData, llbc_ast, logger};
+use itertools::Itertools;
+use serde::Deserialize;
 
 #[derive(Clone, Copy)]
 pub enum Action {
@@ -61,13 +65,57 @@ fn expect_file_contents(path: &Path, actual: snapbox::Data) -> snapbox::assert::
 
 /// Given a string that contains rust code, this calls charon on it and returns the result.
 pub fn translate_rust_text(code: impl Display) -> anyhow::Result<TranslatedCrate> {
+    translate_rust_text_with_args(code, &[])
+}
+
+/// Like `translate_rust_text`, but passes the extra `args` to charon.
+pub fn translate_rust_text_with_args(
+    code: impl Display,
+    args: &[&str],
+) -> anyhow::Result<TranslatedCrate> {
+    Ok(translate_rust_text_to_crate_data(code, args)?.translated)
+}
+
+/// Like `translate_rust_text_with_args`, but returns the whole exported data, including the
+/// version metadata.
+pub fn translate_rust_text_to_crate_data(
+    code: impl Display,
+    args: &[&str],
+) -> anyhow::Result<CrateData> {
+    let tmp_dir = tempfile::TempDir::new()?;
+    let output_path = tmp_dir.path().join("test_crate.llbc");
+    let output_path_str = output_path.to_str().unwrap();
+    let args = [&["--dest-file", output_path_str][..], args].concat();
+    run_charon_on_rust_text(tmp_dir.path(), code, &args)?
+        .assert()
+        .try_success()?;
+
+    // Extract the computed crate data.
+    let crate_data: CrateData = {
+        let file = File::open(output_path)?;
+        let reader = BufReader::new(file);
+        let mut deserializer = serde_json::Deserializer::from_reader(reader);
+        // Deeply nested code produces deeply nested json.
+        deserializer.disable_recursion_limit();
+        let deserializer = serde_stacker::Deserializer::new(&mut deserializer);
+        CrateData::deserialize(deserializer)?
+    };
+
+    Ok(crate_data)
+}
+
+/// Writes the rust code to a file in `dir` and calls charon on it with the extra `args`.
+fn run_charon_on_rust_text(
+    dir: &Path,
+    code: impl Display,
+    args: &[&str],
+) -> anyhow::Result<std::process::Output> {
     // Initialize the logger
     logger::initialize_logger();
 
     // Write the code to a temporary file.
     use std::io::Write;
-    let tmp_dir = tempfile::TempDir::new()?;
-    let input_path = tmp_dir.path().join("test_crate.rs");
+    let input_path = dir.join("test_crate.rs");
     {
         let mut tmp_file = File::create(&input_path)?;
         write!(tmp_file, "{}", code)?;
@@ -75,24 +123,84 @@ pub fn translate_rust_text(code: impl Display) -> anyhow::Result<TranslatedCrate
     }
 
     // Call charon
-    let output_path = tmp_dir.path().join("test_crate.llbc");
-    Command::cargo_bin("charon")?
+    let output = Command::cargo_bin("charon")?
         .arg("--no-cargo")
         .arg("--rustc-flag=--edition=2021")
         .arg("--rustc-flag=--crate-type=rlib")
         .arg("--input")
         .arg(input_path)
-        .arg("--dest-file")
-        .arg(&output_path)
-        .assert()
-        .try_success()?;
+        .args(args)
+        .output()?;
+    Ok(output)
+}
 
-    // Extract the computed crate data.
-    let crate_data: CrateData = {
-        let file = File::open(output_path)?;
-        let reader = BufReader::new(file);
-        serde_json::from_reader(reader)?
+/// Calls charon on the rust code with the extra `args`, without serializing the result, and
+/// returns what charon printed on stderr. Doesn't fail if charon reports errors.
+pub fn translate_rust_text_stderr(code: impl Display, args: &[&str]) -> anyhow::Result<String> {
+    let tmp_dir = tempfile::TempDir::new()?;
+    let args = [&["--no-serialize"][..], args].concat();
+    let output = run_charon_on_rust_text(tmp_dir.path(), code, &args)?;
+    Ok(String::from_utf8(output.stderr)?)
+}
+
+/// Calls charon on the rust code with the extra `args`, without serializing the result, and
+/// returns what charon printed on stdout. Doesn't fail if charon reports errors.
+pub fn translate_rust_text_stdout(code: impl Display, args: &[&str]) -> anyhow::Result<String> {
+    let tmp_dir = tempfile::TempDir::new()?;
+    let args = [&["--no-serialize"][..], args].concat();
+    let output = run_charon_on_rust_text(tmp_dir.path(), code, &args)?;
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// `Name` is a complex datastructure; to inspect it we serialize it a little bit.
+pub fn repr_name(crate_data: &TranslatedCrate, n: &Name) -> String {
+    n.name
+        .iter()
+        .map(|path_elem| match path_elem {
+            PathElem::Ident(i, _) => i.clone(),
+            PathElem::Impl(elem, _) => match elem {
+                ImplElem::Trait(impl_id) => match crate_data.trait_impls.get(*impl_id) {
+                    None => format!("<trait impl#{impl_id}>"),
+                    Some(timpl) => {
+                        let trait_name = trait_name(crate_data, timpl.impl_trait.trait_id);
+                        format!("<impl {trait_name} for ??>")
+                    }
+                },
+                ImplElem::Ty(..) => "<inherent impl>".to_string(),
+            },
+            PathElem::Monomorphized(args) => args.fmt_with_ctx(&crate_data.into_fmt()),
+        })
+        .join("::")
+}
+
+/// The last component of the name of the trait.
+pub fn trait_name(crate_data: &TranslatedCrate, trait_id: TraitDeclId) -> &str {
+    let tr = &crate_data.trait_decls[trait_id];
+    let PathElem::Ident(trait_name, _) = tr.item_meta.name.name.last().unwrap() else {
+        panic!()
     };
+    trait_name
+}
+
+/// Find the function with the given name, as printed by [repr_name].
+pub fn fun_by_name<'a>(crate_data: &'a TranslatedCrate, name: &str) -> &'a FunDecl {
+    crate_data
+        .fun_decls
+        .iter()
+        .find(|f| repr_name(crate_data, &f.item_meta.name) == name)
+        .unwrap_or_else(|| panic!("no function named `{name}`"))
+}
+
+/// Find the type with the given name, as printed by [repr_name].
+pub fn type_by_name<'a>(crate_data: &'a TranslatedCrate, name: &str) -> &'a TypeDecl {
+    crate_data
+        .type_decls
+        .iter()
+        .find(|ty| repr_name(crate_data, &ty.item_meta.name) == name)
+        .unwrap_or_else(|| panic!("no type named `{name}`"))
+}
 
-    Ok(crate_data.translated)
+/// The body of the function, which must have been translated and structured.
+pub fn llbc_body(fun: &FunDecl) -> &llbc_ast::ExprBody {
+    fun.body.as_ref().unwrap().as_structured().unwrap()
 }